@@ -0,0 +1,754 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    language::{
+        expression::{BinaryOperator, Expression},
+        statement::Statement,
+        value::Value,
+    },
+    transition_system::{
+        simulation::RandomWalk,
+        state::State,
+        trace::{Lasso, Trace},
+        transition::Transition,
+        transition_system::TransitionSystem,
+    },
+};
+
+use super::{automaton::Automaton, channel::Channel, edge::Edge, location::Location};
+
+/// How the variables of an automaton flow into each other through the edges.
+#[derive(Debug, Clone)]
+pub struct VariableDependencies {
+    readers: HashMap<String, HashSet<Edge>>,
+    writers: HashMap<String, HashSet<Edge>>,
+    /// The variables whose values each variable flows into, directly or not.
+    affects: HashMap<String, HashSet<String>>,
+    /// The variables read by guards and invariants.
+    observed: HashSet<String>,
+}
+
+impl VariableDependencies {
+    pub fn new(automaton: &Automaton) -> Self {
+        let mut readers: HashMap<String, HashSet<Edge>> = HashMap::new();
+        let mut writers: HashMap<String, HashSet<Edge>> = HashMap::new();
+        let mut direct: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut observed: HashSet<String> = HashSet::new();
+
+        for edge in automaton.get_edges() {
+            let guard = edge.guard.node.identifiers();
+            observed.extend(guard.iter().cloned());
+            let mut read = guard;
+            if let Some(update) = &edge.update.node {
+                read.extend(update.read_identifiers());
+                for written in update.assigned_identifiers() {
+                    writers.entry(written).or_default().insert(edge.clone());
+                }
+                let mut flows: Vec<(String, Vec<String>)> = Vec::new();
+                Self::flows(update, &[], &mut flows);
+                for (target, sources) in flows {
+                    for source in sources {
+                        direct.entry(source).or_default().insert(target.clone());
+                    }
+                }
+            }
+            for identifier in read {
+                readers.entry(identifier).or_default().insert(edge.clone());
+            }
+        }
+        for location in automaton.get_locations() {
+            if let Location::Normal { invariant, .. }
+            | Location::Initial { invariant, .. }
+            | Location::Conjunction { invariant, .. } = location
+            {
+                observed.extend(invariant.node.identifiers());
+            }
+        }
+
+        // The transitive closure of the direct flows
+        let mut affects: HashMap<String, HashSet<String>> = HashMap::new();
+        for variable in direct.keys() {
+            let mut reached: HashSet<String> = HashSet::new();
+            let mut worklist: Vec<&String> = vec![variable];
+            while let Some(current) = worklist.pop() {
+                for next in direct.get(current).into_iter().flatten() {
+                    if reached.insert(next.clone()) {
+                        worklist.push(next);
+                    }
+                }
+            }
+            affects.insert(variable.clone(), reached);
+        }
+
+        VariableDependencies {
+            readers,
+            writers,
+            affects,
+            observed,
+        }
+    }
+
+    /// The assignment targets of the statement along with the variables their
+    /// values depend on, including those of the enclosing conditions.
+    pub(crate) fn flows(
+        statement: &Statement,
+        control: &[String],
+        flows: &mut Vec<(String, Vec<String>)>,
+    ) {
+        match statement {
+            Statement::Assigment { identifier, value } => {
+                let mut sources = value.identifiers();
+                sources.extend(control.iter().cloned());
+                for target in identifier.identifiers() {
+                    flows.push((target, sources.clone()));
+                }
+            }
+            Statement::ClockReset { clock } => flows.push((clock.clone(), control.to_vec())),
+            Statement::Sequence(statements) => {
+                for statement in statements {
+                    Self::flows(statement, control, flows);
+                }
+            }
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let mut control = control.to_vec();
+                control.extend(condition.identifiers());
+                Self::flows(then, &control, flows);
+                if let Some(otherwise) = otherwise {
+                    Self::flows(otherwise, &control, flows);
+                }
+            }
+        }
+    }
+
+    /// The edges whose guard or update reads the variable.
+    pub fn readers(&self, variable: &str) -> HashSet<&Edge> {
+        self.readers.get(variable).into_iter().flatten().collect()
+    }
+
+    /// The edges whose update assigns to the variable.
+    pub fn writers(&self, variable: &str) -> HashSet<&Edge> {
+        self.writers.get(variable).into_iter().flatten().collect()
+    }
+
+    /// The variables whose values may depend on the value of the variable.
+    pub fn affects(&self, variable: &str) -> HashSet<&String> {
+        self.affects.get(variable).into_iter().flatten().collect()
+    }
+
+    /// The variables which may influence the criterion variables or any guard
+    /// or invariant, including those variables themselves.
+    pub fn influencing(&self, criterion: &HashSet<String>) -> HashSet<String> {
+        self.affecting(&criterion.union(&self.observed).cloned().collect())
+    }
+
+    /// The criterion variables along with the variables which may affect them.
+    pub fn affecting(&self, criterion: &HashSet<String>) -> HashSet<String> {
+        let mut affecting = criterion.clone();
+        for (variable, affected) in self.affects.iter() {
+            if affected.iter().any(|target| criterion.contains(target)) {
+                affecting.insert(variable.clone());
+            }
+        }
+        affecting
+    }
+}
+
+/// The conjunction of the top-level conjuncts of the expression which only read
+/// variables in `keep`, which is true if there are none.
+pub(crate) fn retain_conjuncts(expression: &Expression, keep: &HashSet<String>) -> Expression {
+    let mut conjuncts: Vec<&Expression> = Vec::new();
+    let mut worklist: Vec<&Expression> = vec![expression];
+    while let Some(current) = worklist.pop() {
+        match current {
+            Expression::Binary(lhs, BinaryOperator::LogicalAnd, rhs) => {
+                worklist.push(rhs);
+                worklist.push(lhs);
+            }
+            Expression::Parenthesized(inner) => match inner.as_ref() {
+                Expression::Binary(_, BinaryOperator::LogicalAnd, _) => worklist.push(inner),
+                _ => conjuncts.push(current),
+            },
+            _ => conjuncts.push(current),
+        }
+    }
+
+    conjuncts
+        .into_iter()
+        .filter(|conjunct| {
+            conjunct
+                .identifiers()
+                .iter()
+                .all(|identifier| keep.contains(identifier))
+        })
+        .cloned()
+        .reduce(|lhs, rhs| Expression::new_logical_and(&lhs, &rhs))
+        .unwrap_or_else(|| Expression::new_boolean(&true))
+}
+
+/// The statement without the assignments to variables outside `keep`, or none
+/// if nothing is left of it.
+pub(crate) fn retain_assignments(
+    statement: &Statement,
+    keep: &HashSet<String>,
+) -> Option<Statement> {
+    match statement {
+        Statement::Assigment { identifier, .. } => identifier
+            .identifiers()
+            .iter()
+            .all(|target| keep.contains(target))
+            .then(|| statement.clone()),
+        Statement::ClockReset { clock } => keep.contains(clock).then(|| statement.clone()),
+        Statement::Sequence(statements) => {
+            let retained: Vec<Statement> = statements
+                .iter()
+                .filter_map(|statement| retain_assignments(statement, keep))
+                .collect();
+            (!retained.is_empty()).then(|| Statement::new_sequence(&retained))
+        }
+        Statement::If {
+            condition,
+            then,
+            otherwise,
+        } => {
+            let then = retain_assignments(then, keep);
+            let otherwise = otherwise
+                .as_ref()
+                .and_then(|otherwise| retain_assignments(otherwise, keep));
+            match (then, otherwise) {
+                (None, None) => None,
+                (then, otherwise) => Some(Statement::new_if(
+                    condition,
+                    &then.unwrap_or_else(|| Statement::new_sequence(&[])),
+                    otherwise.as_ref(),
+                )),
+            }
+        }
+    }
+}
+
+/// The strongly connected components of the states reachable by the actions,
+/// found by Tarjan's algorithm. Every component is listed before the
+/// components reachable from it.
+pub fn strongly_connected_components<TS: TransitionSystem>(
+    ts: &TS,
+    actions: &HashSet<Channel>,
+) -> Vec<Vec<State>> {
+    let mut components: Vec<Vec<State>> = Vec::new();
+    let mut indices: HashMap<State, usize> = HashMap::new();
+    let mut lowlinks: HashMap<State, usize> = HashMap::new();
+    let mut stack: Vec<State> = Vec::new();
+    let mut on_stack: HashSet<State> = HashSet::new();
+    // The recursion of the algorithm as frames of a state and its successors not yet tried
+    let mut calls: Vec<(State, Vec<State>)> = Vec::new();
+    let mut next = Some(ts.get_initial_state());
+
+    loop {
+        if let Some(state) = next.take() {
+            indices.insert(state.clone(), indices.len());
+            lowlinks.insert(state.clone(), indices[&state]);
+            stack.push(state.clone());
+            on_stack.insert(state.clone());
+            let successors = ts
+                .timed_transitions(&state, actions)
+                .into_iter()
+                .map(|transition| transition.target)
+                .collect();
+            calls.push((state, successors));
+        }
+
+        let Some((state, successors)) = calls.last_mut() else {
+            break;
+        };
+        match successors.pop() {
+            Some(successor) if !indices.contains_key(&successor) => next = Some(successor),
+            Some(successor) => {
+                if on_stack.contains(&successor) {
+                    let lowlink = lowlinks[state].min(indices[&successor]);
+                    lowlinks.insert(state.clone(), lowlink);
+                }
+            }
+            None => {
+                let (state, _) = calls.pop().unwrap();
+                if lowlinks[&state] == indices[&state] {
+                    let mut component: Vec<State> = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack.remove(&member);
+                        let is_root = member == state;
+                        component.push(member);
+                        if is_root {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+                if let Some((parent, _)) = calls.last() {
+                    let lowlink = lowlinks[parent].min(lowlinks[&state]);
+                    lowlinks.insert(parent.clone(), lowlink);
+                }
+            }
+        }
+    }
+
+    // Tarjan's algorithm finds the components reachable from a component before it
+    components.reverse();
+    components
+}
+
+/// Finds a reachable cycle as a lasso, that is a stem from the initial state
+/// to a state and a loop of at least one transition back to that state.
+pub fn find_cycle<TS: TransitionSystem>(ts: &TS, actions: &HashSet<Channel>) -> Option<Lasso> {
+    let initial = ts.get_initial_state();
+    let mut path: Vec<Transition> = Vec::new();
+    let mut on_path: HashSet<State> = HashSet::from([initial.clone()]);
+    // States fully explored without finding a cycle cannot reach one
+    let mut finished: HashSet<State> = HashSet::new();
+    let mut frames: Vec<Vec<Transition>> = vec![ts.timed_transitions(&initial, actions)];
+
+    while let Some(frame) = frames.last_mut() {
+        match frame.pop() {
+            Some(transition) if on_path.contains(&transition.target) => {
+                // The loop starts after the transitions leading to the revisited state
+                let start = match path
+                    .iter()
+                    .position(|step| step.source == transition.target)
+                {
+                    Some(start) => start,
+                    None => path.len(),
+                };
+                let mut stem = Trace::new(&initial);
+                for step in path[..start].iter() {
+                    stem.push(step).expect(
+                        "every transition of the path leaves the target of the one before it",
+                    );
+                }
+                let mut cycle = path.split_off(start);
+                cycle.push(transition);
+                return Some(Lasso { stem, cycle });
+            }
+            Some(transition) => {
+                if !finished.contains(&transition.target) {
+                    on_path.insert(transition.target.clone());
+                    frames.push(ts.timed_transitions(&transition.target, actions));
+                    path.push(transition);
+                }
+            }
+            None => {
+                frames.pop();
+                let state = match path.pop() {
+                    Some(transition) => transition.target,
+                    None => initial.clone(),
+                };
+                on_path.remove(&state);
+                finished.insert(state);
+            }
+        }
+    }
+
+    None
+}
+
+/// The number of random walks `estimate_state_space` samples.
+const ESTIMATE_WALKS: usize = 32;
+/// The number of steps of each walk sampled by `estimate_state_space`.
+const ESTIMATE_MAX_STEPS: usize = 256;
+
+/// How many states an automaton may have, estimated without exploring it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateSpaceEstimate {
+    /// The number of locations times the number of boolean environments, or
+    /// none if a variable is not a boolean or it does not fit in a `u128`.
+    pub static_bound: Option<u128>,
+    /// The distinct states visited by the random walks, which is a lower bound.
+    pub sampled_states: usize,
+    pub walks: usize,
+    pub max_steps: usize,
+}
+
+impl StateSpaceEstimate {
+    /// Whether exploring the state space visits at most the budget of states.
+    /// Only the static bound can show this, so it is false if the bound is
+    /// exceeded even though the reachable states may be fewer.
+    pub fn is_explorable_within(&self, budget_states: usize) -> bool {
+        self.static_bound
+            .is_some_and(|bound| bound <= budget_states as u128)
+    }
+}
+
+/// The number of locations times the number of values every variable may
+/// take, or none if the bound does not fit in a `u128` or a variable holds an
+/// identifier, whose values are not known. A boolean takes two values and a
+/// clock any time up to one beyond the ceiling, where delays stop advancing
+/// it, or up to a larger initial or assigned time.
+pub fn static_state_space_bound(automaton: &Automaton) -> Option<u128> {
+    let environment = automaton.get_initial_environment();
+    let initial_times =
+        environment
+            .sorted_entries()
+            .into_iter()
+            .filter_map(|(_, value)| match value {
+                Value::Clock(time) => Some(*time),
+                _ => None,
+            });
+    let assigned_times = automaton
+        .get_edges()
+        .iter()
+        .filter_map(|edge| edge.update.node.as_ref())
+        .flat_map(assigned_clock_constants);
+    let latest = initial_times
+        .chain(assigned_times)
+        .fold(automaton.clock_ceiling().checked_add(1)?, u32::max);
+    let times = u128::from(latest) + 1;
+
+    let mut environments: u128 = 1;
+    for (_, value) in environment.sorted_entries() {
+        let values = match value {
+            Value::Bool(_) => 2,
+            Value::Clock(_) => times,
+            Value::Identifier(_) => return None,
+        };
+        environments = environments.checked_mul(values)?;
+    }
+    environments.checked_mul(automaton.get_locations().len() as u128)
+}
+
+/// The times the statement assigns to clocks.
+fn assigned_clock_constants(statement: &Statement) -> Vec<u32> {
+    match statement {
+        Statement::Assigment { value, .. } => value.clock_constants(),
+        Statement::Sequence(statements) => statements
+            .iter()
+            .flat_map(assigned_clock_constants)
+            .collect(),
+        Statement::If {
+            then, otherwise, ..
+        } => {
+            let mut constants = assigned_clock_constants(then);
+            if let Some(otherwise) = otherwise {
+                constants.extend(assigned_clock_constants(otherwise));
+            }
+            constants
+        }
+        Statement::ClockReset { .. } => Vec::new(),
+    }
+}
+
+/// Estimates the state space by its static bound and by sampling random walks
+/// with a fixed seed.
+pub fn estimate_state_space(automaton: &Automaton) -> StateSpaceEstimate {
+    estimate_state_space_with(automaton, ESTIMATE_WALKS, ESTIMATE_MAX_STEPS, 0)
+}
+
+/// Estimates the state space as `estimate_state_space`, where the walk with
+/// index `i` uses the seed plus `i` such that the same seed yields the same
+/// estimate.
+pub fn estimate_state_space_with(
+    automaton: &Automaton,
+    walks: usize,
+    max_steps: usize,
+    seed: u64,
+) -> StateSpaceEstimate {
+    let mut visited: HashSet<State> = HashSet::from([automaton.get_initial_state()]);
+    for walk in 0..walks {
+        let random_walk = RandomWalk::new(
+            automaton,
+            automaton.get_actions(),
+            seed.wrapping_add(walk as u64),
+        )
+        .with_max_steps(max_steps);
+        visited.extend(random_walk.map(|transition| transition.target));
+    }
+
+    StateSpaceEstimate {
+        static_bound: static_state_space_bound(automaton),
+        sampled_states: visited.len(),
+        walks,
+        max_steps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, fixtures::new_edge, guard::Guard,
+            invariant::Invariant, location::Location, update::Update,
+        },
+        language::{expression::Expression, statement::Statement, value::Value},
+        transition_system::transition_system::TransitionSystem,
+    };
+
+    use super::{
+        estimate_state_space, estimate_state_space_with, find_cycle, static_state_space_bound,
+        strongly_connected_components, VariableDependencies,
+    };
+
+    fn assign(target: &str, source: &str) -> Statement {
+        Statement::new_assignment(
+            &Expression::new_identifier(target),
+            &Expression::new_identifier(source),
+        )
+    }
+
+    /// a -step!-> b [y := x; log := y; trace := log] -back?-> a guarded by y
+    fn new_fixture() -> (Automaton, Edge, Edge) {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let step = Edge::new(
+            &a,
+            &Channel::new_output("step"),
+            &Guard::new_true(),
+            &Update::new(&Statement::new_sequence(&[
+                assign("y", "x"),
+                assign("log", "y"),
+                assign("trace", "log"),
+                Statement::new_simple_assignment("x", &Value::new_true()),
+            ])),
+            &b,
+        );
+        let back = Edge::new(
+            &b,
+            &Channel::new_input("back"),
+            &Guard::new(&Expression::new_identifier("y")),
+            &Update::empty(),
+            &a,
+        );
+        let automaton = Automaton::new(
+            "fixture",
+            &HashSet::from([step.clone(), back.clone()]),
+            None,
+        )
+        .ok()
+        .unwrap();
+        (automaton, step, back)
+    }
+
+    #[test]
+    fn analysis_variable_dependencies() {
+        let (automaton, step, back) = new_fixture();
+
+        let dependencies = VariableDependencies::new(&automaton);
+
+        assert_eq!(dependencies.readers("y"), HashSet::from([&step, &back]));
+        assert_eq!(dependencies.writers("y"), HashSet::from([&step]));
+        assert!(dependencies.readers("trace").is_empty());
+        let x_affects: HashSet<&str> = dependencies
+            .affects("x")
+            .into_iter()
+            .map(|variable| variable.as_str())
+            .collect();
+        assert_eq!(x_affects, HashSet::from(["y", "log", "trace"]));
+        assert!(dependencies.affects("trace").is_empty());
+    }
+
+    #[test]
+    fn analysis_slice_removes_uninfluential_updates() {
+        let (automaton, _, _) = new_fixture();
+
+        let sliced = automaton.slice(&HashSet::new()).ok().unwrap();
+        let sliced_to_log = automaton
+            .slice(&HashSet::from([String::from("log")]))
+            .ok()
+            .unwrap();
+
+        let assigned = |automaton: &Automaton| -> HashSet<String> {
+            automaton
+                .get_edges()
+                .iter()
+                .filter_map(|edge| edge.update.node.as_ref())
+                .flat_map(|update| update.assigned_identifiers())
+                .collect()
+        };
+        assert_eq!(
+            assigned(&sliced),
+            HashSet::from([String::from("x"), String::from("y")])
+        );
+        assert_eq!(
+            assigned(&sliced_to_log),
+            HashSet::from([String::from("x"), String::from("y"), String::from("log")])
+        );
+        assert_eq!(sliced.words_up_to(4), automaton.words_up_to(4));
+        assert_eq!(
+            sliced.reachable_locations(sliced.get_actions()),
+            automaton.reachable_locations(automaton.get_actions())
+        );
+    }
+
+    #[test]
+    fn analysis_acyclic_has_no_cycle() {
+        let invariant = &Invariant::new_true();
+        let out = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let edges = HashSet::from([
+            new_edge(&a, &out, &b),
+            new_edge(&a, &out, &c),
+            new_edge(&b, &out, &c),
+        ]);
+        let automaton = Automaton::new("acyclic", &edges, None).ok().unwrap();
+        let actions = automaton.get_actions();
+
+        let cycle = find_cycle(&automaton, actions);
+        let components = strongly_connected_components(&automaton, actions);
+
+        assert_eq!(cycle, None);
+        let order: Vec<Vec<Location>> = components
+            .iter()
+            .map(|component| {
+                component
+                    .iter()
+                    .map(|state| state.location.clone())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(order, vec![vec![a], vec![b], vec![c]]);
+    }
+
+    #[test]
+    fn analysis_two_state_loop() {
+        let invariant = &Invariant::new_true();
+        let out = Channel::new_output("out");
+        let input = Channel::new_input("in");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let edges = HashSet::from([
+            new_edge(&a, &out, &b),
+            new_edge(&b, &out, &c),
+            new_edge(&c, &input, &b),
+        ]);
+        let automaton = Automaton::new("lasso", &edges, None).ok().unwrap();
+        let actions = automaton.get_actions();
+
+        let lasso = find_cycle(&automaton, actions).unwrap();
+        let components = strongly_connected_components(&automaton, actions);
+
+        assert_eq!(lasso.stem.len(), 1);
+        assert_eq!(lasso.cycle.len(), 2);
+        assert_eq!(lasso.loop_state().location, b);
+        assert_eq!(lasso.cycle.last().unwrap().target, *lasso.loop_state());
+        assert_eq!(lasso.unrolled().unwrap().replay(&automaton), Ok(()));
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[1].len(), 2);
+    }
+
+    #[test]
+    fn analysis_slice_to_variables_drops_unrelated_updates_and_guards() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let guard = Guard::new(&Expression::new_logical_and(
+            &Expression::new_identifier("x"),
+            &Expression::new_identifier("noise"),
+        ));
+        let update = Update::new(&Statement::new_sequence(&[
+            assign("y", "x"),
+            Statement::new_simple_assignment("noise", &Value::new_true()),
+        ]));
+        let edges = HashSet::from([
+            Edge::new(&a, &Channel::new_output("step"), &guard, &update, &b),
+            Edge::new(
+                &b,
+                &Channel::new_input("back"),
+                &Guard::new_true(),
+                &Update::empty(),
+                &a,
+            ),
+        ]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+
+        let sliced = automaton
+            .slice_to_variables(&HashSet::from([String::from("y")]))
+            .ok()
+            .unwrap();
+
+        let step = sliced
+            .get_edges()
+            .iter()
+            .find(|edge| edge.action == Channel::new_output("step"))
+            .unwrap();
+        assert_eq!(
+            step.update,
+            Update::new(&Statement::new_sequence(&[assign("y", "x")]))
+        );
+        assert_eq!(step.guard, Guard::new(&Expression::new_identifier("x")));
+        assert_eq!(
+            sliced.get_initial_environment(),
+            automaton.get_initial_environment()
+        );
+    }
+
+    #[test]
+    fn analysis_estimate_state_space() {
+        let (automaton, _, _) = new_fixture();
+
+        let estimate = estimate_state_space(&automaton);
+
+        // Two locations and the four variables x, y, log and trace
+        assert_eq!(estimate.static_bound, Some(32));
+        // y is false after the step, so back is never enabled
+        assert_eq!(estimate.sampled_states, 2);
+        assert!(estimate.is_explorable_within(32));
+        assert!(!estimate.is_explorable_within(31));
+    }
+
+    #[test]
+    fn analysis_estimate_state_space_is_deterministic() {
+        let automaton = Automaton::parse(
+            "location a init\nlocation b\na -flip! do x = !x-> b\nb -flop! do y = x-> a\nb -stay?-> b",
+        )
+        .ok()
+        .unwrap();
+
+        let estimate = estimate_state_space_with(&automaton, 3, 4, 7);
+
+        assert_eq!(estimate.static_bound, Some(8));
+        assert_eq!(estimate, estimate_state_space_with(&automaton, 3, 4, 7));
+        assert!(estimate.sampled_states <= 8);
+    }
+
+    #[test]
+    fn analysis_static_bound_overflows() {
+        let variables: Vec<String> = (0..127).map(|i| format!("var v{} = false", i)).collect();
+        let automaton = Automaton::parse(&format!(
+            "{}\nlocation a init\nlocation b\na -go!-> b",
+            variables.join("\n")
+        ))
+        .ok()
+        .unwrap();
+        let fewer = Automaton::parse(&format!(
+            "{}\nlocation a init\na -go!-> a",
+            variables.join("\n")
+        ))
+        .ok()
+        .unwrap();
+
+        assert_eq!(static_state_space_bound(&automaton), None);
+        assert_eq!(static_state_space_bound(&fewer), Some(1 << 127));
+        assert!(!estimate_state_space_with(&automaton, 1, 1, 0).is_explorable_within(usize::MAX));
+    }
+
+    #[test]
+    fn analysis_static_bound_counts_clock_values() {
+        let automaton = Automaton::parse(
+            "var x = 0\nlocation a init invariant x <= 3\nlocation b\na -go! when x >= 2-> b",
+        )
+        .ok()
+        .unwrap();
+        let reachable = automaton.explored_statistics().reachable_states.unwrap();
+
+        let estimate = estimate_state_space(&automaton);
+
+        assert_eq!(reachable, 7);
+        assert_eq!(estimate.static_bound, Some(10));
+        assert!(!estimate.is_explorable_within(4));
+        assert!(estimate.sampled_states <= reachable);
+    }
+}
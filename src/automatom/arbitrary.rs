@@ -0,0 +1,587 @@
+use std::{collections::HashSet, fmt::Debug};
+
+use crate::language::{
+    environment::Environment,
+    expression::{BinaryOperator, Expression},
+    statement::Statement,
+    value::Value,
+};
+
+use super::{
+    automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+    location::Location, update::Update,
+};
+
+/// The most locations of a generated automaton.
+const MAX_LOCATIONS: usize = 4;
+/// The most edges of a generated automaton.
+const MAX_EDGES: usize = 6;
+/// The depth of generated guards, invariants and assigned values.
+const MAX_DEPTH: usize = 3;
+
+/// Generates random expressions, statements, edges and automata from a seed,
+/// such that a fixed seed always yields the same values. Generated expressions
+/// have no parenthesized nodes, such that printing them has to add the
+/// parentheses their structure requires.
+pub struct Generator {
+    random: u64,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Generator { random: seed }
+    }
+
+    /// The next number of the SplitMix64 generator.
+    fn next_random(&mut self) -> u64 {
+        self.random = self.random.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.random;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number from zero up to but excluding the bound.
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_random() % bound as u64) as usize
+    }
+
+    pub fn boolean(&mut self) -> bool {
+        self.below(2) == 1
+    }
+
+    /// An expression of at most the depth over literal booleans and the
+    /// identifiers, which is closed if there are no identifiers.
+    pub fn expression(&mut self, identifiers: &[String], max_depth: usize) -> Expression {
+        let leaf = max_depth <= 1 || self.below(3) == 0;
+        if leaf {
+            return match self.below(4) {
+                0 => Expression::new_boolean(&self.boolean()),
+                _ if identifiers.is_empty() => Expression::new_boolean(&self.boolean()),
+                _ => Expression::new_identifier(&identifiers[self.below(identifiers.len())]),
+            };
+        }
+        if self.below(4) == 0 {
+            let operand = self.expression(identifiers, max_depth - 1);
+            return Expression::new_logical_not(&operand);
+        }
+        let operators = [
+            BinaryOperator::LogicalAnd,
+            BinaryOperator::LogicalOr,
+            BinaryOperator::Equal,
+            BinaryOperator::NotEqual,
+            BinaryOperator::Implication,
+            BinaryOperator::BiImplication,
+        ];
+        let operator = operators[self.below(operators.len())].clone();
+        let lhs = self.expression(identifiers, max_depth - 1);
+        let rhs = self.expression(identifiers, max_depth - 1);
+        Expression::new_binary_expression(&lhs, &operator, &rhs)
+    }
+
+    /// A statement assigning the identifiers in the form read by the parser,
+    /// that is a sequence of assignments and ifs whose branches are assignments
+    /// or sequences of them. Assigned values are at most the depth. There must
+    /// be an identifier to assign.
+    pub fn statement(&mut self, identifiers: &[String], max_depth: usize) -> Statement {
+        match self.below(3) {
+            0 => {
+                let statements: Vec<Statement> = (0..2 + self.below(2))
+                    .map(|_| self.simple_statement(identifiers, max_depth))
+                    .collect();
+                Statement::new_sequence(&statements)
+            }
+            _ => self.simple_statement(identifiers, max_depth),
+        }
+    }
+
+    fn simple_statement(&mut self, identifiers: &[String], max_depth: usize) -> Statement {
+        if self.below(3) == 0 {
+            let condition = self.expression(identifiers, max_depth);
+            let then = self.branch(identifiers, max_depth);
+            let otherwise = match self.boolean() {
+                true => Some(self.branch(identifiers, max_depth)),
+                false => None,
+            };
+            return Statement::new_if(&condition, &then, otherwise.as_ref());
+        }
+        self.assignment(identifiers, max_depth)
+    }
+
+    fn branch(&mut self, identifiers: &[String], max_depth: usize) -> Statement {
+        match self.boolean() {
+            true => Statement::new_sequence(&[
+                self.assignment(identifiers, max_depth),
+                self.assignment(identifiers, max_depth),
+            ]),
+            false => self.assignment(identifiers, max_depth),
+        }
+    }
+
+    fn assignment(&mut self, identifiers: &[String], max_depth: usize) -> Statement {
+        let identifier = &identifiers[self.below(identifiers.len())];
+        Statement::new_assignment(
+            &Expression::new_identifier(identifier),
+            &self.expression(identifiers, max_depth),
+        )
+    }
+
+    /// An edge between two of the locations by one of the actions, where the
+    /// guard is true and the update empty half of the time. The update is
+    /// always empty if there are no identifiers to assign.
+    pub fn edge(
+        &mut self,
+        locations: &[Location],
+        actions: &[Channel],
+        identifiers: &[String],
+    ) -> Edge {
+        let source = &locations[self.below(locations.len())];
+        self.edge_from(source, locations, actions, identifiers)
+    }
+
+    fn edge_from(
+        &mut self,
+        source: &Location,
+        locations: &[Location],
+        actions: &[Channel],
+        identifiers: &[String],
+    ) -> Edge {
+        let action = &actions[self.below(actions.len())];
+        let guard = match self.boolean() {
+            true => Guard::new(&self.expression(identifiers, MAX_DEPTH)),
+            false => Guard::new_true(),
+        };
+        let update = match self.boolean() && !identifiers.is_empty() {
+            true => Update::new(&self.statement(identifiers, MAX_DEPTH)),
+            false => Update::empty(),
+        };
+        let target = &locations[self.below(locations.len())];
+        Edge::new(source, action, &guard, &update, target)
+    }
+
+    /// A well-formed automaton with a single initial location, from which an
+    /// edge leaves, actions which are either inputs or outputs and every
+    /// identifier declared as a boolean with a random initial value. Clocks are
+    /// never generated, as the symbolic exploration the automata are checked
+    /// against rejects them. The initial location has a true invariant, such
+    /// that it holds initially.
+    pub fn automaton(&mut self, identifiers: &[String]) -> Automaton {
+        let locations: Vec<Location> = (0..1 + self.below(MAX_LOCATIONS))
+            .map(|index| {
+                let name = format!("l{}", index);
+                match index {
+                    0 => Location::new_initial(&name, &Invariant::new_true()),
+                    _ if self.below(3) == 0 => Location::new_normal(
+                        &name,
+                        &Invariant::new(&self.expression(identifiers, 2)),
+                    ),
+                    _ => Location::new_normal(&name, &Invariant::new_true()),
+                }
+            })
+            .collect();
+        let actions: Vec<Channel> = (0..1 + self.below(3))
+            .map(|index| match self.boolean() {
+                true => Channel::new_input(&format!("a{}", index)),
+                false => Channel::new_output(&format!("a{}", index)),
+            })
+            .collect();
+        let mut environment = Environment::new_empty();
+        for identifier in identifiers {
+            environment.insert(identifier, &Value::new_boolean(self.boolean()));
+        }
+
+        let mut edges =
+            HashSet::from([self.edge_from(&locations[0], &locations, &actions, identifiers)]);
+        for _ in 0..self.below(MAX_EDGES) {
+            edges.insert(self.edge(&locations, &actions, identifiers));
+        }
+        match Automaton::new_with_options("arbitrary", &edges, Some(&environment), false) {
+            Ok(automaton) => automaton,
+            Err(error) => panic!("The generated automaton is not well-formed: {}", error),
+        }
+    }
+}
+
+/// Smaller expressions than the given one: its operands, literals in place of
+/// it and the expression with one operand shrunk.
+pub fn shrink_expression(expression: &Expression) -> Vec<Expression> {
+    let mut candidates = Vec::new();
+    match expression {
+        Expression::Literal(Value::Bool(true)) => {}
+        Expression::Literal(Value::Bool(false)) => candidates.push(Expression::new_boolean(&true)),
+        Expression::Literal(_) => candidates.push(Expression::new_boolean(&false)),
+        Expression::Parenthesized(inner) => {
+            candidates.push(inner.as_ref().clone());
+            for shrunk in shrink_expression(inner) {
+                candidates.push(Expression::new_parenthesized(&shrunk));
+            }
+        }
+        Expression::Unary(_, operand) => {
+            candidates.push(operand.as_ref().clone());
+            for shrunk in shrink_expression(operand) {
+                candidates.push(Expression::new_logical_not(&shrunk));
+            }
+        }
+        Expression::Binary(lhs, operator, rhs) => {
+            candidates.push(Expression::new_boolean(&false));
+            candidates.push(lhs.as_ref().clone());
+            candidates.push(rhs.as_ref().clone());
+            for shrunk in shrink_expression(lhs) {
+                candidates.push(Expression::new_binary_expression(&shrunk, operator, rhs));
+            }
+            for shrunk in shrink_expression(rhs) {
+                candidates.push(Expression::new_binary_expression(lhs, operator, &shrunk));
+            }
+        }
+    }
+    candidates
+}
+
+/// Smaller statements than the given one: the statements nested in it and the
+/// statement with a part of it shrunk.
+pub fn shrink_statement(statement: &Statement) -> Vec<Statement> {
+    let mut candidates = Vec::new();
+    match statement {
+        Statement::Assigment { identifier, value } => {
+            for shrunk in shrink_expression(value) {
+                candidates.push(Statement::new_assignment(identifier, &shrunk));
+            }
+        }
+        Statement::Sequence(statements) => {
+            candidates.extend(statements.iter().cloned());
+            for index in 0..statements.len() {
+                let mut fewer = statements.clone();
+                fewer.remove(index);
+                // A sequence of one statement is read back as the statement
+                if fewer.len() > 1 {
+                    candidates.push(Statement::new_sequence(&fewer));
+                }
+            }
+        }
+        Statement::If {
+            condition,
+            then,
+            otherwise,
+        } => {
+            candidates.push(then.as_ref().clone());
+            if let Some(otherwise) = otherwise {
+                candidates.push(otherwise.as_ref().clone());
+                candidates.push(Statement::new_if(condition, then, None));
+            }
+            for shrunk in shrink_expression(condition) {
+                candidates.push(Statement::new_if(&shrunk, then, otherwise.as_deref()));
+            }
+        }
+        Statement::ClockReset { .. } => (),
+    }
+    candidates
+}
+
+/// Smaller well-formed automata than the given one, obtained by removing an
+/// edge or shrinking the guard or update of an edge. The initial environment
+/// is kept, such that every identifier stays declared.
+pub fn shrink_automaton(automaton: &Automaton) -> Vec<Automaton> {
+    let edges = automaton.edges_sorted();
+    let mut candidates: Vec<HashSet<Edge>> = Vec::new();
+    for (index, edge) in edges.iter().enumerate() {
+        let others = || -> HashSet<Edge> {
+            edges
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, edge)| edge.clone())
+                .collect()
+        };
+        let replaced = |replacement: Edge| -> HashSet<Edge> {
+            let mut edges = others();
+            edges.insert(replacement);
+            edges
+        };
+
+        candidates.push(others());
+        for guard in shrink_expression(&edge.guard.node) {
+            candidates.push(replaced(Edge {
+                guard: Guard::new(&guard),
+                ..edge.clone()
+            }));
+        }
+        if let Some(update) = &edge.update.node {
+            candidates.push(replaced(Edge {
+                update: Update::empty(),
+                ..edge.clone()
+            }));
+            for update in shrink_statement(update) {
+                candidates.push(replaced(Edge {
+                    update: Update::new(&update),
+                    ..edge.clone()
+                }));
+            }
+        }
+    }
+
+    candidates
+        .iter()
+        .filter_map(|edges| {
+            Automaton::new_with_options(
+                &automaton.name,
+                edges,
+                Some(automaton.get_initial_environment()),
+                false,
+            )
+            .ok()
+        })
+        .collect()
+}
+
+/// Checks the property on values generated from consecutive seeds. A value
+/// failing the property is shrunk by repeatedly taking the first smaller
+/// candidate which also fails, and the smallest one found is reported by
+/// panicking.
+pub fn for_all<T: Debug>(
+    cases: usize,
+    seed: u64,
+    generate: impl Fn(&mut Generator) -> T,
+    shrink: impl Fn(&T) -> Vec<T>,
+    property: impl Fn(&T) -> bool,
+) {
+    if let Some((case, value)) = find_counterexample(cases, seed, generate, shrink, property) {
+        panic!(
+            "The property failed for case {} with seed {}, shrunk to {:?}",
+            case,
+            seed.wrapping_add(case),
+            value
+        );
+    }
+}
+
+/// As `for_all`, but returns the first failing case along with the value it
+/// was shrunk to instead of panicking.
+pub fn find_counterexample<T>(
+    cases: usize,
+    seed: u64,
+    generate: impl Fn(&mut Generator) -> T,
+    shrink: impl Fn(&T) -> Vec<T>,
+    property: impl Fn(&T) -> bool,
+) -> Option<(u64, T)> {
+    for case in 0..cases as u64 {
+        let mut generator = Generator::new(seed.wrapping_add(case));
+        let mut value = generate(&mut generator);
+        if property(&value) {
+            continue;
+        }
+        while let Some(smaller) = shrink(&value)
+            .into_iter()
+            .find(|candidate| !property(candidate))
+        {
+            value = smaller;
+        }
+        return Some((case, value));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, panic};
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        language::{
+            environment::Environment, evaluation::Evaluation, expression::Expression,
+            interpreter::Interpreter, parser::parse_expression, statement::Statement, value::Value,
+        },
+        transition_system::{
+            state::State,
+            transition_system_breadth_first_search::TransitionSystemBreadthFirstSearch,
+            transition_system_depth_first_search::TransitionSystemDepthFirstSearch,
+        },
+    };
+
+    use super::{
+        find_counterexample, for_all, shrink_automaton, shrink_expression, Generator, MAX_DEPTH,
+    };
+
+    /// The automaton with the parentheses in its guards, updates and
+    /// invariants removed, which generated automata never have.
+    fn without_parentheses(automaton: &Automaton) -> Automaton {
+        let location = |location: &Location| match location {
+            Location::Normal { name, invariant } => {
+                Location::new_normal(name, &Invariant::new(&invariant.node.without_parentheses()))
+            }
+            Location::Initial { name, invariant } => {
+                Location::new_initial(name, &Invariant::new(&invariant.node.without_parentheses()))
+            }
+            location => location.clone(),
+        };
+        let edges: HashSet<Edge> = automaton
+            .get_edges()
+            .iter()
+            .map(|edge| Edge {
+                source: location(&edge.source),
+                guard: Guard::new(&edge.guard.node.without_parentheses()),
+                update: Update {
+                    node: edge
+                        .update
+                        .node
+                        .as_ref()
+                        .map(Statement::without_parentheses),
+                },
+                target: location(&edge.target),
+                ..edge.clone()
+            })
+            .collect();
+        Automaton::new_with_options(
+            &automaton.name,
+            &edges,
+            Some(automaton.get_initial_environment()),
+            false,
+        )
+        .ok()
+        .unwrap()
+    }
+
+    fn identifiers() -> Vec<String> {
+        vec![String::from("x"), String::from("y"), String::from("z")]
+    }
+
+    fn evaluate(expression: &Expression, assignment: usize) -> Option<bool> {
+        let mut environment = Environment::new_empty();
+        for (index, identifier) in identifiers().iter().enumerate() {
+            environment.insert(
+                identifier,
+                &Value::new_boolean(assignment >> index & 1 == 1),
+            );
+        }
+        match Interpreter::new(&environment).eval_expression(expression) {
+            Ok(Evaluation::Bool(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn arbitrary_parse_round_trips_generated_expressions() {
+        for_all(
+            200,
+            0,
+            |generator| generator.expression(&identifiers(), 4),
+            shrink_expression,
+            |expression| {
+                parse_expression(&expression.to_string())
+                    .is_ok_and(|parsed| parsed.without_parentheses() == *expression)
+            },
+        );
+    }
+
+    #[test]
+    fn arbitrary_simplify_preserves_evaluation() {
+        for_all(
+            200,
+            1000,
+            |generator| generator.expression(&identifiers(), 4),
+            shrink_expression,
+            |expression| {
+                let simplified = expression.simplify();
+                (0..8).all(|assignment| {
+                    evaluate(expression, assignment) == evaluate(&simplified, assignment)
+                })
+            },
+        );
+    }
+
+    #[test]
+    fn arbitrary_breadth_and_depth_first_search_reach_the_same_states() {
+        for_all(
+            50,
+            2000,
+            |generator| generator.automaton(&identifiers()),
+            shrink_automaton,
+            |automaton| {
+                let actions = automaton.get_actions();
+                let breadth: HashSet<State> =
+                    TransitionSystemBreadthFirstSearch::new(actions, automaton).collect();
+                let depth: HashSet<State> =
+                    TransitionSystemDepthFirstSearch::new(actions, automaton).collect();
+                breadth == depth
+            },
+        );
+    }
+
+    #[test]
+    fn arbitrary_dsl_round_trips_generated_automata() {
+        for_all(
+            50,
+            3000,
+            |generator| generator.automaton(&identifiers()),
+            shrink_automaton,
+            |automaton| {
+                Automaton::parse(&automaton.to_dsl())
+                    .is_ok_and(|parsed| without_parentheses(&parsed).structurally_equal(automaton))
+            },
+        );
+    }
+
+    #[test]
+    fn arbitrary_shrinks_to_smaller_valid_automata() {
+        let automaton = Generator::new(7).automaton(&identifiers());
+        let edges = automaton.get_edges().len();
+
+        // Fails for any automaton with an edge, so it shrinks to a single edge
+        let counterexample = find_counterexample(
+            1,
+            7,
+            |generator| generator.automaton(&identifiers()),
+            shrink_automaton,
+            |automaton| automaton.get_edges().is_empty(),
+        );
+
+        let (case, shrunk) = counterexample.unwrap();
+        assert_eq!(case, 0);
+        assert_eq!(shrunk.get_edges().len(), 1);
+        for smaller in shrink_automaton(&automaton) {
+            assert!(smaller.get_edges().len() <= edges);
+            assert_eq!(
+                smaller.get_initial_location(),
+                automaton.get_initial_location()
+            );
+        }
+        assert_eq!(
+            Generator::new(7).automaton(&identifiers()).to_dsl(),
+            automaton.to_dsl()
+        );
+    }
+
+    #[test]
+    fn arbitrary_generates_without_identifiers() {
+        for seed in 0..32 {
+            let mut generator = Generator::new(seed);
+
+            let expression = generator.expression(&[], MAX_DEPTH);
+            let automaton = generator.automaton(&[]);
+
+            assert!(expression.identifiers().is_empty());
+            assert!(automaton.get_initial_environment().is_empty());
+        }
+    }
+
+    #[test]
+    fn arbitrary_for_all_panics_with_counterexample() {
+        let result = panic::catch_unwind(|| {
+            for_all(
+                1,
+                7,
+                |generator| generator.automaton(&identifiers()),
+                shrink_automaton,
+                |automaton| automaton.get_edges().is_empty(),
+            )
+        });
+
+        let message = result.err().unwrap();
+        assert!(message
+            .downcast_ref::<String>()
+            .unwrap()
+            .starts_with("The property failed for case 0 with seed 7"));
+    }
+}
@@ -1,87 +1,158 @@
-use std::collections::VecDeque;
-use std::{collections::HashSet, fmt::Debug};
+use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::HashSet,
+    fmt::{Debug, Display},
+};
 
-use crate::language::environment::Environment;
+use crate::diagnostics::{trace_event, trace_span};
+use crate::language::environment::{Environment, MissingIdentifier};
+use crate::language::error::Error as LangError;
+use crate::language::evaluation::Evaluation;
 use crate::language::expression::Expression;
 use crate::language::interpreter::Interpreter;
 use crate::language::lang_type::LangType;
 use crate::language::statement::Statement;
 use crate::language::type_checker::TypeChecker;
 use crate::language::value::Value;
+use crate::transition_system::state::State;
+use crate::transition_system::transition_iterator::TransitionIterator;
+use crate::transition_system::transition_system::TransitionSystem;
 
+use super::analysis::{
+    retain_assignments, retain_conjuncts, static_state_space_bound, VariableDependencies,
+};
 use super::channel::*;
+use super::dsl;
 use super::edge::*;
 use super::error::*;
+use super::guard::Guard;
+use super::guard_cache::GuardCache;
 use super::invariant::Invariant;
+use super::lint::Lint;
 use super::location::*;
+use super::statistics::AutomatonStats;
+use super::update::Update;
+
+/// The action name and direction, guard and update of an edge.
+type EdgeLabel = (String, bool, Guard, Update);
+
+/// The variant and invariant of a location along with the labels of its
+/// outgoing and ingoing edges.
+type LocationSignature = (
+    std::mem::Discriminant<Location>,
+    Option<Invariant>,
+    Vec<EdgeLabel>,
+    Vec<EdgeLabel>,
+);
 
 #[derive(Debug, Clone)]
 pub struct Automaton {
     pub name: String,
     locations: HashSet<Location>,
     edges: HashSet<Edge>,
+    outgoing: HashMap<Location, Vec<Edge>>,
+    ingoing: HashMap<Location, Vec<Edge>>,
     actions: HashSet<Channel>,
     inputs: HashSet<Channel>,
     outputs: HashSet<Channel>,
     initial: Location,
     initial_environment: Environment,
+    guard_cache: Option<GuardCache>,
 }
 
 impl Automaton {
+    /// Creates the automaton, automatically declaring the variables of the edges
+    /// and invariants only if no environment is given.
     pub fn new(
         name: &str,
         edges: &HashSet<Edge>,
         environment: Option<&Environment>,
     ) -> Result<Self, Error> {
+        Self::new_with_options(name, edges, environment, environment.is_none())
+    }
+
+    /// Creates the automaton, where variables missing from the environment are
+    /// declared as false if auto_declare is set and are an error otherwise.
+    pub fn new_with_options(
+        name: &str,
+        edges: &HashSet<Edge>,
+        environment: Option<&Environment>,
+        auto_declare: bool,
+    ) -> Result<Self, Error> {
+        let declare_false = |_: &str| Value::new_false();
+        let default: Option<&dyn Fn(&str) -> Value> = match auto_declare {
+            true => Some(&declare_false),
+            false => None,
+        };
+        Self::new_with_defaults(name, edges, environment, default)
+    }
+
+    /// Creates the automaton, where variables missing from the environment are
+    /// declared with the value the default gives their identifier, and are an
+    /// error if there is no default.
+    pub fn new_with_defaults(
+        name: &str,
+        edges: &HashSet<Edge>,
+        environment: Option<&Environment>,
+        default: Option<&dyn Fn(&str) -> Value>,
+    ) -> Result<Self, Error> {
+        trace_span!("automaton::new");
         let mut inputs: HashSet<Channel> = HashSet::new();
         let mut outputs: HashSet<Channel> = HashSet::new();
         let mut actions: HashSet<Channel> = HashSet::new();
         let mut initials: HashSet<Location> = HashSet::new();
         let mut locations: HashSet<Location> = HashSet::new();
         let mut initial: Option<Location> = None;
-        // If we have no environment we automatically declare variables from edges and such
-        let declare_variables = environment == None;
-
-        let mut initial_environment = if declare_variables {
-            Environment::new_empty()
-        } else {
-            environment.unwrap().clone()
-        };
+        let declare_variables = default.is_some();
+        let mut initial_environment = environment.cloned().unwrap_or_else(Environment::new_empty);
 
         // Find all the inputs/outputs used as actions in the edges
         let handle_edge_guard_identifiers =
-            |envir: &mut Environment, expression: &Expression| -> Option<Vec<String>> {
-                let missing = envir.missing_identifiers_in_expression(expression);
+            |envir: &mut Environment, expression: &Expression| -> Option<Vec<MissingIdentifier>> {
+                let missing = envir.missing_identifier_occurrences_in_expression(expression);
 
-                if declare_variables {
-                    for identifier in missing.clone() {
-                        envir.insert(&*identifier, &Value::new_false());
+                if let Some(default) = default {
+                    for occurrence in missing.iter() {
+                        envir.insert(&occurrence.identifier, &default(&occurrence.identifier));
                     }
                 }
-                return if missing.len() == 0 {
+                if missing.is_empty() {
                     None
                 } else {
                     Some(missing)
-                };
+                }
             };
 
         let handle_edge_update_indentifiers =
-            |envir: &mut Environment, statement: &Statement| -> Option<Vec<String>> {
-                let missing = envir.missing_identifiers_in_statement(statement);
+            |envir: &mut Environment, statement: &Statement| -> Option<Vec<MissingIdentifier>> {
+                let missing = envir.missing_identifier_occurrences_in_statement(statement);
 
-                if declare_variables {
-                    for identifier in missing.clone() {
-                        envir.insert(&*identifier, &Value::new_false());
+                if let Some(default) = default {
+                    for occurrence in missing.iter() {
+                        envir.insert(&occurrence.identifier, &default(&occurrence.identifier));
                     }
                 }
-                return if missing.len() == 0 {
+                if missing.is_empty() {
                     None
                 } else {
                     Some(missing)
-                };
+                }
             };
 
-        for edge in edges.clone() {
+        let missing_names = |occurrences: &Vec<MissingIdentifier>| -> Vec<String> {
+            occurrences
+                .iter()
+                .map(|occurrence| occurrence.identifier.clone())
+                .collect()
+        };
+
+        // Errors in edges and invariants are collected such that all are reported
+        let mut errors: Vec<Error> = Vec::new();
+        let mut sorted_edges: Vec<&Edge> = edges.iter().collect();
+        sorted_edges.sort();
+
+        for edge in sorted_edges {
+            trace_event!("automaton::edge", "validating {}", edge);
             actions.insert(edge.action.clone());
             match edge.action {
                 Channel::In(_) => inputs.insert(edge.action.clone()),
@@ -93,39 +164,76 @@ impl Automaton {
             {
                 // Error handling: Check that all identifiers in the guard is declared
                 if !declare_variables {
-                    return Err(Error::MissingIdentifiersInEdgeGuard {
+                    errors.push(Error::MissingIdentifiersInEdgeGuard {
+                        source_location: Some(&(file!(), line!())),
                         automaton: String::from(name),
-                        edge: edge.clone(),
-                        missing: missing_identifiers,
+                        edge: Box::new(edge.clone()),
+                        missing: missing_names(&missing_identifiers),
+                        occurrences: initial_environment.suggest(missing_identifiers),
                     });
+                    continue;
                 }
             }
 
-            if let Some(update) = edge.clone().update.node {
+            if let Some(update) = &edge.update.node {
                 if let Some(missing_identifiers) =
-                    handle_edge_update_indentifiers(&mut initial_environment, &update)
+                    handle_edge_update_indentifiers(&mut initial_environment, update)
                 {
                     // Error handling: Check that all identifiers in the update is declared
                     if !declare_variables {
-                        return Err(Error::MissingIdentifiersInEdgeUpdate {
+                        errors.push(Error::MissingIdentifiersInEdgeUpdate {
+                            source_location: Some(&(file!(), line!())),
                             automaton: String::from(name),
-                            edge: edge.clone(),
-                            missing: missing_identifiers,
+                            edge: Box::new(edge.clone()),
+                            missing: missing_names(&missing_identifiers),
+                            occurrences: initial_environment.suggest(missing_identifiers),
                         });
+                        continue;
                     }
                 }
             }
 
-            // Error handling: Check that the guard is a logical node
-            let actual = TypeChecker::new(&initial_environment)
-                .check_expression(&edge.guard.node)
-                .unwrap();
-            if actual != LangType::Logical {
-                return Err(Error::EdgeGuardIsNotLogical {
+            // Error handling: Check that the update assigns values of the declared types
+            let type_checker = TypeChecker::new(&initial_environment);
+            if let Some(update) = &edge.update.node {
+                match type_checker.check_statement(update) {
+                    Ok(_) => (),
+                    Err(LangError::AssignmentTypeMismatch {
+                        identifier,
+                        expected,
+                        actual,
+                    }) => errors.push(Error::EdgeUpdateTypeMismatch {
+                        source_location: Some(&(file!(), line!())),
+                        automaton: String::from(name),
+                        edge: Box::new(edge.clone()),
+                        identifier,
+                        expected,
+                        actual,
+                    }),
+                    Err(error) => errors.push(Error::EdgeUpdateIsIllTyped {
+                        source_location: Some(&(file!(), line!())),
+                        automaton: String::from(name),
+                        edge: Box::new(edge.clone()),
+                        message: error.to_string(),
+                    }),
+                }
+            }
+
+            // Error handling: Check that the guard is a well-typed logical node
+            match type_checker.check_expression(&edge.guard.node) {
+                Ok(LangType::Logical) => (),
+                Ok(actual) => errors.push(Error::EdgeGuardIsNotLogical {
+                    source_location: Some(&(file!(), line!())),
                     automaton: String::from(name),
-                    edge: edge.clone(),
+                    edge: Box::new(edge.clone()),
                     actual,
-                });
+                }),
+                Err(error) => errors.push(Error::EdgeGuardIsIllTyped {
+                    source_location: Some(&(file!(), line!())),
+                    automaton: String::from(name),
+                    edge: Box::new(edge.clone()),
+                    message: error.to_string(),
+                }),
             }
         }
 
@@ -137,7 +245,10 @@ impl Automaton {
 
         // Error handling: Check that all identifiers are in the invariants
         let mut locations_worklist: VecDeque<Location> = VecDeque::new();
-        locations_worklist.extend(locations.clone());
+        let mut sorted_locations: Vec<Location> = locations.iter().cloned().collect();
+        sorted_locations.sort();
+        // Popped from the back, so reverse to check the locations in sorted order
+        locations_worklist.extend(sorted_locations.into_iter().rev());
 
         let mut check_invariant = |location: &Location, invariant: &Invariant| -> Option<Error> {
             // un-declared identifiers are handle the same way for invariants as edges
@@ -146,13 +257,30 @@ impl Automaton {
             {
                 if !declare_variables {
                     return Some(Error::MissingIdentifiersInLocationInvariant {
+                        source_location: Some(&(file!(), line!())),
                         automaton: String::from(name),
-                        location: location.clone(),
-                        missing: missing_identifiers,
+                        location: Box::new(location.clone()),
+                        missing: missing_names(&missing_identifiers),
+                        occurrences: initial_environment.suggest(missing_identifiers),
                     });
                 }
             }
-            None
+            // Checked before the initial location, such that an ill-typed invariant is not reported as inconsistent
+            match TypeChecker::new(&initial_environment).check_expression(&invariant.node) {
+                Ok(LangType::Logical) => None,
+                Ok(actual) => Some(Error::LocationInvariantIsNotLogical {
+                    source_location: Some(&(file!(), line!())),
+                    automaton: String::from(name),
+                    location: Box::new(location.clone()),
+                    actual,
+                }),
+                Err(error) => Some(Error::LocationInvariantIsIllTyped {
+                    source_location: Some(&(file!(), line!())),
+                    automaton: String::from(name),
+                    location: Box::new(location.clone()),
+                    message: error.to_string(),
+                }),
+            }
         };
 
         while !locations_worklist.is_empty() {
@@ -160,7 +288,7 @@ impl Automaton {
             match current.clone() {
                 Location::Normal { name: _, invariant } => {
                     if let Some(error) = check_invariant(&current, &invariant) {
-                        return Err(error);
+                        errors.push(error);
                     }
                 }
                 Location::Conjunction { locations, .. } => {
@@ -170,7 +298,7 @@ impl Automaton {
                 }
                 Location::Initial { name: _, invariant } => {
                     if let Some(error) = check_invariant(&current, &invariant) {
-                        return Err(error);
+                        errors.push(error);
                     }
                 }
                 // All other location variants does not have an invariant or is a composition
@@ -178,6 +306,10 @@ impl Automaton {
             }
         }
 
+        if let Some(error) = Error::try_aggregate(errors) {
+            return Err(error);
+        }
+
         // Error handling: Actions are not partitioned into inputs/outputs
         if !inputs.is_disjoint(&outputs) {
             let mut intersection: HashSet<Channel> = HashSet::new();
@@ -185,14 +317,16 @@ impl Automaton {
                 intersection.insert(joint.clone());
             }
             return Err(Error::PartitionError {
+                source_location: Some(&(file!(), line!())),
                 automaton: String::from(name),
                 violating: intersection,
             });
         }
 
         // Error handling: Empty automaton
-        if locations.len() == 0 {
+        if locations.is_empty() {
             return Err(Error::EmptyAutomaton {
+                source_location: Some(&(file!(), line!())),
                 automaton: String::from(name),
             });
         }
@@ -210,60 +344,183 @@ impl Automaton {
         }
 
         // Error handling: Zero or more than one initial locations
-        if initials.len() == 0 {
+        if initials.is_empty() {
             return Err(Error::MissingInitialLocation {
+                source_location: Some(&(file!(), line!())),
                 automaton: String::from(name),
             });
         } else if initials.len() > 1 {
             return Err(Error::TooManyInitialLocations {
+                source_location: Some(&(file!(), line!())),
                 automaton: String::from(name),
                 initials: initials.clone(),
             });
         }
 
-        // Error handling: The invariant of the initial location is never enabled
-        let unwrapped_initial = initial.unwrap();
-        if let Location::Initial {
-            name: _,
-            ref invariant,
-        } = unwrapped_initial
-        {
-            let mut interpreter = Interpreter::new(&initial_environment);
-            let evaluation_result = interpreter.eval_expression(&invariant.node.clone());
-            if evaluation_result.is_err() {
-                return Err(Error::InconsistentInitialLocation {
-                    automaton: String::from(name),
-                    location: unwrapped_initial.clone(),
-                });
-            }
-
-            if let Some(evaluation) = evaluation_result.ok() {
-                if evaluation.is_false() {
-                    return Err(Error::InconsistentInitialLocation {
-                        automaton: String::from(name),
-                        location: unwrapped_initial.clone(),
-                    });
-                }
-            }
+        // Index the edges by their source and target locations
+        let mut outgoing: HashMap<Location, Vec<Edge>> = HashMap::new();
+        let mut ingoing: HashMap<Location, Vec<Edge>> = HashMap::new();
+        for edge in edges {
+            outgoing
+                .entry(edge.source.clone())
+                .or_default()
+                .push(edge.clone());
+            ingoing
+                .entry(edge.target.clone())
+                .or_default()
+                .push(edge.clone());
         }
 
-        Ok(Automaton {
+        let automaton = Automaton {
             name: String::from(name),
             locations: locations.clone(),
             edges: edges.clone(),
+            outgoing,
+            ingoing,
             actions,
             inputs,
             outputs,
-            initial: unwrapped_initial,
+            initial: initial.unwrap(),
             initial_environment: initial_environment.clone(),
-        })
+            guard_cache: None,
+        };
+
+        // Error handling: The invariant of the initial location is never enabled
+        let initial_location = automaton.get_initial_location_cloned();
+        match automaton.is_location_consistent(&initial_location, &initial_environment) {
+            Ok(true) => Ok(automaton),
+            _ => Err(Error::InconsistentInitialLocation {
+                source_location: Some(&(file!(), line!())),
+                automaton: String::from(name),
+                location: Box::new(initial_location),
+            }),
+        }
+    }
+
+    /// Builds an observer with the locations "Ok" and "Violated" which takes
+    /// each watched channel as an input and moves to the terminal "Violated"
+    /// location whenever the property does not hold. Composed to the right of a
+    /// system the property is evaluated after the step of the system, such that
+    /// the property is a safety property of the system if "Violated" is unreachable.
+    /// Identifiers in the property are declared as false in the observer.
+    ///
+    /// Fails if no channels are watched, as the observer would have no edges,
+    /// or if the property is not a valid guard.
+    pub fn monitor(
+        name: &str,
+        property: &Expression,
+        watched: &HashSet<Channel>,
+    ) -> Result<Automaton, Error> {
+        let invariant = &Invariant::new_true();
+        let ok = Location::new_initial("Ok", invariant);
+        let violated = Location::new_normal("Violated", invariant);
+        let holds = Guard::new(property);
+        let fails = Guard::new(&Expression::new_logical_not(property));
+
+        let mut edges: HashSet<Edge> = HashSet::new();
+        for channel in watched {
+            let input = Channel::new_input(channel.name());
+            edges.insert(Edge::new_loop(&ok, &input, &holds, &Update::empty()));
+            edges.insert(Edge::new(&ok, &input, &fails, &Update::empty(), &violated));
+        }
+
+        Automaton::new(name, &edges, None)
+    }
+
+    /// The largest time any guard or invariant compares a clock against, such
+    /// that clocks beyond it satisfy the same constraints.
+    pub fn clock_ceiling(&self) -> u32 {
+        let guards = self.edges.iter().map(|edge| &edge.guard.node);
+        let invariants = self.locations.iter().filter_map(|location| match location {
+            Location::Normal { invariant, .. }
+            | Location::Initial { invariant, .. }
+            | Location::Conjunction { invariant, .. } => Some(&invariant.node),
+            Location::Inconsistent { .. } | Location::Universal { .. } => None,
+        });
+        guards
+            .chain(invariants)
+            .flat_map(Expression::clock_constants)
+            .max()
+            .unwrap_or(0)
     }
 
-    pub fn get_initial_environment(&self) -> Environment {
+    /// Checks whether the invariant of the location holds in the environment.
+    /// Inconsistent locations are never consistent and universal locations always are.
+    pub fn is_location_consistent(
+        &self,
+        location: &Location,
+        environment: &Environment,
+    ) -> Result<bool, Error> {
+        let invariant = match location {
+            Location::Normal { invariant, .. }
+            | Location::Initial { invariant, .. }
+            | Location::Conjunction { invariant, .. } => invariant,
+            Location::Inconsistent { .. } => return Ok(false),
+            Location::Universal { .. } => return Ok(true),
+        };
+
+        let mut interpreter = Interpreter::new(environment);
+        match interpreter.eval_expression(&invariant.node) {
+            Ok(Evaluation::Bool(value)) => Ok(value),
+            Ok(Evaluation::Void) => Err(Error::InvariantEvaluationFailed {
+                source_location: Some(&(file!(), line!())),
+                automaton: self.name.clone(),
+                location: Box::new(location.clone()),
+                message: String::from("The invariant evaluated to void"),
+            }),
+            Ok(Evaluation::Clock(_)) => Err(Error::InvariantEvaluationFailed {
+                source_location: Some(&(file!(), line!())),
+                automaton: self.name.clone(),
+                location: Box::new(location.clone()),
+                message: String::from("The invariant evaluated to a clock"),
+            }),
+            Err(error) => Err(Error::InvariantEvaluationFailed {
+                source_location: Some(&(file!(), line!())),
+                automaton: self.name.clone(),
+                location: Box::new(location.clone()),
+                message: error.to_string(),
+            }),
+        }
+    }
+
+    /// Caches guard evaluations per edge and environment for enabledness checks
+    /// made through the automaton.
+    pub fn with_guard_cache(mut self) -> Self {
+        self.guard_cache = Some(GuardCache::new());
+        self
+    }
+
+    pub fn get_guard_cache(&self) -> Option<&GuardCache> {
+        self.guard_cache.as_ref()
+    }
+
+    /// As `try_is_edge_enabled`, where an edge whose guard fails to evaluate is disabled.
+    pub fn is_edge_enabled(&self, edge: &Edge, state: &State) -> bool {
+        self.try_is_edge_enabled(edge, state).unwrap_or(false)
+    }
+
+    /// Whether the edge is enabled in the state, or the error of evaluating its
+    /// guard, consulting the guard cache if any.
+    pub fn try_is_edge_enabled(&self, edge: &Edge, state: &State) -> Result<bool, LangError> {
+        match &self.guard_cache {
+            Some(cache) => cache.try_enabled(edge, state),
+            None => edge.try_enabled(state),
+        }
+    }
+
+    pub fn get_initial_environment(&self) -> &Environment {
+        &self.initial_environment
+    }
+
+    pub fn get_initial_environment_cloned(&self) -> Environment {
         self.initial_environment.clone()
     }
 
-    pub fn get_initial_location(&self) -> Location {
+    pub fn get_initial_location(&self) -> &Location {
+        &self.initial
+    }
+
+    pub fn get_initial_location_cloned(&self) -> Location {
         self.initial.clone()
     }
 
@@ -275,6 +532,37 @@ impl Automaton {
         &self.edges
     }
 
+    /// The locations sorted by name, breaking ties by their order.
+    pub fn locations_sorted(&self) -> Vec<Location> {
+        let mut locations: Vec<Location> = self.locations.iter().cloned().collect();
+        locations.sort_by(|lhs, rhs| lhs.get_name().cmp(&rhs.get_name()).then(lhs.cmp(rhs)));
+        locations
+    }
+
+    /// The actions sorted by name, with the input before the output of the same name.
+    pub fn actions_sorted(&self) -> Vec<Channel> {
+        let mut actions: Vec<Channel> = self.actions.iter().cloned().collect();
+        actions.sort_by_key(|action| (action.name().to_string(), action.is_output()));
+        actions
+    }
+
+    /// The edges in a canonical order: by source name, action name, target
+    /// name and guard, with the order of edges breaking any remaining ties.
+    pub fn edges_sorted(&self) -> Vec<Edge> {
+        let mut edges: Vec<Edge> = self.edges.iter().cloned().collect();
+        // The key sort is stable, so edges with equal keys stay in edge order
+        edges.sort();
+        edges.sort_by_cached_key(|edge| {
+            (
+                edge.source.get_name(),
+                edge.action.name().to_string(),
+                edge.target.get_name(),
+                edge.guard.to_string(),
+            )
+        });
+        edges
+    }
+
     pub fn get_actions(&self) -> &HashSet<Channel> {
         &self.actions
     }
@@ -287,14 +575,32 @@ impl Automaton {
         &self.outputs
     }
 
-    pub fn ingoing_edges(&self, location: &Location, actions: &HashSet<Channel>) -> Vec<Edge> {
-        let mut result = Vec::new();
-        for edge in self.edges.clone() {
-            if edge.target == *location && actions.contains(&edge.action) {
-                result.push(edge);
-            }
-        }
-        return result;
+    /// The sorted edges whose action has the name, regardless of its direction.
+    pub fn edges_on_channel(&self, name: &str) -> Vec<Edge> {
+        let mut edges: Vec<Edge> = self
+            .edges
+            .iter()
+            .filter(|edge| edge.action.name() == name)
+            .cloned()
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    /// The names of the actions without their directions.
+    pub fn channel_names(&self) -> HashSet<String> {
+        self.actions
+            .iter()
+            .map(|action| String::from(action.name()))
+            .collect()
+    }
+
+    pub fn ingoing_edges<'a>(
+        &'a self,
+        location: &Location,
+        actions: &'a HashSet<Channel>,
+    ) -> impl Iterator<Item = &'a Edge> + 'a {
+        Self::edges_with_actions(self.ingoing.get(location), actions)
     }
 
     pub fn precedeeing_locations(
@@ -304,19 +610,27 @@ impl Automaton {
     ) -> Vec<Location> {
         let mut result = Vec::new();
         for edge in self.ingoing_edges(location, actions) {
-            result.push(edge.source);
+            result.push(edge.source.clone());
         }
-        return result;
+        result
     }
 
-    pub fn outgoing_edges(&self, location: &Location, actions: &HashSet<Channel>) -> Vec<Edge> {
-        let mut result = Vec::new();
-        for edge in self.edges.clone() {
-            if edge.source == *location && actions.contains(&edge.action) {
-                result.push(edge.clone());
-            }
-        }
-        return result;
+    pub fn outgoing_edges<'a>(
+        &'a self,
+        location: &Location,
+        actions: &'a HashSet<Channel>,
+    ) -> impl Iterator<Item = &'a Edge> + 'a {
+        Self::edges_with_actions(self.outgoing.get(location), actions)
+    }
+
+    fn edges_with_actions<'a>(
+        edges: Option<&'a Vec<Edge>>,
+        actions: &'a HashSet<Channel>,
+    ) -> impl Iterator<Item = &'a Edge> + 'a {
+        edges
+            .into_iter()
+            .flatten()
+            .filter(move |edge| actions.contains(&edge.action))
     }
 
     pub fn sucedeeing_locations(
@@ -326,180 +640,1158 @@ impl Automaton {
     ) -> Vec<Location> {
         let mut result = Vec::new();
         for edge in self.outgoing_edges(location, actions) {
-            result.push(edge.target);
+            result.push(edge.target.clone());
         }
-        return result;
+        result
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::collections::HashSet;
+    /// Every edge enabled in the state across all actions, along with the state
+    /// reached by executing it, or the first error of evaluating a guard or update.
+    pub fn enabled_edges(&self, state: &State) -> Result<Vec<(Edge, State)>, LangError> {
+        let mut result = Vec::new();
+        for edge in self.outgoing_edges(&state.location, &self.actions) {
+            if self.try_is_edge_enabled(edge, state)? {
+                result.push((edge.clone(), edge.execute(state)?));
+            }
+        }
+        Ok(result)
+    }
 
-    use crate::{
-        automatom::{
-            channel::Channel, edge::Edge, error::Error, guard::Guard, invariant::Invariant,
-            location::Location, update::Update,
-        },
-        language::{
-            environment::Environment, expression::Expression, statement::Statement, value::Value,
-        },
-    };
+    /// The variables read by guards or invariants which no update assigns to,
+    /// such that they keep their initial value.
+    pub fn readonly_variables(&self) -> HashSet<String> {
+        let (read, written) = self.read_and_written_variables();
+        read.difference(&written).cloned().collect()
+    }
 
-    use super::Automaton;
+    /// The variables assigned to by updates which no guard or invariant reads,
+    /// such that they do not affect the behavior.
+    pub fn unused_variables(&self) -> HashSet<String> {
+        let (read, written) = self.read_and_written_variables();
+        written.difference(&read).cloned().collect()
+    }
 
-    macro_rules! assert_err {
-        ($result: ident, $err_type: pat_param) => {
-            assert!($result.is_err());
-            let err = $result.err().unwrap();
-            assert!(matches!(err, $err_type), "error = {}", err);
-        };
+    /// The variables whose values are used by any guard, invariant or update.
+    pub fn relevant_variables(&self) -> HashSet<String> {
+        let (mut read, _) = self.read_and_written_variables();
+        for edge in self.edges.iter() {
+            if let Some(update) = &edge.update.node {
+                read.extend(update.read_identifiers());
+            }
+        }
+        read
     }
 
-    macro_rules! assert_ok {
-        ($result: ident) => {
-            if $result.is_err() {
-                assert_eq!(
-                    $result.is_err(),
-                    false,
-                    "error = {}",
-                    $result.err().unwrap()
-                );
+    /// Maps every assigned variable to the variables its assigned values are
+    /// computed from, including those of the conditions the assignment is under.
+    pub fn variable_dependencies(&self) -> HashMap<String, HashSet<String>> {
+        let mut dependencies: HashMap<String, HashSet<String>> = HashMap::new();
+        for update in self
+            .edges
+            .iter()
+            .filter_map(|edge| edge.update.node.as_ref())
+        {
+            let mut flows: Vec<(String, Vec<String>)> = Vec::new();
+            VariableDependencies::flows(update, &[], &mut flows);
+            for (target, sources) in flows {
+                dependencies.entry(target).or_default().extend(sources);
             }
-        };
+        }
+        dependencies
     }
 
-    #[test]
-    fn automaton_new() {
-        let invariant = &Invariant::new_true();
-        let channel_in = Channel::new_input("in");
-        let channel_out = Channel::new_output("out");
-        let channels: HashSet<Channel> = HashSet::from([channel_in.clone(), channel_out.clone()]);
-        let guard = Guard::new_true();
-        let update = Update::new_pure();
-        let a = Location::new_initial("a", invariant);
-        let b = Location::new_normal("b", invariant);
-        let c = Location::new_normal("c", invariant);
-        let d = Location::new_normal("d", invariant);
-        let e = Location::new_normal("e", invariant);
-        let f = Location::new_normal("f", invariant);
-        let a_b = Edge::new(&a, &channel_in, &guard, &update, &b);
-        let b_c = Edge::new(&b, &channel_out, &guard, &update, &c);
-        let c_d = Edge::new(&c, &channel_in, &guard, &update, &d);
-        let c_e = Edge::new(&c, &channel_out, &guard, &update, &e);
-        let c_f = Edge::new(&c, &channel_in, &guard, &update, &f);
-        let edges = &HashSet::from([
-            a_b.clone(),
-            b_c.clone(),
-            c_d.clone(),
-            c_e.clone(),
-            c_f.clone(),
-        ]);
+    /// The automaton without the assignments to variables which can never
+    /// influence the criterion variables or any guard or invariant. The sliced
+    /// automaton has the same traces up to the values of the removed variables.
+    /// Fails if the sliced edges do not form a valid automaton.
+    pub fn slice(&self, criterion: &HashSet<String>) -> Result<Automaton, Error> {
+        let keep = VariableDependencies::new(self).influencing(criterion);
+        self.sliced(&keep, false)
+    }
 
-        let result = Automaton::new("my first automaton", edges, None);
-        assert_ok!(result);
+    /// The automaton with only the edges whose action is in `keep`. Locations
+    /// left without edges are dropped and the inputs and outputs are recomputed,
+    /// such that restricting to the inputs shows the input-only behavior. Fails
+    /// with `EmptyAutomaton` if no edges are left and `MissingInitialLocation`
+    /// if the initial location has no edges left.
+    pub fn restrict(&self, keep: &HashSet<Channel>) -> Result<Automaton, Error> {
+        let edges: HashSet<Edge> = self
+            .edges
+            .iter()
+            .filter(|edge| keep.contains(&edge.action))
+            .cloned()
+            .collect();
+        Automaton::new(&self.name, &edges, Some(&self.initial_environment))
+    }
 
-        let automaton = result.ok().unwrap();
-        assert!(automaton.get_initial_environment().is_empty());
-        assert_eq!(automaton.get_initial_location(), a);
-        assert!(automaton.get_locations().contains(&a));
-        assert!(automaton.get_locations().contains(&b));
-        assert!(automaton.get_locations().contains(&c));
-        assert!(automaton.get_locations().contains(&d));
-        assert!(automaton.get_locations().contains(&e));
-        assert!(automaton.get_locations().contains(&f));
-        assert_eq!(automaton.get_locations().len(), 6);
-        assert!(automaton.get_edges().contains(&a_b));
-        assert!(automaton.get_edges().contains(&b_c));
-        assert!(automaton.get_edges().contains(&c_d));
-        assert!(automaton.get_edges().contains(&c_e));
-        assert!(automaton.get_edges().contains(&c_f));
-        assert_eq!(automaton.get_edges().len(), 5);
-        assert!(automaton.get_actions().contains(&channel_in));
-        assert!(automaton.get_actions().contains(&channel_out));
-        assert_eq!(automaton.get_actions().len(), 2);
-        assert!(automaton.get_inputs().contains(&channel_in));
-        assert_eq!(automaton.get_inputs().len(), 1);
-        assert!(automaton.get_outputs().contains(&channel_out));
-        assert_eq!(automaton.get_outputs().len(), 1);
+    /// The union of the locations, edges and variables of the automata, which
+    /// must not share location names or variables. The location named as
+    /// `new_initial` becomes the only initial location and every other location
+    /// is normal. The union is validated as by `new`, so it fails if the actions
+    /// are not partitioned across both automata or `new_initial` is not one of
+    /// their locations.
+    pub fn disjoint_union(
+        &self,
+        other: &Automaton,
+        new_initial: &Location,
+    ) -> Result<Automaton, Error> {
+        let names: HashSet<String> = self.locations.iter().map(Location::get_name).collect();
+        if let Some(location) = other
+            .locations_sorted()
+            .into_iter()
+            .find(|location| names.contains(&location.get_name()))
+        {
+            return Err(Error::DuplicateLocationName {
+                source_location: Some(&(file!(), line!())),
+                automaton: self.name.clone(),
+                other: other.name.clone(),
+                name: location.get_name(),
+            });
+        }
+        if let Some((identifier, _)) = self
+            .initial_environment
+            .sorted_entries()
+            .into_iter()
+            .find(|(identifier, _)| other.initial_environment.contains(identifier))
+        {
+            return Err(Error::DuplicateVariableName {
+                source_location: Some(&(file!(), line!())),
+                automaton: self.name.clone(),
+                other: other.name.clone(),
+                identifier: identifier.to_string(),
+            });
+        }
+        let mut environment = self.initial_environment.clone();
+        environment.concat(&other.initial_environment);
 
-        let ingoing_edges_a = automaton.ingoing_edges(&a, &channels);
-        let preceding_locations_a = automaton.precedeeing_locations(&a, &channels);
-        let outgoing_edges_a = automaton.outgoing_edges(&a, &channels);
-        let sucedeeing_locations_a = automaton.sucedeeing_locations(&a, &channels);
-        assert_eq!(ingoing_edges_a.len(), 0);
-        assert_eq!(preceding_locations_a.len(), 0);
-        assert!(outgoing_edges_a.contains(&a_b));
-        assert_eq!(outgoing_edges_a.len(), 1);
-        assert!(sucedeeing_locations_a.contains(&b));
-        assert_eq!(sucedeeing_locations_a.len(), 1);
+        let initial = new_initial.get_name();
+        let edges: HashSet<Edge> = self
+            .edges
+            .iter()
+            .chain(other.edges.iter())
+            .map(|edge| Edge {
+                source: Self::with_initial(&edge.source, &initial),
+                target: Self::with_initial(&edge.target, &initial),
+                ..edge.clone()
+            })
+            .collect();
 
-        let ingoing_edges_b = automaton.ingoing_edges(&b, &channels);
-        let preceding_locations_b = automaton.precedeeing_locations(&b, &channels);
-        let outgoing_edges_b = automaton.outgoing_edges(&b, &channels);
-        let sucedeeing_locations_b = automaton.sucedeeing_locations(&b, &channels);
-        assert!(ingoing_edges_b.contains(&a_b));
-        assert_eq!(ingoing_edges_b.len(), 1);
-        assert!(preceding_locations_b.contains(&a));
-        assert_eq!(preceding_locations_b.len(), 1);
-        assert!(outgoing_edges_b.contains(&b_c));
-        assert_eq!(outgoing_edges_b.len(), 1);
-        assert!(sucedeeing_locations_b.contains(&c));
-        assert_eq!(sucedeeing_locations_b.len(), 1);
+        Automaton::new(
+            &format!("{}+{}", self.name, other.name),
+            &edges,
+            Some(&environment),
+        )
+    }
 
-        let ingoing_edges_c = automaton.ingoing_edges(&c, &channels);
-        let preceding_locations_c = automaton.precedeeing_locations(&c, &channels);
-        let outgoing_edges_c = automaton.outgoing_edges(&c, &channels);
-        let sucedeeing_locations_c = automaton.sucedeeing_locations(&c, &channels);
-        assert!(ingoing_edges_c.contains(&b_c));
-        assert_eq!(ingoing_edges_c.len(), 1);
-        assert!(preceding_locations_c.contains(&b));
-        assert_eq!(preceding_locations_c.len(), 1);
-        assert!(outgoing_edges_c.contains(&c_d));
-        assert!(outgoing_edges_c.contains(&c_e));
-        assert!(outgoing_edges_c.contains(&c_f));
-        assert_eq!(outgoing_edges_c.len(), 3);
-        assert!(sucedeeing_locations_c.contains(&d));
-        assert!(sucedeeing_locations_c.contains(&e));
-        assert!(sucedeeing_locations_c.contains(&f));
-        assert_eq!(sucedeeing_locations_c.len(), 3);
+    /// The automaton with every edge reversed and the location named as
+    /// `new_initial` as its only initial location, such that forward
+    /// reachability in it is backward reachability in this automaton. Guards
+    /// stay on the reversed edges. Updates cannot be inverted in general, so an
+    /// edge with an update is an `IrreversibleUpdate` error unless `allow_lossy`
+    /// is set, in which case all updates are dropped and the variables keep
+    /// their initial values.
+    pub fn reverse(&self, new_initial: &Location, allow_lossy: bool) -> Result<Automaton, Error> {
+        if !allow_lossy {
+            if let Some(edge) = self
+                .edges_sorted()
+                .into_iter()
+                .find(|edge| edge.update.node.is_some())
+            {
+                return Err(Error::IrreversibleUpdate {
+                    source_location: Some(&(file!(), line!())),
+                    automaton: self.name.clone(),
+                    edge: Box::new(edge),
+                });
+            }
+        }
 
-        let ingoing_edges_d = automaton.ingoing_edges(&d, &channels);
-        let preceding_locations_d = automaton.precedeeing_locations(&d, &channels);
-        let outgoing_edges_d = automaton.outgoing_edges(&d, &channels);
-        let sucedeeing_locations_d = automaton.sucedeeing_locations(&d, &channels);
-        assert!(ingoing_edges_d.contains(&c_d));
-        assert_eq!(ingoing_edges_d.len(), 1);
-        assert!(preceding_locations_d.contains(&c));
-        assert_eq!(preceding_locations_d.len(), 1);
-        assert_eq!(outgoing_edges_d.len(), 0);
-        assert_eq!(sucedeeing_locations_d.len(), 0);
+        let initial = new_initial.get_name();
+        let edges: HashSet<Edge> = self
+            .edges
+            .iter()
+            .map(|edge| Edge {
+                source: Self::with_initial(&edge.target, &initial),
+                target: Self::with_initial(&edge.source, &initial),
+                update: Update::empty(),
+                ..edge.clone()
+            })
+            .collect();
 
-        let ingoing_edges_e = automaton.ingoing_edges(&e, &channels);
-        let preceding_locations_e = automaton.precedeeing_locations(&e, &channels);
-        let outgoing_edges_e = automaton.outgoing_edges(&e, &channels);
-        let sucedeeing_locations_e = automaton.sucedeeing_locations(&e, &channels);
-        assert!(ingoing_edges_e.contains(&c_e));
-        assert_eq!(ingoing_edges_e.len(), 1);
-        assert!(preceding_locations_e.contains(&c));
-        assert_eq!(preceding_locations_e.len(), 1);
-        assert_eq!(outgoing_edges_e.len(), 0);
-        assert_eq!(sucedeeing_locations_e.len(), 0);
+        Automaton::new(&self.name, &edges, Some(&self.initial_environment))
+    }
 
-        let ingoing_edges_f = automaton.ingoing_edges(&f, &channels);
-        let preceding_locations_f = automaton.precedeeing_locations(&f, &channels);
-        let outgoing_edges_f = automaton.outgoing_edges(&f, &channels);
-        let sucedeeing_locations_f = automaton.sucedeeing_locations(&f, &channels);
-        assert!(ingoing_edges_f.contains(&c_f));
-        assert_eq!(ingoing_edges_f.len(), 1);
-        assert!(preceding_locations_f.contains(&c));
-        assert_eq!(preceding_locations_f.len(), 1);
-        assert_eq!(outgoing_edges_f.len(), 0);
-        assert_eq!(sucedeeing_locations_f.len(), 0);
+    /// The location as an initial location if it has the given name and as a
+    /// normal location otherwise.
+    fn with_initial(location: &Location, initial: &str) -> Location {
+        match location {
+            Location::Normal { name, invariant } | Location::Initial { name, invariant } => {
+                match name == initial {
+                    true => Location::new_initial(name, invariant),
+                    false => Location::new_normal(name, invariant),
+                }
+            }
+            _ => location.clone(),
+        }
     }
 
-    #[test]
-    fn automaton_new_missing_identifiers_in_edge_guard() {
+    /// The automaton reduced to the variables of interest and those which may
+    /// affect them. Assignments to other variables are removed, and so are the
+    /// conjuncts of guards and invariants reading other variables. This may
+    /// enable behavior, so the sliced automaton over-approximates the original.
+    pub fn slice_to_variables(&self, variables: &HashSet<String>) -> Result<Automaton, Error> {
+        let keep = VariableDependencies::new(self).affecting(variables);
+        self.sliced(&keep, true)
+    }
+
+    fn sliced(&self, keep: &HashSet<String>, drop_conditions: bool) -> Result<Automaton, Error> {
+        let location = |location: &Location| -> Location {
+            match location {
+                Location::Normal { name, invariant } if drop_conditions => Location::new_normal(
+                    name,
+                    &Invariant::new(&retain_conjuncts(&invariant.node, keep)),
+                ),
+                Location::Initial { name, invariant } if drop_conditions => Location::new_initial(
+                    name,
+                    &Invariant::new(&retain_conjuncts(&invariant.node, keep)),
+                ),
+                _ => location.clone(),
+            }
+        };
+        let edges: HashSet<Edge> = self
+            .edges
+            .iter()
+            .map(|edge| Edge {
+                source: location(&edge.source),
+                guard: if drop_conditions {
+                    Guard::new(&retain_conjuncts(&edge.guard.node, keep))
+                } else {
+                    edge.guard.clone()
+                },
+                target: location(&edge.target),
+                update: match &edge.update.node {
+                    Some(update) => match retain_assignments(update, keep) {
+                        Some(update) => Update::new(&update),
+                        None => Update::empty(),
+                    },
+                    None => Update::empty(),
+                },
+                ..edge.clone()
+            })
+            .collect();
+
+        Automaton::new(&self.name, &edges, Some(&self.initial_environment))
+    }
+
+    /// The automaton where the guard of every edge is conjoined with the
+    /// invariant of its source and the weakest precondition of its update with
+    /// respect to the invariant of its target. Exploring it with `successors`
+    /// only reaches states satisfying their invariants, and edges which can
+    /// never be taken without violating an invariant may get a literal false
+    /// guard.
+    pub fn strengthen_guards(&self) -> Automaton {
+        let invariant = |location: &Location| -> Expression {
+            match location {
+                Location::Normal { invariant, .. }
+                | Location::Initial { invariant, .. }
+                | Location::Conjunction { invariant, .. } => invariant.node.clone(),
+                Location::Inconsistent { .. } => Expression::new_boolean(&false),
+                Location::Universal { .. } => Expression::new_boolean(&true),
+            }
+        };
+        let edges: HashSet<Edge> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                let postcondition = invariant(&edge.target);
+                let precondition = match &edge.update.node {
+                    Some(update) => update.weakest_precondition(&postcondition),
+                    None => postcondition,
+                };
+                Edge {
+                    guard: Guard::new(&Expression::new_conjunction(&[
+                        edge.guard.node.clone(),
+                        invariant(&edge.source),
+                        precondition,
+                    ])),
+                    ..edge.clone()
+                }
+            })
+            .collect();
+
+        match Automaton::new(&self.name, &edges, Some(&self.initial_environment)) {
+            Ok(automaton) => automaton,
+            Err(error) => panic!(
+                "The strengthening of {} is not a valid automaton: {}",
+                self.name, error
+            ),
+        }
+    }
+
+    /// Reports declared variables which are never used, variables which are
+    /// written but never read, locations without outgoing edges and edges with
+    /// a literally false guard, in that order.
+    pub fn lints(&self) -> Vec<Lint> {
+        let mut lints: Vec<Lint> = Vec::new();
+        let read = self.relevant_variables();
+        let (_, written) = self.read_and_written_variables();
+        let edges = self.edges_sorted();
+
+        let declared: Vec<String> = self
+            .initial_environment
+            .differences(&Environment::new_empty())
+            .into_iter()
+            .map(|(identifier, _, _)| identifier)
+            .collect();
+        for identifier in declared {
+            if !read.contains(&identifier) && !written.contains(&identifier) {
+                lints.push(Lint::UnreadVariable { identifier });
+            }
+        }
+        let mut write_only: Vec<&String> = written.difference(&read).collect();
+        write_only.sort();
+        for identifier in write_only {
+            let writers = edges
+                .iter()
+                .filter(|edge| {
+                    edge.update
+                        .node
+                        .as_ref()
+                        .is_some_and(|update| update.assigned_identifiers().contains(identifier))
+                })
+                .cloned()
+                .collect();
+            lints.push(Lint::WriteOnlyVariable {
+                identifier: identifier.clone(),
+                edges: writers,
+            });
+        }
+
+        let mut deadlocking: Vec<&Location> = self
+            .locations
+            .iter()
+            .filter(|location| !self.outgoing.contains_key(location))
+            .collect();
+        deadlocking.sort_by_key(|location| location.get_name());
+        for location in deadlocking {
+            lints.push(Lint::NoOutgoingEdges {
+                location: location.clone(),
+            });
+        }
+
+        for edge in edges {
+            if edge.guard.node == Expression::new_boolean(&false) {
+                lints.push(Lint::FalseGuard {
+                    edge: Box::new(edge),
+                });
+            }
+        }
+        lints
+    }
+
+    /// The lints of the automaton followed by the channels which the interface
+    /// does not have in the opposite direction, such that they never synchronize.
+    pub fn lints_with_interface(&self, interface: &Automaton) -> Vec<Lint> {
+        let mut lints = self.lints();
+        let mut unmatched: Vec<&Channel> = self
+            .actions
+            .iter()
+            .filter(|action| {
+                !interface
+                    .get_actions()
+                    .iter()
+                    .any(|other| other == *action && !other.same_direction(action))
+            })
+            .collect();
+        unmatched.sort_by_key(|channel| channel.to_string());
+        for channel in unmatched {
+            lints.push(Lint::UnmatchedChannel {
+                channel: channel.clone(),
+            });
+        }
+        lints
+    }
+
+    /// The variables read by guards and invariants, and those assigned by updates.
+    fn read_and_written_variables(&self) -> (HashSet<String>, HashSet<String>) {
+        let mut read: HashSet<String> = HashSet::new();
+        let mut written: HashSet<String> = HashSet::new();
+        for edge in self.edges.iter() {
+            read.extend(edge.guard.node.identifiers());
+            if let Some(update) = &edge.update.node {
+                written.extend(update.assigned_identifiers());
+            }
+        }
+        for location in self.locations.iter() {
+            if let Location::Normal { invariant, .. }
+            | Location::Initial { invariant, .. }
+            | Location::Conjunction { invariant, .. } = location
+            {
+                read.extend(invariant.node.identifiers());
+            }
+        }
+        (read, written)
+    }
+
+    /// True if no location has two outgoing edges with the same action, where an
+    /// input and an output of the same name are different actions.
+    pub fn is_deterministic(&self) -> bool {
+        self.outgoing.values().all(|edges| {
+            let actions: HashSet<DirectedChannel> =
+                edges.iter().map(|edge| edge.action.directed()).collect();
+            actions.len() == edges.len()
+        })
+    }
+
+    /// Performs the subset construction over the locations reachable from the
+    /// initial location. The edges leaving a set of locations by an action are
+    /// split by which of their guards hold, such that every combination of
+    /// enabled edges gets an edge to the set of their targets. The edges of such
+    /// a combination must have equal updates. A set allows the states which one
+    /// of its locations allows. Sets are named like "{a,b}".
+    pub fn determinize(&self) -> Result<Automaton, Error> {
+        let subset_location = |subset: &Vec<Location>, initial: bool| -> Location {
+            let name = format!(
+                "{{{}}}",
+                subset
+                    .iter()
+                    .map(|location| location.get_name())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            );
+            let mut invariant: Option<Expression> = None;
+            for location in subset {
+                let operand = match location {
+                    Location::Normal { invariant, .. }
+                    | Location::Initial { invariant, .. }
+                    | Location::Conjunction { invariant, .. } => invariant.node.clone(),
+                    Location::Inconsistent { .. } => continue,
+                    Location::Universal { .. } => Expression::new_boolean(&true),
+                };
+                invariant = Some(match invariant {
+                    Some(lhs) => Expression::new_logical_or(&lhs, &operand).simplify(),
+                    None => operand,
+                });
+            }
+            // Only a set of inconsistent locations allows no state
+            let invariant =
+                Invariant::new(&invariant.unwrap_or_else(|| Expression::new_boolean(&false)));
+            if initial {
+                Location::new_initial(&name, &invariant)
+            } else {
+                Location::new_normal(&name, &invariant)
+            }
+        };
+
+        let actions = self.actions_sorted();
+
+        let initial = vec![self.initial.clone()];
+        let mut subsets: Vec<Vec<Location>> = vec![initial.clone()];
+        let mut worklist: VecDeque<Vec<Location>> = VecDeque::from([initial.clone()]);
+        let mut edges: HashSet<Edge> = HashSet::new();
+        while let Some(subset) = worklist.pop_front() {
+            let source = subset_location(&subset, subset == initial);
+            for action in actions.iter() {
+                let single = HashSet::from([(*action).clone()]);
+                let merged: Vec<&Edge> = subset
+                    .iter()
+                    .flat_map(|location| self.outgoing_edges(location, &single))
+                    .filter(|edge| edge.action.same_direction(action))
+                    .collect();
+
+                // Every combination of enabled edges, dropping those whose guard never holds
+                let never = Expression::new_boolean(&false);
+                let mut combinations: Vec<(Expression, Vec<&Edge>)> =
+                    vec![(Expression::new_boolean(&true), Vec::new())];
+                for edge in merged.iter() {
+                    let guard = edge.guard.node.parenthesized_if_binary();
+                    let mut split = Vec::new();
+                    for (combined, enabled) in combinations {
+                        let with = Expression::new_logical_and(&combined, &guard).simplify();
+                        if with.implies(&never) != Some(true) {
+                            let mut enabled = enabled.clone();
+                            enabled.push(*edge);
+                            split.push((with, enabled));
+                        }
+                        let without = Expression::new_logical_and(
+                            &combined,
+                            &Expression::new_logical_not(&guard),
+                        )
+                        .simplify();
+                        if without.implies(&never) != Some(true) {
+                            split.push((without, enabled));
+                        }
+                    }
+                    combinations = split;
+                }
+
+                for (guard, enabled) in combinations {
+                    let Some(first) = enabled.first() else {
+                        continue;
+                    };
+                    if enabled.iter().any(|edge| edge.update != first.update) {
+                        return Err(Error::NondeterministicUpdateConflict {
+                            source_location: Some(&(file!(), line!())),
+                            automaton: self.name.clone(),
+                            locations: source.get_name(),
+                            action: (*action).clone(),
+                        });
+                    }
+
+                    let mut targets: Vec<Location> = Vec::new();
+                    for edge in enabled.iter() {
+                        if !targets.contains(&edge.target) {
+                            targets.push(edge.target.clone());
+                        }
+                    }
+                    targets.sort_by_key(|location| location.get_name());
+
+                    let target = subset_location(&targets, targets == initial);
+                    edges.insert(Edge::new(
+                        &source,
+                        action,
+                        &Guard::new(&guard),
+                        &first.update,
+                        &target,
+                    ));
+                    if !subsets.contains(&targets) {
+                        subsets.push(targets.clone());
+                        worklist.push_back(targets);
+                    }
+                }
+            }
+        }
+
+        Automaton::new(&self.name, &edges, Some(&self.initial_environment))
+    }
+
+    /// Checks that the automata can be composed, which requires that no channel
+    /// is an output of both.
+    pub fn is_composable_with(&self, other: &Automaton) -> Result<(), Error> {
+        let violating: HashSet<Channel> =
+            self.outputs.intersection(&other.outputs).cloned().collect();
+        if violating.is_empty() {
+            return Ok(());
+        }
+        Err(Error::OverlappingOutputs {
+            source_location: Some(&(file!(), line!())),
+            automaton: self.name.clone(),
+            other: other.name.clone(),
+            violating,
+        })
+    }
+
+    /// True if the automaton has no behavior, that is if it deadlocks in its
+    /// initial state or every transition it can take leads to an inconsistent location.
+    pub fn is_empty_behavior(&self) -> bool {
+        !TransitionIterator::new(&self.actions, self).any(|transition| {
+            let target = transition.target;
+            matches!(
+                self.is_location_consistent(&target.location, &target.environment),
+                Ok(true)
+            )
+        })
+    }
+
+    /// The reachable states whose invariant does not hold, which indicates a
+    /// modelling error as nothing prevents the automaton from entering them.
+    pub fn locations_with_violated_invariants(&self) -> Vec<(Location, State)> {
+        self.reachable_states(&self.actions)
+            .into_iter()
+            .filter(|state| {
+                matches!(
+                    self.is_location_consistent(&state.location, &state.environment),
+                    Ok(false)
+                )
+            })
+            .map(|state| (state.location.clone(), state))
+            .collect()
+    }
+
+    /// The values each variable takes across all reachable states.
+    pub fn variable_value_domains(&self) -> HashMap<String, HashSet<Value>> {
+        let mut domains: HashMap<String, HashSet<Value>> = HashMap::new();
+        for state in self.reachable_states(&self.actions) {
+            for (identifier, value) in state.environment.sorted_entries() {
+                domains
+                    .entry(identifier.to_string())
+                    .or_default()
+                    .insert(value.clone());
+            }
+        }
+        domains
+    }
+
+    /// The reachable states in which both an input and an output edge with the
+    /// same name are enabled, along with that name, sorted by state and name.
+    /// `new` rejects automata whose inputs and outputs share a name, so this is
+    /// only non-empty for automata assembled without that check.
+    pub fn conflicting_actions(&self) -> Vec<(State, String)> {
+        let mut conflicts = Vec::new();
+        for state in self.reachable_states(&self.actions) {
+            let mut directions: HashMap<&str, (bool, bool)> = HashMap::new();
+            for edge in self.outgoing_edges(&state.location, &self.actions) {
+                if self.is_edge_enabled(edge, &state) {
+                    let entry = directions.entry(edge.action.name()).or_default();
+                    if edge.action.is_input() {
+                        entry.0 = true;
+                    } else {
+                        entry.1 = true;
+                    }
+                }
+            }
+            for (name, (input, output)) in directions {
+                if input && output {
+                    conflicts.push((state.clone(), String::from(name)));
+                }
+            }
+        }
+        conflicts.sort();
+        conflicts
+    }
+
+    /// Counts the locations, edges, channels and variables of the automaton
+    /// without exploring its state space.
+    pub fn statistics(&self) -> AutomatonStats {
+        let mut statistics = AutomatonStats {
+            inputs: self.inputs.len(),
+            outputs: self.outputs.len(),
+            variables: self.initial_environment.count(),
+            state_space_bound: static_state_space_bound(self),
+            ..Default::default()
+        };
+        for location in self.locations.iter() {
+            match location {
+                Location::Normal { .. } => statistics.normal_locations += 1,
+                Location::Initial { .. } => statistics.initial_locations += 1,
+                Location::Conjunction { .. } => statistics.conjunction_locations += 1,
+                Location::Inconsistent { .. } => statistics.inconsistent_locations += 1,
+                Location::Universal { .. } => statistics.universal_locations += 1,
+            }
+        }
+        for edge in self.edges.iter() {
+            *statistics
+                .edges_per_action
+                .entry(edge.action.to_string())
+                .or_default() += 1;
+            statistics.max_guard_depth = statistics
+                .max_guard_depth
+                .max(AutomatonStats::depth(&edge.guard.node));
+        }
+        statistics
+    }
+
+    /// The statistics including the number of reachable states and the diameter,
+    /// which requires exploring the state space.
+    pub fn explored_statistics(&self) -> AutomatonStats {
+        let mut depths: HashMap<State, usize> = HashMap::from([(self.get_initial_state(), 0)]);
+        let mut frontier: VecDeque<State> = VecDeque::from([self.get_initial_state()]);
+        while let Some(state) = frontier.pop_front() {
+            let depth = depths[&state];
+            for transition in self.timed_transitions(&state, &self.actions) {
+                let successor = transition.target;
+                if !depths.contains_key(&successor) {
+                    depths.insert(successor.clone(), depth + 1);
+                    frontier.push_back(successor);
+                }
+            }
+        }
+
+        AutomatonStats {
+            reachable_states: Some(depths.len()),
+            diameter: depths.values().max().copied(),
+            ..self.statistics()
+        }
+    }
+
+    /// Parses an automaton from text, see `dsl::parse` for the format, and
+    /// validates it as `new` does.
+    pub fn parse(input: &str) -> Result<Automaton, Error> {
+        dsl::parse(input)
+    }
+
+    /// Prints the automaton in the text format read by `parse`.
+    pub fn to_dsl(&self) -> String {
+        dsl::print(self)
+    }
+
+    /// True if the automata have the same locations, edges, channels and
+    /// initial data, regardless of their names.
+    pub fn structurally_equal(&self, other: &Automaton) -> bool {
+        self.structurally_eq(other, false)
+    }
+
+    /// As `structurally_equal`, but invariants of locations, including those of
+    /// the sources and targets of edges, are ignored if `ignore_invariants` is
+    /// set, such that only the control structure is compared.
+    pub fn structurally_eq(&self, other: &Automaton, ignore_invariants: bool) -> bool {
+        let location = |location: &Location| -> Location {
+            match ignore_invariants {
+                true => location.without_invariants(),
+                false => location.clone(),
+            }
+        };
+        let locations = |automaton: &Automaton| -> HashSet<Location> {
+            automaton.locations.iter().map(location).collect()
+        };
+        let edges = |automaton: &Automaton| -> HashSet<Edge> {
+            automaton
+                .edges
+                .iter()
+                .map(|edge| Edge {
+                    source: location(&edge.source),
+                    target: location(&edge.target),
+                    ..edge.clone()
+                })
+                .collect()
+        };
+        let directed = |actions: &HashSet<Channel>| -> HashSet<DirectedChannel> {
+            actions.iter().map(|action| action.directed()).collect()
+        };
+        location(&self.initial) == location(&other.initial)
+            && self.initial_environment == other.initial_environment
+            && locations(self) == locations(other)
+            && directed(&self.actions) == directed(&other.actions)
+            && edges(self) == edges(other)
+    }
+
+    /// Searches for a bijection from the location names of this automaton to
+    /// those of `other` which maps the initial location to the initial location
+    /// and preserves invariants along with the action, guard and update of
+    /// every edge. Edge ids are ignored. Candidates are pruned by comparing the
+    /// invariant and the labels of the ingoing and outgoing edges of locations
+    /// before backtracking over the remaining choices.
+    pub fn isomorphic(&self, other: &Automaton) -> Option<HashMap<String, String>> {
+        if self.locations.len() != other.locations.len()
+            || self.edges.len() != other.edges.len()
+            || self.initial_environment != other.initial_environment
+        {
+            return None;
+        }
+
+        let lhs = self.locations_sorted();
+        let rhs = other.locations_sorted();
+        let lhs_adjacency = self.labelled_adjacency(&lhs);
+        let rhs_adjacency = other.labelled_adjacency(&rhs);
+        let lhs_signatures = Self::location_signatures(&lhs, &lhs_adjacency);
+        let rhs_signatures = Self::location_signatures(&rhs, &rhs_adjacency);
+
+        let candidates: Vec<Vec<usize>> = lhs_signatures
+            .iter()
+            .map(|signature| {
+                (0..rhs.len())
+                    .filter(|&index| rhs_signatures[index] == *signature)
+                    .collect()
+            })
+            .collect();
+
+        let mut mapping: Vec<usize> = Vec::with_capacity(lhs.len());
+        let mut used = vec![false; rhs.len()];
+        let no_edges = Vec::new();
+        let consistent = |mapping: &Vec<usize>, candidate: usize| -> bool {
+            let current = mapping.len();
+            (0..=current).all(|mapped| {
+                let image = if mapped == current {
+                    candidate
+                } else {
+                    mapping[mapped]
+                };
+                let labels = |adjacency: &HashMap<(usize, usize), Vec<EdgeLabel>>, key| {
+                    adjacency.get(&key).unwrap_or(&no_edges).clone()
+                };
+                labels(&lhs_adjacency, (current, mapped))
+                    == labels(&rhs_adjacency, (candidate, image))
+                    && labels(&lhs_adjacency, (mapped, current))
+                        == labels(&rhs_adjacency, (image, candidate))
+            })
+        };
+
+        // Depth-first search where each level maps the next location of lhs
+        let mut choices: Vec<usize> = vec![0];
+        while let Some(choice) = choices.last_mut() {
+            let current = mapping.len();
+            if current == lhs.len() {
+                return Some(
+                    mapping
+                        .iter()
+                        .enumerate()
+                        .map(|(from, &to)| (lhs[from].get_name(), rhs[to].get_name()))
+                        .collect(),
+                );
+            }
+            let next = candidates[current][*choice..]
+                .iter()
+                .position(|&candidate| !used[candidate] && consistent(&mapping, candidate));
+            match next {
+                Some(offset) => {
+                    let candidate = candidates[current][*choice + offset];
+                    *choice += offset + 1;
+                    used[candidate] = true;
+                    mapping.push(candidate);
+                    choices.push(0);
+                }
+                None => {
+                    choices.pop();
+                    if let Some(candidate) = mapping.pop() {
+                        used[candidate] = false;
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// The sorted labels of the edges between every pair of location indices.
+    fn labelled_adjacency(
+        &self,
+        locations: &[Location],
+    ) -> HashMap<(usize, usize), Vec<EdgeLabel>> {
+        let indices: HashMap<&Location, usize> = locations
+            .iter()
+            .enumerate()
+            .map(|(index, location)| (location, index))
+            .collect();
+        let mut adjacency: HashMap<(usize, usize), Vec<EdgeLabel>> = HashMap::new();
+        for edge in self.edges.iter() {
+            adjacency
+                .entry((indices[&edge.source], indices[&edge.target]))
+                .or_default()
+                .push((
+                    edge.action.name().to_string(),
+                    edge.action.is_input(),
+                    edge.guard.clone(),
+                    edge.update.clone(),
+                ));
+        }
+        for labels in adjacency.values_mut() {
+            labels.sort();
+        }
+        adjacency
+    }
+
+    /// Summarises each location by its variant, invariant and the labels of its
+    /// outgoing and ingoing edges, which any isomorphism must preserve.
+    fn location_signatures(
+        locations: &[Location],
+        adjacency: &HashMap<(usize, usize), Vec<EdgeLabel>>,
+    ) -> Vec<LocationSignature> {
+        let mut signatures: Vec<LocationSignature> = locations
+            .iter()
+            .map(|location| {
+                let invariant = match location {
+                    Location::Normal { invariant, .. }
+                    | Location::Initial { invariant, .. }
+                    | Location::Conjunction { invariant, .. } => Some(invariant.clone()),
+                    Location::Inconsistent { .. } | Location::Universal { .. } => None,
+                };
+                (
+                    std::mem::discriminant(location),
+                    invariant,
+                    Vec::new(),
+                    Vec::new(),
+                )
+            })
+            .collect();
+        for ((source, target), labels) in adjacency {
+            signatures[*source].2.extend(labels.iter().cloned());
+            signatures[*target].3.extend(labels.iter().cloned());
+        }
+        for signature in signatures.iter_mut() {
+            signature.2.sort();
+            signature.3.sort();
+        }
+        signatures
+    }
+
+    /// True if the automaton can perform the word from its initial state.
+    pub fn accepts(&self, word: &[Channel]) -> bool {
+        self.run(word).is_feasible()
+    }
+
+    /// Enumerates every word, including the empty word, of at most the given
+    /// length which the automaton can perform from its initial state.
+    pub fn words_up_to(&self, length: usize) -> HashSet<Vec<Channel>> {
+        let mut words: HashSet<Vec<Channel>> = HashSet::from([Vec::new()]);
+        let mut frontier: HashSet<(Vec<Channel>, State)> =
+            HashSet::from([(Vec::new(), self.get_initial_state())]);
+
+        for _ in 0..length {
+            let mut next: HashSet<(Vec<Channel>, State)> = HashSet::new();
+            for (word, state) in frontier {
+                for transition in self.transitions(&state, &self.actions) {
+                    let mut extended = word.clone();
+                    extended.push(transition.action);
+                    words.insert(extended.clone());
+                    next.insert((extended, transition.target));
+                }
+            }
+            frontier = next;
+        }
+
+        words
+    }
+}
+
+impl Display for Automaton {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Automaton {}\n", self.name))?;
+        f.write_fmt(format_args!("Initial: {}\n", self.initial))?;
+        f.write_str("Locations:\n")?;
+        for location in self.locations_sorted() {
+            f.write_fmt(format_args!("  {}\n", location))?;
+        }
+        f.write_str("Edges:")?;
+        for edge in self.edges_sorted() {
+            f.write_fmt(format_args!("\n  {}", edge))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::{
+        automatom::{
+            channel::Channel, edge::Edge, error::Error, fixtures::DIRECTED_DIAMOND, guard::Guard,
+            invariant::Invariant, location::Location, update::Update,
+        },
+        language::{
+            environment::Environment,
+            expression::{BinaryOperator, Expression},
+            lang_type::LangType,
+            statement::Statement,
+            value::Value,
+        },
+        transition_system::{
+            composition::Composition, reachability::trace_to_location, state::State,
+            transition_system::TransitionSystem,
+        },
+    };
+
+    use super::Automaton;
+
+    macro_rules! assert_err {
+        ($result: ident, $err_type: pat_param) => {
+            assert!($result.is_err());
+            let err = $result.err().unwrap();
+            assert!(matches!(err, $err_type), "error = {}", err);
+        };
+    }
+
+    macro_rules! assert_ok {
+        ($result: ident) => {
+            if $result.is_err() {
+                assert_eq!(
+                    $result.is_err(),
+                    false,
+                    "error = {}",
+                    $result.err().unwrap()
+                );
+            }
+        };
+    }
+
+    #[test]
+    fn automaton_new() {
+        let invariant = &Invariant::new_true();
+        let channel_in = Channel::new_input("in");
+        let channel_out = Channel::new_output("out");
+        let channels: HashSet<Channel> = HashSet::from([channel_in.clone(), channel_out.clone()]);
+        let guard = Guard::new_true();
+        let update = Update::new_pure();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let d = Location::new_normal("d", invariant);
+        let e = Location::new_normal("e", invariant);
+        let f = Location::new_normal("f", invariant);
+        let a_b = Edge::new(&a, &channel_in, &guard, &update, &b);
+        let b_c = Edge::new(&b, &channel_out, &guard, &update, &c);
+        let c_d = Edge::new(&c, &channel_in, &guard, &update, &d);
+        let c_e = Edge::new(&c, &channel_out, &guard, &update, &e);
+        let c_f = Edge::new(&c, &channel_in, &guard, &update, &f);
+        let edges = &HashSet::from([
+            a_b.clone(),
+            b_c.clone(),
+            c_d.clone(),
+            c_e.clone(),
+            c_f.clone(),
+        ]);
+
+        let result = Automaton::new("my first automaton", edges, None);
+        assert_ok!(result);
+
+        let automaton = result.ok().unwrap();
+        assert!(automaton.get_initial_environment().is_empty());
+        assert_eq!(automaton.get_initial_location(), &a);
+        assert!(automaton.get_locations().contains(&a));
+        assert!(automaton.get_locations().contains(&b));
+        assert!(automaton.get_locations().contains(&c));
+        assert!(automaton.get_locations().contains(&d));
+        assert!(automaton.get_locations().contains(&e));
+        assert!(automaton.get_locations().contains(&f));
+        assert_eq!(automaton.get_locations().len(), 6);
+        assert!(automaton.get_edges().contains(&a_b));
+        assert!(automaton.get_edges().contains(&b_c));
+        assert!(automaton.get_edges().contains(&c_d));
+        assert!(automaton.get_edges().contains(&c_e));
+        assert!(automaton.get_edges().contains(&c_f));
+        assert_eq!(automaton.get_edges().len(), 5);
+        assert!(automaton.get_actions().contains(&channel_in));
+        assert!(automaton.get_actions().contains(&channel_out));
+        assert_eq!(automaton.get_actions().len(), 2);
+        assert!(automaton.get_inputs().contains(&channel_in));
+        assert_eq!(automaton.get_inputs().len(), 1);
+        assert!(automaton.get_outputs().contains(&channel_out));
+        assert_eq!(automaton.get_outputs().len(), 1);
+
+        let ingoing_edges_a: Vec<&Edge> = automaton.ingoing_edges(&a, &channels).collect();
+        let preceding_locations_a = automaton.precedeeing_locations(&a, &channels);
+        let outgoing_edges_a: Vec<&Edge> = automaton.outgoing_edges(&a, &channels).collect();
+        let sucedeeing_locations_a = automaton.sucedeeing_locations(&a, &channels);
+        assert_eq!(ingoing_edges_a.len(), 0);
+        assert_eq!(preceding_locations_a.len(), 0);
+        assert!(outgoing_edges_a.contains(&&a_b));
+        assert_eq!(outgoing_edges_a.len(), 1);
+        assert!(sucedeeing_locations_a.contains(&b));
+        assert_eq!(sucedeeing_locations_a.len(), 1);
+
+        let ingoing_edges_b: Vec<&Edge> = automaton.ingoing_edges(&b, &channels).collect();
+        let preceding_locations_b = automaton.precedeeing_locations(&b, &channels);
+        let outgoing_edges_b: Vec<&Edge> = automaton.outgoing_edges(&b, &channels).collect();
+        let sucedeeing_locations_b = automaton.sucedeeing_locations(&b, &channels);
+        assert!(ingoing_edges_b.contains(&&a_b));
+        assert_eq!(ingoing_edges_b.len(), 1);
+        assert!(preceding_locations_b.contains(&a));
+        assert_eq!(preceding_locations_b.len(), 1);
+        assert!(outgoing_edges_b.contains(&&b_c));
+        assert_eq!(outgoing_edges_b.len(), 1);
+        assert!(sucedeeing_locations_b.contains(&c));
+        assert_eq!(sucedeeing_locations_b.len(), 1);
+
+        let ingoing_edges_c: Vec<&Edge> = automaton.ingoing_edges(&c, &channels).collect();
+        let preceding_locations_c = automaton.precedeeing_locations(&c, &channels);
+        let outgoing_edges_c: Vec<&Edge> = automaton.outgoing_edges(&c, &channels).collect();
+        let sucedeeing_locations_c = automaton.sucedeeing_locations(&c, &channels);
+        assert!(ingoing_edges_c.contains(&&b_c));
+        assert_eq!(ingoing_edges_c.len(), 1);
+        assert!(preceding_locations_c.contains(&b));
+        assert_eq!(preceding_locations_c.len(), 1);
+        assert!(outgoing_edges_c.contains(&&c_d));
+        assert!(outgoing_edges_c.contains(&&c_e));
+        assert!(outgoing_edges_c.contains(&&c_f));
+        assert_eq!(outgoing_edges_c.len(), 3);
+        assert!(sucedeeing_locations_c.contains(&d));
+        assert!(sucedeeing_locations_c.contains(&e));
+        assert!(sucedeeing_locations_c.contains(&f));
+        assert_eq!(sucedeeing_locations_c.len(), 3);
+
+        let ingoing_edges_d: Vec<&Edge> = automaton.ingoing_edges(&d, &channels).collect();
+        let preceding_locations_d = automaton.precedeeing_locations(&d, &channels);
+        let outgoing_edges_d: Vec<&Edge> = automaton.outgoing_edges(&d, &channels).collect();
+        let sucedeeing_locations_d = automaton.sucedeeing_locations(&d, &channels);
+        assert!(ingoing_edges_d.contains(&&c_d));
+        assert_eq!(ingoing_edges_d.len(), 1);
+        assert!(preceding_locations_d.contains(&c));
+        assert_eq!(preceding_locations_d.len(), 1);
+        assert_eq!(outgoing_edges_d.len(), 0);
+        assert_eq!(sucedeeing_locations_d.len(), 0);
+
+        let ingoing_edges_e: Vec<&Edge> = automaton.ingoing_edges(&e, &channels).collect();
+        let preceding_locations_e = automaton.precedeeing_locations(&e, &channels);
+        let outgoing_edges_e: Vec<&Edge> = automaton.outgoing_edges(&e, &channels).collect();
+        let sucedeeing_locations_e = automaton.sucedeeing_locations(&e, &channels);
+        assert!(ingoing_edges_e.contains(&&c_e));
+        assert_eq!(ingoing_edges_e.len(), 1);
+        assert!(preceding_locations_e.contains(&c));
+        assert_eq!(preceding_locations_e.len(), 1);
+        assert_eq!(outgoing_edges_e.len(), 0);
+        assert_eq!(sucedeeing_locations_e.len(), 0);
+
+        let ingoing_edges_f: Vec<&Edge> = automaton.ingoing_edges(&f, &channels).collect();
+        let preceding_locations_f = automaton.precedeeing_locations(&f, &channels);
+        let outgoing_edges_f: Vec<&Edge> = automaton.outgoing_edges(&f, &channels).collect();
+        let sucedeeing_locations_f = automaton.sucedeeing_locations(&f, &channels);
+        assert!(ingoing_edges_f.contains(&&c_f));
+        assert_eq!(ingoing_edges_f.len(), 1);
+        assert!(preceding_locations_f.contains(&c));
+        assert_eq!(preceding_locations_f.len(), 1);
+        assert_eq!(outgoing_edges_f.len(), 0);
+        assert_eq!(sucedeeing_locations_f.len(), 0);
+    }
+
+    #[test]
+    #[ignore = "timing test, run with --ignored in release mode"]
+    fn automaton_edge_lookup_does_not_scan_every_edge() {
+        use std::time::Instant;
+
+        // A cycle of a thousand locations with one edge leaving each
+        let invariant = &Invariant::new_true();
+        let channel = Channel::new_output("go");
+        let channels = HashSet::from([channel.clone()]);
+        let guard = Guard::new_true();
+        let update = Update::new_pure();
+        let mut locations = vec![Location::new_initial("l0", invariant)];
+        locations.extend((1..1000).map(|i| Location::new_normal(&format!("l{i}"), invariant)));
+        let edges: HashSet<Edge> = (0..locations.len())
+            .map(|i| {
+                let target = &locations[(i + 1) % locations.len()];
+                Edge::new(&locations[i], &channel, &guard, &update, target)
+            })
+            .collect();
+        let automaton = Automaton::new("cycle", &edges, None).ok().unwrap();
+        assert_eq!(automaton.get_edges().len(), 1000);
+
+        let start = Instant::now();
+        let mut indexed = 0;
+        for location in &locations {
+            indexed += automaton.outgoing_edges(location, &channels).count();
+            indexed += automaton.ingoing_edges(location, &channels).count();
+        }
+        let indexed_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut scanned = 0;
+        for location in &locations {
+            scanned += automaton
+                .get_edges()
+                .iter()
+                .filter(|edge| edge.source == *location && channels.contains(&edge.action))
+                .count();
+            scanned += automaton
+                .get_edges()
+                .iter()
+                .filter(|edge| edge.target == *location && channels.contains(&edge.action))
+                .count();
+        }
+        let scanned_time = start.elapsed();
+
+        assert_eq!(indexed, scanned);
+        // Looking up a location touches its own edges, not all thousand
+        assert!(
+            indexed_time * 10 < scanned_time,
+            "indexed {indexed_time:?} vs scanned {scanned_time:?}"
+        );
+    }
+
+    #[test]
+    fn automaton_new_same_shape_edges_with_different_ids() {
+        let location = Location::new_initial("initial", &Invariant::new_true());
+        let channel = Channel::new_output("channel");
+        let edge = Edge::new_loop(&location, &channel, &Guard::new_true(), &Update::empty());
+        let edges = HashSet::from([edge.with_id("first"), edge.with_id("second")]);
+        let result = Automaton::new("automaton", &edges, None);
+        assert_ok!(result);
+
+        let automaton = result.ok().unwrap();
+        assert_eq!(automaton.get_edges().len(), 2);
+        let channels = HashSet::from([channel]);
+        assert_eq!(automaton.outgoing_edges(&location, &channels).count(), 2);
+    }
+
+    #[test]
+    fn automaton_new_missing_identifiers_in_edge_guard() {
         let location = Location::new_initial("initial", &Invariant::new_true());
         let channel = Channel::new_output("channel");
         let node = Expression::new_identifier("ident");
@@ -507,120 +1799,1655 @@ mod tests {
         let edges = HashSet::from([edge]);
         let environment = Environment::new_empty();
         let automaton = Automaton::new("automaton", &edges, Some(&environment));
-        assert_err!(automaton, Error::MissingIdentifiersInEdgeGuard { .. });
+        assert_err!(automaton, Error::MissingIdentifiersInEdgeGuard { .. });
+    }
+
+    #[test]
+    fn automaton_new_missing_identifiers_in_edge_guard_mentions_subexpression() {
+        let location = Location::new_initial("initial", &Invariant::new_true());
+        let channel = Channel::new_output("channel");
+        let operand = Expression::new_logical_or(
+            &Expression::new_identifier("known"),
+            &Expression::new_identifier("unknown"),
+        );
+        let node = Expression::new_logical_and(&Expression::new_boolean(&true), &operand);
+        let edge = Edge::new_loop(&location, &channel, &Guard::new(&node), &Update::empty());
+        let edges = HashSet::from([edge]);
+        let mut environment = Environment::new_empty();
+        environment.insert("known", &Value::new_true());
+
+        let automaton = Automaton::new("automaton", &edges, Some(&environment));
+
+        let err = automaton.err().unwrap();
+        assert!(matches!(
+            &err,
+            Error::MissingIdentifiersInEdgeGuard { occurrences, .. }
+                if occurrences.len() == 1 && occurrences[0].context == operand
+        ));
+        assert!(err.to_string().contains("'unknown' in 'known || unknown'"));
+    }
+
+    #[test]
+    fn automaton_new_missing_identifiers_in_edge_update() {
+        let location = Location::new_initial("initial", &Invariant::new_true());
+        let channel = Channel::new_output("channel");
+        let assignment = Statement::new_simple_assignment("unknown", &Value::new_false());
+        let edge = Edge::new_loop(
+            &location,
+            &channel,
+            &Guard::new_true(),
+            &Update::new(&assignment),
+        );
+        let edges = HashSet::from([edge]);
+        let environment = Environment::new_empty();
+        let automaton = Automaton::new("automaton", &edges, Some(&environment));
+        assert_err!(automaton, Error::MissingIdentifiersInEdgeUpdate { .. });
+    }
+
+    #[test]
+    fn automaton_new_edge_update_type_mismatch() {
+        let location = Location::new_initial("initial", &Invariant::new_true());
+        let channel = Channel::new_output("channel");
+        let assignment = Statement::new_simple_assignment("x", &Value::new_true());
+        let edge = Edge::new_loop(
+            &location,
+            &channel,
+            &Guard::new_true(),
+            &Update::new(&assignment),
+        );
+        let edges = HashSet::from([edge]);
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_clock(0));
+
+        let automaton = Automaton::new("automaton", &edges, Some(&environment));
+
+        assert!(matches!(
+            automaton.err().unwrap(),
+            Error::EdgeUpdateTypeMismatch {
+                identifier,
+                expected: LangType::Clock,
+                actual: LangType::Logical,
+                ..
+            } if identifier == "x"
+        ));
+    }
+
+    #[test]
+    fn automaton_new_edge_update_ill_typed() {
+        let automaton = Automaton::parse(
+            "var c = 0\nvar f = false\nlocation a init\nlocation b\na -x! do f = c && true-> b",
+        );
+
+        assert_err!(automaton, Error::EdgeUpdateIsIllTyped { .. });
+    }
+
+    #[test]
+    fn automaton_new_edge_guard_ill_typed() {
+        let automaton =
+            Automaton::parse("var c = 0\nlocation a init\nlocation b\na -x! when c && true-> b");
+
+        assert_err!(automaton, Error::EdgeGuardIsIllTyped { .. });
+    }
+
+    #[test]
+    fn automaton_new_location_invariant_ill_typed() {
+        let automaton = Automaton::parse(
+            "var c = 0\nlocation a init invariant c && true\nlocation b\na -x!-> b",
+        );
+
+        assert_err!(automaton, Error::LocationInvariantIsIllTyped { .. });
+    }
+
+    #[test]
+    fn automaton_new_location_invariant_not_logical() {
+        let automaton =
+            Automaton::parse("var c = 0\nlocation a init\nlocation b invariant c\na -x!-> b");
+
+        assert_err!(automaton, Error::LocationInvariantIsNotLogical { .. });
+    }
+
+    #[test]
+    fn automaton_new_partition_error() {
+        let location = Location::new_initial("initial", &Invariant::new_true());
+        let channel_ident = "channel";
+        let in_channel = Channel::new_output(channel_ident);
+        let out_channel = Channel::new_input(channel_ident);
+        let in_edge = Edge::new_loop(
+            &location,
+            &in_channel,
+            &Guard::new_false(),
+            &Update::new_pure(),
+        );
+        let out_edge = Edge::new_loop(
+            &location,
+            &out_channel,
+            &Guard::new_true(),
+            &Update::new_pure(),
+        );
+        let edges = HashSet::from([in_edge, out_edge]);
+        let automaton = Automaton::new("automaton", &edges, None);
+        assert_eq!(edges.len(), 2); // If the edges are the same then there is only a single element in the set
+        assert_err!(automaton, Error::PartitionError { .. });
+    }
+
+    #[test]
+    fn automaton_new_empty_automaton() {
+        let edges = HashSet::default();
+        let environment = Environment::new_empty();
+        let automaton = Automaton::new("automaton", &edges, Some(&environment));
+        assert_err!(automaton, Error::EmptyAutomaton { .. });
+    }
+
+    #[test]
+    fn automaton_new_missing_initial_location() {
+        let location = Location::new_normal("loc", &Invariant::new_true());
+        let in_channel = Channel::new_output("in");
+        let edge = Edge::new_loop(
+            &location,
+            &in_channel,
+            &Guard::new_false(),
+            &Update::new_pure(),
+        );
+        let edges = HashSet::from([edge]);
+        let automaton = Automaton::new("automaton", &edges, None);
+        assert_err!(automaton, Error::MissingInitialLocation { .. });
+    }
+
+    #[test]
+    fn automaton_new_error_records_source_location() {
+        let edges = HashSet::default();
+        let automaton = Automaton::new("automaton", &edges, None);
+
+        let source_location = automaton.err().unwrap().source_location();
+
+        let (file, line) = source_location.unwrap();
+        assert_eq!(file, file!());
+        assert!(line > 0);
+    }
+
+    #[test]
+    fn automaton_new_aggregates_errors() {
+        let a = Location::new_initial("a", &Invariant::new_true());
+        let b = Location::new_normal("b", &Invariant::new(&Expression::new_identifier("z")));
+        let guarded = Edge::new(
+            &a,
+            &Channel::new_input("in"),
+            &Guard::new(&Expression::new_identifier("x")),
+            &Update::empty(),
+            &b,
+        );
+        let updating = Edge::new(
+            &b,
+            &Channel::new_output("out"),
+            &Guard::new_true(),
+            &Update::new(&Statement::new_simple_assignment("y", &Value::new_true())),
+            &a,
+        );
+        let environment = Environment::new_empty();
+        let single = HashSet::from([Edge::new_loop(
+            &a,
+            &Channel::new_input("in"),
+            &Guard::new(&Expression::new_identifier("x")),
+            &Update::empty(),
+        )]);
+        let multiple = HashSet::from([guarded, updating]);
+
+        let single = Automaton::new("automaton", &single, Some(&environment));
+        let multiple = Automaton::new("automaton", &multiple, Some(&environment));
+
+        assert_err!(single, Error::MissingIdentifiersInEdgeGuard { .. });
+        let Err(Error::AggregatedError { errors, .. }) = multiple else {
+            panic!("Expected an aggregated error");
+        };
+        assert_eq!(errors.len(), 3);
+        // Edges are checked in sorted order, where normal locations come first
+        assert!(matches!(
+            errors[0],
+            Error::MissingIdentifiersInEdgeUpdate { .. }
+        ));
+        assert!(matches!(
+            errors[1],
+            Error::MissingIdentifiersInEdgeGuard { .. }
+        ));
+        assert!(matches!(
+            errors[2],
+            Error::MissingIdentifiersInLocationInvariant { .. }
+        ));
+    }
+
+    #[test]
+    fn automaton_error_try_aggregate() {
+        let error = || Error::EmptyAutomaton {
+            automaton: String::from("automaton"),
+            source_location: None,
+        };
+
+        assert_eq!(Error::try_aggregate(Vec::new()), None);
+        assert_eq!(Error::try_aggregate(vec![error()]), Some(error()));
+        assert!(matches!(
+            Error::try_aggregate(vec![error(), error()]),
+            Some(Error::AggregatedError { errors, .. }) if errors.len() == 2
+        ));
+    }
+
+    #[test]
+    fn automaton_new_too_many_initial_location() {
+        let location_a = Location::new_initial("a", &Invariant::new_true());
+        let location_b = Location::new_initial("b", &Invariant::new_true());
+        let in_channel = Channel::new_output("in");
+        let edge = Edge::new(
+            &location_a,
+            &in_channel,
+            &Guard::new_false(),
+            &Update::new_pure(),
+            &location_b,
+        );
+        let edges = HashSet::from([edge]);
+        let automaton = Automaton::new("automaton", &edges, None);
+        assert_err!(automaton, Error::TooManyInitialLocations { .. });
+    }
+
+    #[test]
+    fn automaton_new_inconsistent_initial_location() {
+        let location = Location::new_initial("a", &Invariant::new_false());
+        let in_channel = Channel::new_output("in");
+        let edge = Edge::new_loop(
+            &location,
+            &in_channel,
+            &Guard::new_false(),
+            &Update::new_pure(),
+        );
+        let edges = HashSet::from([edge]);
+        let automaton = Automaton::new("automaton", &edges, None);
+        assert_err!(automaton, Error::InconsistentInitialLocation { .. });
+    }
+
+    #[test]
+    fn automaton_is_location_consistent() {
+        let initial = Location::new_initial("initial", &Invariant::new_true());
+        let consistent = Location::new_normal("consistent", &Invariant::new_true());
+        let inconsistent = Location::new_normal("inconsistent", &Invariant::new_false());
+        let channel = Channel::new_output("out");
+        let edges = HashSet::from([
+            Edge::new(
+                &initial,
+                &channel,
+                &Guard::new_true(),
+                &Update::empty(),
+                &consistent,
+            ),
+            Edge::new(
+                &initial,
+                &channel,
+                &Guard::new_true(),
+                &Update::empty(),
+                &inconsistent,
+            ),
+        ]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+        let environment = automaton.get_initial_environment();
+
+        let result = automaton.is_location_consistent(&consistent, environment);
+        assert_eq!(result, Ok(true));
+        let result = automaton.is_location_consistent(&inconsistent, environment);
+        assert_eq!(result, Ok(false));
+    }
+
+    #[test]
+    fn automaton_is_location_consistent_unknown_identifier() {
+        let initial = Location::new_initial("initial", &Invariant::new_true());
+        let channel = Channel::new_output("out");
+        let edges = HashSet::from([Edge::new_loop(
+            &initial,
+            &channel,
+            &Guard::new_true(),
+            &Update::empty(),
+        )]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+        let location = Location::new_normal(
+            "location",
+            &Invariant::new(&Expression::new_identifier("unknown")),
+        );
+
+        let result = automaton.is_location_consistent(&location, &Environment::new_empty());
+        assert_err!(result, Error::InvariantEvaluationFailed { .. });
+    }
+
+    #[test]
+    fn automaton_new_inconsistent_initial_location_with_boolean_evaluation() {
+        let invariant_node = Expression::new_identifier("bool");
+        let mut environment = Environment::new_empty();
+        environment.insert("bool", &Value::new_false());
+        let location = Location::new_initial("a", &Invariant::new(&invariant_node));
+        let in_channel = Channel::new_output("in");
+        let edge = Edge::new_loop(
+            &location,
+            &in_channel,
+            &Guard::new_false(),
+            &Update::new_pure(),
+        );
+        let edges = HashSet::from([edge]);
+        let automaton = Automaton::new("automaton", &edges, Some(&environment));
+        assert_err!(automaton, Error::InconsistentInitialLocation { .. });
+    }
+
+    #[test]
+    fn automaton_accepts() {
+        let invariant = &Invariant::new_true();
+        let channel_in = Channel::new_input("in");
+        let channel_out = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let edges = HashSet::from([
+            Edge::new(&a, &channel_in, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&b, &channel_out, &Guard::new_true(), &Update::empty(), &a),
+        ]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+
+        assert!(automaton.accepts(&[]));
+        assert!(automaton.accepts(&[channel_in.clone(), channel_out.clone(), channel_in.clone()]));
+        assert!(!automaton.accepts(&[channel_in.clone(), channel_in.clone()]));
+        assert!(!automaton.accepts(&[channel_out]));
+    }
+
+    #[test]
+    fn automaton_words_up_to_equal_for_pruned_automaton() {
+        let invariant = &Invariant::new_true();
+        let channel_in = Channel::new_input("in");
+        let channel_out = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let dead = Location::new_normal("dead", invariant);
+        let guard = Guard::new_true();
+        let update = Update::empty();
+        // b and c are equivalent and dead is unreachable, so both describe (in out)*
+        let redundant = Automaton::new(
+            "redundant",
+            &HashSet::from([
+                Edge::new(&a, &channel_in, &guard, &update, &b),
+                Edge::new(&a, &channel_in, &guard, &update, &c),
+                Edge::new(&b, &channel_out, &guard, &update, &a),
+                Edge::new(&c, &channel_out, &guard, &update, &a),
+                Edge::new(&dead, &channel_out, &guard, &update, &dead),
+            ]),
+            None,
+        )
+        .ok()
+        .unwrap();
+        let pruned = Automaton::new(
+            "pruned",
+            &HashSet::from([
+                Edge::new(&a, &channel_in, &guard, &update, &b),
+                Edge::new(&b, &channel_out, &guard, &update, &a),
+            ]),
+            None,
+        )
+        .ok()
+        .unwrap();
+
+        let words = pruned.words_up_to(4);
+
+        assert_eq!(words, redundant.words_up_to(4));
+        assert_eq!(words.len(), 5);
+        assert!(words.contains(&vec![
+            channel_in.clone(),
+            channel_out.clone(),
+            channel_in,
+            channel_out
+        ]));
+    }
+
+    #[test]
+    fn automaton_enabled_edges() {
+        let invariant = &Invariant::new_true();
+        let channel_in = Channel::new_input("in");
+        let channel_out = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let a_b = Edge::new(&a, &channel_in, &Guard::new_true(), &Update::empty(), &b);
+        let a_c = Edge::new(&a, &channel_out, &Guard::new_true(), &Update::empty(), &c);
+        let a_a = Edge::new_loop(&a, &channel_out, &Guard::new_false(), &Update::empty());
+        let b_c = Edge::new(&b, &channel_out, &Guard::new_true(), &Update::empty(), &c);
+        let edges = HashSet::from([a_b.clone(), a_c.clone(), a_a, b_c]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+        let initial = automaton.get_initial_state();
+
+        let enabled = automaton.enabled_edges(&initial).unwrap();
+
+        assert_eq!(enabled.len(), 2);
+        for (edge, successor) in enabled {
+            assert!(edge == a_b || edge == a_c);
+            assert_eq!(successor.location, edge.target);
+        }
+    }
+
+    #[test]
+    fn automaton_guard_cache_reused_across_queries() {
+        let invariant = &Invariant::new_true();
+        let channel = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let edge = Edge::new(&a, &channel, &Guard::new_true(), &Update::empty(), &b);
+        let automaton = Automaton::new("automaton", &HashSet::from([edge]), None)
+            .ok()
+            .unwrap()
+            .with_guard_cache();
+        let initial = automaton.get_initial_state();
+
+        automaton.enabled_edges(&initial).unwrap();
+        automaton.successors(&initial, automaton.get_actions());
+        automaton.transitions(&initial, automaton.get_actions());
+
+        assert_eq!(automaton.get_guard_cache().unwrap().evaluations(), 1);
+    }
+
+    #[test]
+    fn automaton_is_empty_behavior_all_inconsistent() {
+        let channel = Channel::new_output("out");
+        let a = Location::new_initial("a", &Invariant::new_true());
+        let inconsistent = Location::new_inconsistent("inconsistent");
+        let edge = Edge::new(
+            &a,
+            &channel,
+            &Guard::new_true(),
+            &Update::empty(),
+            &inconsistent,
+        );
+        let automaton = Automaton::new("automaton", &HashSet::from([edge]), None)
+            .ok()
+            .unwrap();
+
+        assert!(automaton.is_empty_behavior());
+    }
+
+    #[test]
+    fn automaton_is_empty_behavior_immediate_deadlock() {
+        let channel = Channel::new_output("out");
+        let a = Location::new_initial("a", &Invariant::new_true());
+        let edge = Edge::new_loop(&a, &channel, &Guard::new_false(), &Update::empty());
+        let automaton = Automaton::new("automaton", &HashSet::from([edge]), None)
+            .ok()
+            .unwrap();
+
+        assert!(automaton.is_empty_behavior());
+    }
+
+    #[test]
+    fn automaton_is_empty_behavior_reachable_location() {
+        let channel = Channel::new_output("out");
+        let a = Location::new_initial("a", &Invariant::new_true());
+        let b = Location::new_normal("b", &Invariant::new_true());
+        let edge = Edge::new(&a, &channel, &Guard::new_true(), &Update::empty(), &b);
+        let automaton = Automaton::new("automaton", &HashSet::from([edge]), None)
+            .ok()
+            .unwrap();
+
+        assert!(!automaton.is_empty_behavior());
+    }
+
+    #[test]
+    fn automaton_edges_sorted_is_canonical() {
+        let invariant = &Invariant::new_true();
+        let channel_a = Channel::new_input("a");
+        let channel_b = Channel::new_output("b");
+        let x = Location::new_initial("x", invariant);
+        let y = Location::new_normal("y", invariant);
+        let x_b_y = Edge::new(&x, &channel_b, &Guard::new_true(), &Update::empty(), &y);
+        let x_a_y_false = Edge::new(&x, &channel_a, &Guard::new_false(), &Update::empty(), &y);
+        let x_a_y_true = Edge::new(&x, &channel_a, &Guard::new_true(), &Update::empty(), &y);
+        let not_false = Guard::new(&Expression::new_logical_not(&Expression::new_boolean(
+            &false,
+        )));
+        let x_a_y_not_false = Edge::new(&x, &channel_a, &not_false, &Update::empty(), &y);
+        let x_a_x = Edge::new_loop(&x, &channel_a, &Guard::new_true(), &Update::empty());
+        let y_a_x = Edge::new(&y, &channel_a, &Guard::new_true(), &Update::empty(), &x);
+        let edges = HashSet::from([
+            x_b_y.clone(),
+            x_a_y_false.clone(),
+            x_a_y_true.clone(),
+            x_a_y_not_false.clone(),
+            x_a_x.clone(),
+            y_a_x.clone(),
+        ]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+
+        let sorted = automaton.edges_sorted();
+
+        // Edges between the same locations by the same action are ordered by their guard strings
+        assert_eq!(
+            sorted,
+            vec![
+                x_a_x,
+                x_a_y_not_false,
+                x_a_y_false,
+                x_a_y_true,
+                x_b_y,
+                y_a_x
+            ]
+        );
+        for _ in 0..10 {
+            let copy = Automaton::new("automaton", &edges, None).ok().unwrap();
+            assert_eq!(copy.edges_sorted(), sorted);
+        }
+    }
+
+    #[test]
+    fn automaton_display_lists_locations_and_edges() {
+        let invariant = &Invariant::new_true();
+        let channel_in = Channel::new_input("press");
+        let channel_out = Channel::new_output("light");
+        let a = Location::new_initial("off", invariant);
+        let b = Location::new_normal("on", invariant);
+        let edges = HashSet::from([
+            Edge::new(&a, &channel_in, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&b, &channel_out, &Guard::new_true(), &Update::empty(), &a),
+        ]);
+        let automaton = Automaton::new("lamp", &edges, None).ok().unwrap();
+
+        let text = automaton.to_string();
+
+        assert!(text.starts_with("Automaton lamp"));
+        assert!(text.contains(&a.to_string()));
+        assert!(text.contains(&b.to_string()));
+        assert!(text.contains(&channel_in.to_string()));
+        assert!(text.contains(&channel_out.to_string()));
+        assert_eq!(text, automaton.to_string());
+    }
+
+    #[test]
+    fn automaton_is_composable_with_shared_output() {
+        let invariant = &Invariant::new_true();
+        let channel = Channel::new_output("shared");
+        let a = Location::new_initial("a", invariant);
+        let edge = Edge::new_loop(&a, &channel, &Guard::new_true(), &Update::empty());
+        let first = Automaton::new("first", &HashSet::from([edge.clone()]), None)
+            .ok()
+            .unwrap();
+        let second = Automaton::new("second", &HashSet::from([edge]), None)
+            .ok()
+            .unwrap();
+
+        let result = first.is_composable_with(&second);
+
+        assert_err!(result, Error::OverlappingOutputs { .. });
+    }
+
+    #[test]
+    fn automaton_is_composable_with_producer_consumer() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let produce = Edge::new_loop(
+            &a,
+            &Channel::new_output("item"),
+            &Guard::new_true(),
+            &Update::empty(),
+        );
+        let consume = Edge::new_loop(
+            &a,
+            &Channel::new_input("item"),
+            &Guard::new_true(),
+            &Update::empty(),
+        );
+        let producer = Automaton::new("producer", &HashSet::from([produce]), None)
+            .ok()
+            .unwrap();
+        let consumer = Automaton::new("consumer", &HashSet::from([consume]), None)
+            .ok()
+            .unwrap();
+
+        let result = producer.is_composable_with(&consumer);
+
+        assert_ok!(result);
+    }
+
+    #[test]
+    fn automaton_monitor_detects_violation_in_composition() {
+        let invariant = &Invariant::new_true();
+        let go = Channel::new_output("go");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let set_x = Update::new(&Statement::new_simple_assignment("x", &Value::new_true()));
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_false());
+        let system = Automaton::new(
+            "system",
+            &HashSet::from([
+                Edge::new_loop(&a, &go, &Guard::new_true(), &Update::empty()),
+                Edge::new(&a, &go, &Guard::new_true(), &set_x, &b),
+            ]),
+            Some(&environment),
+        )
+        .ok()
+        .unwrap();
+        let never_x = Expression::new_logical_not(&Expression::new_identifier("x"));
+
+        let monitor = Automaton::monitor("monitor", &never_x, &HashSet::from([go.clone()]))
+            .ok()
+            .unwrap();
+
+        let violated = Location::new_normal("Violated", invariant);
+        assert_eq!(monitor.get_initial_location().get_name(), "Ok");
+        assert!(monitor.get_inputs().contains(&go));
+        assert!(monitor.get_outputs().is_empty());
+        assert_eq!(
+            monitor
+                .outgoing_edges(&violated, monitor.get_actions())
+                .count(),
+            0
+        );
+
+        let composition = Composition::new(&system, &monitor).ok().unwrap();
+        let target = Composition::compose_locations(&b, &violated);
+        let trace = trace_to_location(&composition, composition.get_actions(), &target);
+        assert_eq!(trace.unwrap().len(), 1);
+        let safe = Automaton::monitor(
+            "safe",
+            &Expression::new_boolean(&true),
+            &HashSet::from([go]),
+        )
+        .ok()
+        .unwrap();
+        let composition = Composition::new(&system, &safe).ok().unwrap();
+        let unreachable = Composition::compose_locations(&b, &violated);
+        assert!(trace_to_location(&composition, composition.get_actions(), &unreachable).is_none());
+    }
+
+    #[test]
+    fn automaton_monitor_rejects_invalid_observers() {
+        let unwatched =
+            Automaton::monitor("monitor", &Expression::new_boolean(&true), &HashSet::new());
+        assert_err!(unwatched, Error::EmptyAutomaton { .. });
+        let clock = Automaton::monitor(
+            "monitor",
+            &Expression::new_clock(1),
+            &HashSet::from([Channel::new_output("go")]),
+        );
+        let Err(Error::AggregatedError { errors, .. }) = clock else {
+            panic!("Expected the errors of both observer edges");
+        };
+        assert!(matches!(
+            errors[..],
+            [
+                Error::EdgeGuardIsNotLogical { .. },
+                Error::EdgeGuardIsIllTyped { .. }
+            ]
+        ));
+    }
+
+    #[test]
+    fn automaton_determinize_subset_construction() {
+        let invariant = &Invariant::new_true();
+        let x = Channel::new_output("x");
+        let y = Channel::new_output("y");
+        let z = Channel::new_input("z");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let d = Location::new_normal("d", invariant);
+        let guard = Guard::new_true();
+        let update = Update::empty();
+        let edges = HashSet::from([
+            Edge::new(&a, &x, &guard, &update, &b),
+            Edge::new(&a, &x, &guard, &update, &c),
+            Edge::new(&b, &y, &guard, &update, &d),
+            Edge::new(&c, &z, &guard, &update, &d),
+            Edge::new(&c, &y, &Guard::new_false(), &update, &d),
+        ]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+        assert!(!automaton.is_deterministic());
+
+        let deterministic = automaton.determinize().ok().unwrap();
+
+        assert!(deterministic.is_deterministic());
+        assert_eq!(deterministic.get_initial_location().get_name(), "{a}");
+        let names: HashSet<String> = deterministic
+            .get_locations()
+            .iter()
+            .map(|location| location.get_name())
+            .collect();
+        assert_eq!(
+            names,
+            HashSet::from([
+                String::from("{a}"),
+                String::from("{b,c}"),
+                String::from("{d}")
+            ])
+        );
+        assert_eq!(
+            deterministic.get_initial_environment(),
+            automaton.get_initial_environment()
+        );
+        assert_eq!(deterministic.words_up_to(3), automaton.words_up_to(3));
+    }
+
+    #[test]
+    fn automaton_determinize_update_conflict() {
+        let invariant = &Invariant::new_true();
+        let x = Channel::new_output("x");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let set = Update::new(&Statement::new_simple_assignment("v", &Value::new_true()));
+        let edges = HashSet::from([
+            Edge::new(&a, &x, &Guard::new_true(), &set, &b),
+            Edge::new(&a, &x, &Guard::new_true(), &Update::empty(), &c),
+        ]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+
+        let result = automaton.determinize();
+
+        assert_err!(result, Error::NondeterministicUpdateConflict { .. });
+    }
+
+    #[test]
+    fn automaton_determinize_keeps_updates_of_exclusive_guards() {
+        let invariant = &Invariant::new_true();
+        let x = Channel::new_output("x");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let v = Expression::new_identifier("v");
+        let set = Update::new(&Statement::new_simple_assignment("w", &Value::new_true()));
+        let edges = HashSet::from([
+            Edge::new(&a, &x, &Guard::new(&v), &set, &b),
+            Edge::new(
+                &a,
+                &x,
+                &Guard::new(&Expression::new_logical_not(&v)),
+                &Update::empty(),
+                &c,
+            ),
+        ]);
+        let mut environment = Environment::new_empty();
+        environment.insert("v", &Value::new_true());
+        environment.insert("w", &Value::new_false());
+        let automaton = Automaton::new("automaton", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+
+        let deterministic = automaton.determinize().ok().unwrap();
+
+        // v holds initially, so only the edge to b fires and sets w
+        let initial = deterministic.get_initial_state();
+        let successors = deterministic.successors(&initial, deterministic.get_actions());
+        assert_eq!(successors.len(), 1);
+        assert_eq!(successors[0].location.get_name(), "{b}");
+        assert!(successors[0]
+            .environment
+            .contains_with_value("w", &Value::new_true()));
     }
 
     #[test]
-    fn automaton_new_missing_identifiers_in_edge_update() {
-        let location = Location::new_initial("initial", &Invariant::new_true());
-        let channel = Channel::new_output("channel");
-        let assignment = Statement::new_simple_assignment("unknown", &Value::new_false());
-        let edge = Edge::new_loop(
-            &location,
-            &channel,
-            &Guard::new_true(),
-            &Update::new(&assignment),
+    fn automaton_determinize_disjoins_invariants() {
+        let x = Channel::new_output("x");
+        let a = Location::new_initial("a", &Invariant::new_true());
+        let b = Location::new_normal("b", &Invariant::new(&Expression::new_identifier("v")));
+        let c = Location::new_normal(
+            "c",
+            &Invariant::new(&Expression::new_logical_not(&Expression::new_identifier(
+                "v",
+            ))),
         );
-        let edges = HashSet::from([edge]);
-        let environment = Environment::new_empty();
-        let automaton = Automaton::new("automaton", &edges, Some(&environment));
-        assert_err!(automaton, Error::MissingIdentifiersInEdgeUpdate { .. });
+        let edges = HashSet::from([
+            Edge::new(&a, &x, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&a, &x, &Guard::new_true(), &Update::empty(), &c),
+        ]);
+        let mut environment = Environment::new_empty();
+        environment.insert("v", &Value::new_false());
+        let automaton = Automaton::new("automaton", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+
+        let deterministic = automaton.determinize().ok().unwrap();
+
+        // c allows the state, so {b,c} does too
+        let initial = deterministic.get_initial_state();
+        let successors = deterministic.successors(&initial, deterministic.get_actions());
+        assert_eq!(successors.len(), 1);
+        assert_eq!(successors[0].location.get_name(), "{b,c}");
     }
 
     #[test]
-    fn automaton_new_partition_error() {
-        let location = Location::new_initial("initial", &Invariant::new_true());
-        let channel_ident = "channel";
-        let in_channel = Channel::new_output(channel_ident);
-        let out_channel = Channel::new_input(channel_ident);
-        let in_edge = Edge::new_loop(
-            &location,
-            &in_channel,
-            &Guard::new_false(),
-            &Update::new_pure(),
+    fn automaton_is_deterministic_distinguishes_directions() {
+        let a = Location::new_initial("a", &Invariant::new_true());
+        let input = Edge::new_loop(
+            &a,
+            &Channel::new_input("sync"),
+            &Guard::new_true(),
+            &Update::empty(),
         );
-        let out_edge = Edge::new_loop(
-            &location,
-            &out_channel,
+        let output = Edge::new_loop(
+            &a,
+            &Channel::new_output("sync"),
             &Guard::new_true(),
-            &Update::new_pure(),
+            &Update::empty(),
         );
-        let edges = HashSet::from([in_edge, out_edge]);
-        let automaton = Automaton::new("automaton", &edges, None);
-        assert_eq!(edges.len(), 2); // If the edges are the same then there is only a single element in the set
-        assert_err!(automaton, Error::PartitionError { .. });
+        // Bypasses the partition check of new, which rejects this automaton
+        let automaton = Automaton {
+            name: String::from("automaton"),
+            locations: HashSet::from([a.clone()]),
+            edges: HashSet::from([input.clone(), output.clone()]),
+            outgoing: HashMap::from([(a.clone(), vec![input.clone(), output.clone()])]),
+            ingoing: HashMap::from([(a.clone(), vec![input, output])]),
+            actions: HashSet::from([Channel::new_input("sync")]),
+            inputs: HashSet::from([Channel::new_input("sync")]),
+            outputs: HashSet::from([Channel::new_output("sync")]),
+            initial: a.clone(),
+            initial_environment: Environment::new_empty(),
+            guard_cache: None,
+        };
+
+        assert!(automaton.is_deterministic());
     }
 
     #[test]
-    fn automaton_new_empty_automaton() {
-        let edges = HashSet::default();
-        let environment = Environment::new_empty();
-        let automaton = Automaton::new("automaton", &edges, Some(&environment));
-        assert_err!(automaton, Error::EmptyAutomaton { .. });
+    fn automaton_new_with_options_auto_declares_missing_variables() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let guard = Guard::new(&Expression::new_logical_and(
+            &Expression::new_identifier("x"),
+            &Expression::new_identifier("y"),
+        ));
+        let edges = HashSet::from([Edge::new(
+            &a,
+            &Channel::new_output("out"),
+            &guard,
+            &Update::empty(),
+            &b,
+        )]);
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_true());
+
+        let declared = Automaton::new_with_options("automaton", &edges, Some(&environment), true);
+        let strict = Automaton::new_with_options("automaton", &edges, Some(&environment), false);
+
+        let initial_environment = declared.ok().unwrap().get_initial_environment_cloned();
+        assert_eq!(initial_environment.get_value("x"), Some(&Value::new_true()));
+        assert_eq!(
+            initial_environment.get_value("y"),
+            Some(&Value::new_false())
+        );
+        assert_err!(strict, Error::MissingIdentifiersInEdgeGuard { .. });
     }
 
     #[test]
-    fn automaton_new_missing_initial_location() {
-        let location = Location::new_normal("loc", &Invariant::new_true());
-        let in_channel = Channel::new_output("in");
-        let edge = Edge::new_loop(
-            &location,
-            &in_channel,
-            &Guard::new_false(),
-            &Update::new_pure(),
+    fn automaton_new_suggests_close_identifiers() {
+        let result = Automaton::parse(
+            "
+            var ready = false
+            location a init
+            location b
+            a -out! when read && unrelated-> b
+            ",
         );
-        let edges = HashSet::from([edge]);
-        let automaton = Automaton::new("automaton", &edges, None);
-        assert_err!(automaton, Error::MissingInitialLocation { .. });
+
+        let Err(Error::MissingIdentifiersInEdgeGuard { occurrences, .. }) = &result else {
+            panic!("Expected missing identifiers in the guard");
+        };
+        let suggestions: Vec<(&str, Option<&str>)> = occurrences
+            .iter()
+            .map(|occurrence| {
+                (
+                    occurrence.identifier.as_str(),
+                    occurrence.suggestion.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            suggestions,
+            vec![("read", Some("ready")), ("unrelated", None)]
+        );
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("'read' in 'read && unrelated' (did you mean 'ready'?)"));
     }
 
     #[test]
-    fn automaton_new_too_many_initial_location() {
-        let location_a = Location::new_initial("a", &Invariant::new_true());
-        let location_b = Location::new_initial("b", &Invariant::new_true());
-        let in_channel = Channel::new_output("in");
-        let edge = Edge::new(
-            &location_a,
-            &in_channel,
-            &Guard::new_false(),
-            &Update::new_pure(),
-            &location_b,
+    fn automaton_new_with_defaults_declares_missing_variables_with_defaults() {
+        let a = Location::new_initial("a", &Invariant::new_true());
+        let guard = Guard::new(&Expression::new_logical_and(
+            &Expression::new_clock_constraint("x", &BinaryOperator::GreaterEqual, 2),
+            &Expression::new_identifier("ready"),
+        ));
+        let edges = HashSet::from([Edge::new_loop(
+            &a,
+            &Channel::new_output("out"),
+            &guard,
+            &Update::new(&Statement::new_clock_reset("x")),
+        )]);
+        let default = |identifier: &str| match identifier {
+            "x" => Value::new_clock(0),
+            _ => Value::new_true(),
+        };
+
+        let automaton = Automaton::new_with_defaults("automaton", &edges, None, Some(&default));
+
+        let initial_environment = automaton.ok().unwrap().get_initial_environment_cloned();
+        assert_eq!(
+            initial_environment.get_value("x"),
+            Some(&Value::new_clock(0))
+        );
+        assert_eq!(
+            initial_environment.get_value("ready"),
+            Some(&Value::new_true())
         );
-        let edges = HashSet::from([edge]);
-        let automaton = Automaton::new("automaton", &edges, None);
-        assert_err!(automaton, Error::TooManyInitialLocations { .. });
     }
 
     #[test]
-    fn automaton_new_inconsistent_initial_location() {
-        let location = Location::new_initial("a", &Invariant::new_false());
-        let in_channel = Channel::new_output("in");
-        let edge = Edge::new_loop(
-            &location,
-            &in_channel,
-            &Guard::new_false(),
-            &Update::new_pure(),
+    fn automaton_readonly_and_unused_variables() {
+        let a = Location::new_initial("a", &Invariant::new(&Expression::new_identifier("bound")));
+        let b = Location::new_normal("b", &Invariant::new_true());
+        let guard = Guard::new(&Expression::new_logical_and(
+            &Expression::new_identifier("constant"),
+            &Expression::new_identifier("flag"),
+        ));
+        let set_flag = Update::new(&Statement::new_simple_assignment(
+            "flag",
+            &Value::new_false(),
+        ));
+        let edges = HashSet::from([
+            Edge::new(&a, &Channel::new_output("out"), &guard, &set_flag, &b),
+            Edge::new(
+                &b,
+                &Channel::new_input("in"),
+                &Guard::new_true(),
+                &Update::new(&Statement::new_simple_assignment(
+                    "written",
+                    &Value::new_true(),
+                )),
+                &a,
+            ),
+        ]);
+        let mut environment = Environment::new_empty();
+        for (identifier, value) in [
+            ("bound", true),
+            ("constant", true),
+            ("flag", true),
+            ("written", false),
+        ] {
+            environment.insert(identifier, &Value::Bool(value));
+        }
+        let automaton = Automaton::new("automaton", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+
+        let readonly = automaton.readonly_variables();
+        let unused = automaton.unused_variables();
+
+        assert_eq!(
+            readonly,
+            HashSet::from([String::from("constant"), String::from("bound")])
         );
-        let edges = HashSet::from([edge]);
-        let automaton = Automaton::new("automaton", &edges, None);
-        assert_err!(automaton, Error::InconsistentInitialLocation { .. });
+        assert_eq!(unused, HashSet::from([String::from("written")]));
     }
 
     #[test]
-    fn automaton_new_inconsistent_initial_location_with_boolean_evaluation() {
-        let invariant_node = Expression::new_identifier("bool");
+    fn automaton_relevant_variables_survive_projection() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let copy = Update::new(&Statement::new_assignment(
+            &Expression::new_identifier("copy"),
+            &Expression::new_identifier("source"),
+        ));
+        let edges = HashSet::from([Edge::new(
+            &a,
+            &Channel::new_output("out"),
+            &Guard::new(&Expression::new_identifier("guarded")),
+            &copy,
+            &b,
+        )]);
         let mut environment = Environment::new_empty();
-        environment.insert("bool", &Value::new_false());
-        let location = Location::new_initial("a", &Invariant::new(&invariant_node));
-        let in_channel = Channel::new_output("in");
-        let edge = Edge::new_loop(
-            &location,
-            &in_channel,
-            &Guard::new_false(),
-            &Update::new_pure(),
+        for identifier in ["copy", "source", "guarded", "unrelated"] {
+            environment.insert(identifier, &Value::new_true());
+        }
+        let automaton = Automaton::new("automaton", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+
+        let relevant = automaton.relevant_variables();
+        let projected = automaton.get_initial_state().project(&relevant);
+
+        assert_eq!(
+            relevant,
+            HashSet::from([String::from("source"), String::from("guarded")])
         );
-        let edges = HashSet::from([edge]);
-        let automaton = Automaton::new("automaton", &edges, Some(&environment));
-        assert_err!(automaton, Error::InconsistentInitialLocation { .. });
+        for identifier in relevant.iter() {
+            assert!(projected.environment.contains(identifier));
+        }
+        assert!(!projected.environment.contains("copy"));
+        assert!(!projected.environment.contains("unrelated"));
+    }
+
+    #[test]
+    fn automaton_locations_with_violated_invariants() {
+        let a = Location::new_initial("a", &Invariant::new_true());
+        let b = Location::new_normal("b", &Invariant::new(&Expression::new_identifier("x")));
+        let clear = Update::new(&Statement::new_simple_assignment("x", &Value::new_false()));
+        let edges = HashSet::from([
+            Edge::new(
+                &a,
+                &Channel::new_output("keep"),
+                &Guard::new_true(),
+                &Update::empty(),
+                &b,
+            ),
+            Edge::new(
+                &a,
+                &Channel::new_output("clear"),
+                &Guard::new_true(),
+                &clear,
+                &b,
+            ),
+        ]);
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_true());
+        let automaton = Automaton::new("automaton", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+
+        let violated = automaton.locations_with_violated_invariants();
+
+        assert_eq!(violated.len(), 1);
+        let (location, state) = &violated[0];
+        assert_eq!(*location, b);
+        assert_eq!(state.environment.get_value("x"), Some(&Value::new_false()));
+    }
+
+    #[test]
+    fn automaton_conflicting_actions() {
+        let a = Location::new_initial("a", &Invariant::new_true());
+        let b = Location::new_normal("b", &Invariant::new_true());
+        let x = Expression::new_identifier("x");
+        let input = Edge::new(
+            &a,
+            &Channel::new_input("sync"),
+            &Guard::new(&x),
+            &Update::empty(),
+            &b,
+        );
+        let output = Edge::new(
+            &a,
+            &Channel::new_output("sync"),
+            &Guard::new_true(),
+            &Update::empty(),
+            &b,
+        );
+        let other = Edge::new(
+            &b,
+            &Channel::new_output("reset"),
+            &Guard::new_true(),
+            &Update::empty(),
+            &a,
+        );
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_true());
+        // Bypasses the partition check of new, which rejects this automaton
+        let automaton = Automaton {
+            name: String::from("automaton"),
+            locations: HashSet::from([a.clone(), b.clone()]),
+            edges: HashSet::from([input.clone(), output.clone(), other.clone()]),
+            outgoing: HashMap::from([
+                (a.clone(), vec![input.clone(), output.clone()]),
+                (b.clone(), vec![other.clone()]),
+            ]),
+            ingoing: HashMap::from([(b.clone(), vec![input, output]), (a.clone(), vec![other])]),
+            actions: HashSet::from([Channel::new_input("sync"), Channel::new_output("reset")]),
+            inputs: HashSet::from([Channel::new_input("sync")]),
+            outputs: HashSet::from([Channel::new_output("sync"), Channel::new_output("reset")]),
+            initial: a.clone(),
+            initial_environment: environment.clone(),
+            guard_cache: None,
+        };
+
+        let conflicts = automaton.conflicting_actions();
+
+        assert_eq!(
+            conflicts,
+            vec![(State::new(&a, &environment), String::from("sync"))]
+        );
+    }
+
+    #[test]
+    fn automaton_conflicting_actions_empty_when_partitioned() {
+        let a = Location::new_initial("a", &Invariant::new_true());
+        let edges = HashSet::from([
+            Edge::new_loop(
+                &a,
+                &Channel::new_input("request"),
+                &Guard::new_true(),
+                &Update::empty(),
+            ),
+            Edge::new_loop(
+                &a,
+                &Channel::new_output("response"),
+                &Guard::new_true(),
+                &Update::empty(),
+            ),
+        ]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+
+        assert!(automaton.conflicting_actions().is_empty());
+    }
+
+    fn new_ring(names: &[&str], guard: &Guard) -> Automaton {
+        let locations: Vec<Location> = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| match index {
+                0 => Location::new_initial(name, &Invariant::new_true()),
+                _ => Location::new_normal(name, &Invariant::new_true()),
+            })
+            .collect();
+        let mut edges = HashSet::new();
+        for (index, source) in locations.iter().enumerate() {
+            let target = &locations[(index + 1) % locations.len()];
+            edges.insert(Edge::new(
+                source,
+                &Channel::new_output("step"),
+                guard,
+                &Update::empty(),
+                target,
+            ));
+            edges.insert(Edge::new(
+                source,
+                &Channel::new_input("skip"),
+                &Guard::new_true(),
+                &Update::empty(),
+                &locations[(index + 2) % locations.len()],
+            ));
+        }
+        Automaton::new("ring", &edges, Some(&Environment::new_empty()))
+            .ok()
+            .unwrap()
+    }
+
+    #[test]
+    fn automaton_structurally_equal_ignores_name() {
+        let lhs = new_ring(&["a", "b", "c"], &Guard::new_true());
+        let mut rhs = new_ring(&["a", "b", "c"], &Guard::new_true());
+        rhs.name = String::from("other");
+        let different = new_ring(&["a", "b", "c"], &Guard::new_false());
+
+        assert!(lhs.structurally_equal(&rhs));
+        assert!(!lhs.structurally_equal(&different));
+    }
+
+    #[test]
+    fn automaton_structurally_eq_ignoring_invariants() {
+        let lhs =
+            Automaton::parse("location a init\nlocation b invariant x\na -go!-> b\nb -back?-> a")
+                .ok()
+                .unwrap();
+        let rhs =
+            Automaton::parse("location a init invariant !x\nlocation b\na -go!-> b\nb -back?-> a")
+                .ok()
+                .unwrap();
+        let other =
+            Automaton::parse("location a init\nlocation b invariant x\na -go!-> b\nb -back!-> a")
+                .ok()
+                .unwrap();
+
+        assert!(lhs.structurally_eq(&rhs, true));
+        assert!(!lhs.structurally_eq(&rhs, false));
+        assert!(lhs.structurally_eq(&lhs, false));
+        assert!(!lhs.structurally_eq(&other, true));
+    }
+
+    #[test]
+    fn automaton_isomorphic_renamed_locations() {
+        let lhs = new_ring(&["a", "b", "c", "d"], &Guard::new_true());
+        let rhs = new_ring(&["w", "x", "y", "z"], &Guard::new_true());
+
+        let mapping = lhs.isomorphic(&rhs);
+
+        assert!(!lhs.structurally_equal(&rhs));
+        assert_eq!(
+            mapping,
+            Some(HashMap::from([
+                (String::from("a"), String::from("w")),
+                (String::from("b"), String::from("x")),
+                (String::from("c"), String::from("y")),
+                (String::from("d"), String::from("z")),
+            ]))
+        );
+    }
+
+    #[test]
+    fn automaton_isomorphic_rejects_different_automata() {
+        let lhs = new_ring(&["a", "b", "c"], &Guard::new_true());
+        let guarded = new_ring(&["a", "b", "c"], &Guard::new_false());
+        let larger = new_ring(&["a", "b", "c", "d"], &Guard::new_true());
+
+        assert_eq!(
+            lhs.isomorphic(&lhs),
+            Some(HashMap::from([
+                (String::from("a"), String::from("a")),
+                (String::from("b"), String::from("b")),
+                (String::from("c"), String::from("c")),
+            ]))
+        );
+        assert_eq!(lhs.isomorphic(&guarded), None);
+        assert_eq!(lhs.isomorphic(&larger), None);
+    }
+
+    #[test]
+    fn automaton_strengthen_guards_preserves_invariant_checked_reachability() {
+        let automaton = Automaton::parse(
+            "
+            automaton strengthen
+            var x = false
+            var y = false
+            location a init
+            location b invariant x
+            location c invariant !x
+            location d invariant false
+            a -set? do x = true-> b
+            a -clear? do x = false; y = true-> b
+            a -go? when y-> c
+            a -doom!-> d
+            b -back! do if y then x = false else y = true-> a
+            c -flip! do x = true-> c
+            c -leave!-> a
+            ",
+        )
+        .ok()
+        .unwrap();
+        // The original explored with successors whose target invariant holds
+        let mut expected: HashSet<State> = HashSet::from([automaton.get_initial_state()]);
+        let mut frontier = vec![automaton.get_initial_state()];
+        while let Some(state) = frontier.pop() {
+            for successor in automaton.successors(&state, automaton.get_actions()) {
+                let consistent =
+                    automaton.is_location_consistent(&successor.location, &successor.environment);
+                if consistent == Ok(true) && expected.insert(successor.clone()) {
+                    frontier.push(successor);
+                }
+            }
+        }
+
+        let strengthened = automaton.strengthen_guards();
+        let reachable: HashSet<State> = strengthened
+            .reachable_states(strengthened.get_actions())
+            .into_iter()
+            .collect();
+
+        assert_eq!(reachable, expected);
+        let doom = strengthened
+            .get_edges()
+            .iter()
+            .find(|edge| edge.action.name() == "doom")
+            .unwrap();
+        assert_eq!(doom.guard, Guard::new_false());
+        let set = strengthened
+            .get_edges()
+            .iter()
+            .find(|edge| edge.action.name() == "set")
+            .unwrap();
+        assert_eq!(set.guard, Guard::new_true());
+    }
+
+    #[test]
+    fn automaton_variable_value_domains() {
+        let automaton = Automaton::parse(
+            "
+            var flag = false
+            var toggle = false
+            location a init
+            location b
+            a -go? do toggle = !toggle; flag = flag && toggle-> b
+            b -back! do flag = false-> a
+            ",
+        )
+        .ok()
+        .unwrap();
+
+        let domains = automaton.variable_value_domains();
+
+        assert_eq!(
+            domains,
+            HashMap::from([
+                (String::from("flag"), HashSet::from([Value::new_false()])),
+                (
+                    String::from("toggle"),
+                    HashSet::from([Value::new_false(), Value::new_true()])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn automaton_restrict_to_inputs() {
+        let automaton = Automaton::parse(DIRECTED_DIAMOND).ok().unwrap();
+        let inputs = automaton.get_inputs().clone();
+
+        let restricted = automaton.restrict(&inputs).ok().unwrap();
+
+        let edges: Vec<(String, String)> = restricted
+            .edges_sorted()
+            .iter()
+            .map(|edge| (edge.source.get_name(), edge.target.get_name()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                (String::from("a"), String::from("b")),
+                (String::from("a"), String::from("c")),
+                (String::from("d"), String::from("f")),
+                (String::from("e"), String::from("f")),
+            ]
+        );
+        assert_eq!(restricted.get_locations().len(), 6);
+        assert!(restricted.get_outputs().is_empty());
+        assert_eq!(restricted.get_inputs(), &inputs);
+    }
+
+    #[test]
+    fn automaton_restrict_errors() {
+        let automaton =
+            Automaton::parse("location a init\nlocation b\nlocation c\na -in?-> b\nb -out!-> c")
+                .ok()
+                .unwrap();
+
+        let empty = automaton.restrict(&HashSet::new());
+        let without_initial = automaton.restrict(&HashSet::from([Channel::new_output("out")]));
+
+        assert_err!(empty, Error::EmptyAutomaton { .. });
+        assert_err!(without_initial, Error::MissingInitialLocation { .. });
+    }
+
+    #[test]
+    fn automaton_disjoint_union() {
+        let lhs = Automaton::parse(
+            "automaton lhs\nvar x = false\nlocation a init\nlocation b\na -in?-> b\nb -out!-> a",
+        )
+        .ok()
+        .unwrap();
+        let rhs = Automaton::parse(
+            "automaton rhs\nvar y = true\nlocation c init\nlocation d\nc -go?-> d\nd -done!-> c",
+        )
+        .ok()
+        .unwrap();
+        let c = Location::new_initial("c", &Invariant::new_true());
+
+        let union = lhs.disjoint_union(&rhs, &c).ok().unwrap();
+
+        assert_eq!(union.get_locations().len(), 4);
+        assert_eq!(union.get_edges().len(), 4);
+        assert_eq!(union.get_initial_location(), &c);
+        let initials = union
+            .get_locations()
+            .iter()
+            .filter(|location| matches!(location, Location::Initial { .. }))
+            .count();
+        assert_eq!(initials, 1);
+        assert!(union
+            .get_locations()
+            .contains(&Location::new_normal("a", &Invariant::new_true())));
+        assert_eq!(union.get_initial_environment().count(), 2);
+    }
+
+    #[test]
+    fn automaton_disjoint_union_collisions() {
+        let lhs = Automaton::parse("var x = false\nlocation a init\nlocation b\na -in?-> b")
+            .ok()
+            .unwrap();
+        let same_location = Automaton::parse("location b init\nb -go?-> b")
+            .ok()
+            .unwrap();
+        let same_variable = Automaton::parse("var x = true\nlocation c init\nc -go?-> c")
+            .ok()
+            .unwrap();
+        let shared_action = Automaton::parse("location c init\nc -in!-> c")
+            .ok()
+            .unwrap();
+        let a = Location::new_initial("a", &Invariant::new_true());
+
+        let location_collision = lhs.disjoint_union(&same_location, &a);
+        let variable_collision = lhs.disjoint_union(&same_variable, &a);
+        let partition = lhs.disjoint_union(&shared_action, &a);
+
+        assert_err!(location_collision, Error::DuplicateLocationName { .. });
+        assert_err!(variable_collision, Error::DuplicateVariableName { .. });
+        assert_err!(partition, Error::PartitionError { .. });
+    }
+
+    #[test]
+    fn automaton_reverse_backward_reachability() {
+        // h is unreachable, but reaches g which a also reaches
+        let automaton = Automaton::parse(&format!(
+            "{}\nlocation g\nlocation h\na -out!-> g\nh -in?-> g",
+            DIRECTED_DIAMOND
+        ))
+        .ok()
+        .unwrap();
+        let f = Location::new_normal("f", &Invariant::new_true());
+        // The locations with a path to f, found by a fixpoint over the edges
+        let mut expected: HashSet<String> = HashSet::from([f.get_name()]);
+        loop {
+            let sources: Vec<String> = automaton
+                .get_edges()
+                .iter()
+                .filter(|edge| expected.contains(&edge.target.get_name()))
+                .map(|edge| edge.source.get_name())
+                .collect();
+            let size = expected.len();
+            expected.extend(sources);
+            if expected.len() == size {
+                break;
+            }
+        }
+
+        let reversed = automaton.reverse(&f, false).ok().unwrap();
+
+        let reachable: HashSet<String> = reversed
+            .reachable_locations(reversed.get_actions())
+            .iter()
+            .map(Location::get_name)
+            .collect();
+        assert_eq!(reachable, expected);
+        assert_eq!(reachable.len(), 6);
+        assert_eq!(reversed.get_initial_location().get_name(), "f");
+    }
+
+    #[test]
+    fn automaton_reverse_updates() {
+        let automaton = Automaton::parse("location a init\nlocation b\na -go? do x = true-> b")
+            .ok()
+            .unwrap();
+        let b = Location::new_normal("b", &Invariant::new_true());
+
+        let strict = automaton.reverse(&b, false);
+        let lossy = automaton.reverse(&b, true).ok().unwrap();
+
+        assert_err!(strict, Error::IrreversibleUpdate { .. });
+        assert!(lossy
+            .get_edges()
+            .iter()
+            .all(|edge| edge.update.node.is_none()));
+    }
+
+    #[test]
+    fn automaton_variable_dependencies() {
+        let invariant = &Invariant::new_true();
+        let l = Location::new_initial("l", invariant);
+        let update = Update::new(&Statement::new_sequence(&[
+            Statement::new_assignment(
+                &Expression::new_identifier("a"),
+                &Expression::new_logical_and(
+                    &Expression::new_identifier("b"),
+                    &Expression::new_identifier("c"),
+                ),
+            ),
+            Statement::new_simple_assignment("d", &Value::new_true()),
+        ]));
+        let edges = HashSet::from([Edge::new_loop(
+            &l,
+            &Channel::new_output("out"),
+            &Guard::new_true(),
+            &update,
+        )]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+
+        let dependencies = automaton.variable_dependencies();
+
+        assert_eq!(
+            dependencies,
+            HashMap::from([
+                (
+                    String::from("a"),
+                    HashSet::from([String::from("b"), String::from("c")])
+                ),
+                (String::from("d"), HashSet::new()),
+            ])
+        );
+    }
+
+    #[test]
+    fn automaton_output_is_identical_across_constructions() {
+        let construct = || {
+            let invariant = &Invariant::new_true();
+            let locations: Vec<Location> = (0..6)
+                .map(|index| match index {
+                    0 => Location::new_initial("l0", invariant),
+                    _ => Location::new_normal(&format!("l{}", index), invariant),
+                })
+                .collect();
+            let mut environment = Environment::new_empty();
+            let mut edges = HashSet::new();
+            for (index, pair) in locations.windows(2).enumerate() {
+                let variable = format!("v{}", index);
+                environment.insert(&variable, &Value::new_false());
+                edges.insert(Edge::new(
+                    &pair[0],
+                    &Channel::new_output(&format!("o{}", index)),
+                    &Guard::new(&Expression::new_identifier(&variable)),
+                    &Update::new(&Statement::new_simple_assignment(
+                        &variable,
+                        &Value::new_true(),
+                    )),
+                    &pair[1],
+                ));
+                edges.insert(Edge::new(
+                    &pair[1],
+                    &Channel::new_input(&format!("i{}", index)),
+                    &Guard::new_true(),
+                    &Update::empty(),
+                    &pair[0],
+                ));
+            }
+            let automaton = Automaton::new("ordered", &edges, Some(&environment))
+                .ok()
+                .unwrap();
+            let error = Automaton::new(
+                "ordered",
+                &locations
+                    .iter()
+                    .map(|location| {
+                        Edge::new_loop(
+                            &Location::new_initial(&location.get_name(), invariant),
+                            &Channel::new_output("o"),
+                            &Guard::new_true(),
+                            &Update::empty(),
+                        )
+                    })
+                    .collect(),
+                None,
+            )
+            .err()
+            .unwrap();
+            (
+                automaton.to_string(),
+                automaton.get_initial_state().to_string(),
+                error.to_string(),
+            )
+        };
+
+        let first = construct();
+        let second = construct();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn automaton_sorted_accessors() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let edges = HashSet::from([
+            Edge::new(
+                &a,
+                &Channel::new_output("y"),
+                &Guard::new_true(),
+                &Update::empty(),
+                &b,
+            ),
+            Edge::new(
+                &b,
+                &Channel::new_input("x"),
+                &Guard::new_true(),
+                &Update::empty(),
+                &a,
+            ),
+        ]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+
+        let locations = automaton.locations_sorted();
+        let actions = automaton.actions_sorted();
+
+        assert_eq!(locations, vec![a.clone(), b.clone()]);
+        assert_eq!(
+            actions,
+            vec![Channel::new_input("x"), Channel::new_output("y")]
+        );
+        assert!(b < a);
+        assert_eq!(
+            Channel::new_input("x").cmp(&Channel::new_output("x")),
+            std::cmp::Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn automaton_edges_on_channel_ignores_direction() {
+        let automaton = Automaton::parse(
+            "
+            location a init
+            location b
+            a -sync?-> b
+            b -sync?-> a
+            b -done!-> b
+            ",
+        )
+        .ok()
+        .unwrap();
+        // A channel is either an input or an output of a single automaton
+        let both = Automaton::parse(
+            "
+            location a init
+            location b
+            a -sync?-> b
+            b -sync!-> a
+            ",
+        );
+
+        let sync = automaton.edges_on_channel("sync");
+        let done = automaton.edges_on_channel("done");
+        let names = automaton.channel_names();
+
+        assert_eq!(sync.len(), 2);
+        assert!(sync.iter().all(|edge| edge.action.is_input()));
+        assert_eq!(done.len(), 1);
+        assert!(done[0].action.is_output());
+        assert!(automaton.edges_on_channel("missing").is_empty());
+        assert_eq!(
+            names,
+            HashSet::from([String::from("sync"), String::from("done")])
+        );
+        assert!(matches!(both, Err(Error::PartitionError { .. })));
     }
 }
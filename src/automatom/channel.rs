@@ -17,6 +17,19 @@ impl PartialEq for Channel {
     }
 }
 
+/// Channels are ordered by name alone, consistent with equality ignoring the direction.
+impl Ord for Channel {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.name().cmp(other.name())
+    }
+}
+
+impl PartialOrd for Channel {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Hash for Channel {
     fn hash<H: Hasher>(&self, state: &mut H) {
         match self {
@@ -42,6 +55,60 @@ impl Channel {
     pub fn new_output(name: &str) -> Self {
         Channel::Out(String::from(name))
     }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Channel::In(name) | Channel::Out(name) => name,
+        }
+    }
+
+    pub fn is_input(&self) -> bool {
+        matches!(self, Channel::In(_))
+    }
+
+    pub fn is_output(&self) -> bool {
+        matches!(self, Channel::Out(_))
+    }
+
+    /// True if both channels are inputs or both are outputs, regardless of name.
+    pub fn same_direction(&self, other: &Channel) -> bool {
+        self.is_input() == other.is_input()
+    }
+
+    pub fn directed(&self) -> DirectedChannel {
+        DirectedChannel(self.clone())
+    }
+}
+
+/// A channel where equality and hashing take the direction into account, such
+/// that `a?` and `a!` are different. `Channel` itself only compares the names
+/// as required when checking that the actions are partitioned.
+#[derive(Debug, Clone, Eq)]
+pub struct DirectedChannel(pub Channel);
+
+impl PartialEq for DirectedChannel {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.same_direction(&other.0) && self.0 == other.0
+    }
+}
+
+impl Hash for DirectedChannel {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.is_input().hash(state);
+        self.0.hash(state);
+    }
+}
+
+impl From<Channel> for DirectedChannel {
+    fn from(channel: Channel) -> Self {
+        DirectedChannel(channel)
+    }
+}
+
+impl Display for DirectedChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
 }
 
 impl Display for Channel {
@@ -60,7 +127,7 @@ mod tests {
         hash::{Hash, Hasher},
     };
 
-    use super::Channel;
+    use super::{Channel, DirectedChannel};
 
     #[test]
     fn channel_equality() {
@@ -105,4 +172,34 @@ mod tests {
         assert_eq!(intersection.len(), 1);
         assert!(!set_a.is_disjoint(&set_b));
     }
+
+    #[test]
+    fn channel_same_direction() {
+        let input_a = Channel::new_input("a");
+        let input_b = Channel::new_input("b");
+        let output_a = Channel::new_output("a");
+        assert!(input_a.same_direction(&input_b));
+        assert!(!input_a.same_direction(&output_a));
+        assert!(output_a.same_direction(&output_a));
+    }
+
+    #[test]
+    fn directed_channel_equality() {
+        let input = Channel::new_input("name").directed();
+        let output = Channel::new_output("name").directed();
+        let other = Channel::new_input("other").directed();
+        assert_ne!(input, output);
+        assert_ne!(input, other);
+        assert_eq!(input, DirectedChannel(Channel::new_input("name")));
+    }
+
+    #[test]
+    fn directed_channel_hashset() {
+        let set: HashSet<DirectedChannel> = HashSet::from([
+            Channel::new_input("name").directed(),
+            Channel::new_output("name").directed(),
+        ]);
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&Channel::new_output("name").directed()));
+    }
 }
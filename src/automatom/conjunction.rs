@@ -1,6 +1,6 @@
 use std::collections::{hash_set::Intersection, HashSet};
 
-use crate::language::environment::Environment;
+use crate::{diagnostics::trace_span, language::environment::Environment};
 
 use super::{automaton::Automaton, channel::Channel, edge::Edge, error::Error, location::Location};
 
@@ -18,6 +18,7 @@ pub struct Conjunction<'a> {
 
 impl<'a> Conjunction<'a> {
     pub fn new(automata: Vec<&'a Automaton>) -> Result<Self, Error> {
+        trace_span!("conjunction::new");
         // Atleast two automatons are required for a conjunction
         if automata.len() < 2 {
             // Too few operands/automata
@@ -32,7 +33,7 @@ impl<'a> Conjunction<'a> {
             // Act_o = ⋃_{i∈I} Act_o^i
             outputs.extend(i.get_outputs().to_owned());
 
-            initial_locations.insert(i.get_initial_location());
+            initial_locations.insert(i.get_initial_location_cloned());
         }
         let mut actions: HashSet<Channel> = HashSet::default();
         actions.extend(inputs.clone());
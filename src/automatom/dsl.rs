@@ -0,0 +1,280 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::language::{
+    environment::Environment,
+    error::Error as LangError,
+    expression::Expression,
+    parser::{tokenize, Parser, Token},
+    value::Value,
+};
+
+use super::{
+    automaton::Automaton, channel::Channel, edge::Edge, error::Error, guard::Guard,
+    invariant::Invariant, location::Location, update::Update,
+};
+
+/// Parses an automaton written one declaration per line, where blank lines and
+/// lines starting with `#` are ignored:
+///
+/// ```text
+/// automaton lamp
+/// var on = false
+/// location off init
+/// location lit invariant on
+/// press: off -press? when !on do on = true-> lit
+/// lit -release! do on = false-> off
+/// ```
+///
+/// Edges may only use declared locations. Without any `var` declarations the
+/// variables are declared automatically.
+pub(crate) fn parse(input: &str) -> Result<Automaton, Error> {
+    let mut name = String::from("automaton");
+    let mut environment: Option<Environment> = None;
+    let mut locations: HashMap<String, Location> = HashMap::new();
+    let mut edges: HashSet<Edge> = HashSet::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line_error = |error: LangError| Error::ParseError {
+            source_location: Some(&(file!(), line!())),
+            line: index + 1,
+            message: error.to_string(),
+        };
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let tokens = tokenize(line).map_err(line_error)?;
+        let mut parser = Parser::new(&tokens, line.len());
+
+        if parser.accept_keyword("automaton") {
+            name = parser.identifier().map_err(line_error)?;
+        } else if parser.accept_keyword("var") {
+            let identifier = parser.identifier().map_err(line_error)?;
+            parser.expect(&Token::Assign, "'='").map_err(line_error)?;
+            let value = parser.value().map_err(line_error)?;
+            environment
+                .get_or_insert_with(Environment::new_empty)
+                .insert(&identifier, &value);
+        } else if parser.accept_keyword("location") {
+            let location_name = parser.identifier().map_err(line_error)?;
+            let initial = parser.accept_keyword("init");
+            let invariant = match parser.accept_keyword("invariant") {
+                true => Invariant::new(&parser.expression().map_err(line_error)?),
+                false => Invariant::new_true(),
+            };
+            let location = match initial {
+                true => Location::new_initial(&location_name, &invariant),
+                false => Location::new_normal(&location_name, &invariant),
+            };
+            if locations.insert(location_name.clone(), location).is_some() {
+                return Err(Error::ParseError {
+                    source_location: Some(&(file!(), line!())),
+                    line: index + 1,
+                    message: format!("location {} is declared more than once", location_name),
+                });
+            }
+        } else {
+            let edge = parse_edge(&mut parser, &locations).map_err(line_error)?;
+            edges.insert(edge);
+        }
+        parser.expect_end().map_err(line_error)?;
+    }
+
+    Automaton::new(&name, &edges, environment.as_ref())
+}
+
+/// Parses `[id:] source -action(?|!) [when guard] [do update]-> target`.
+fn parse_edge(
+    parser: &mut Parser,
+    locations: &HashMap<String, Location>,
+) -> Result<Edge, LangError> {
+    let location = |parser: &mut Parser, name: Option<String>| -> Result<Location, LangError> {
+        let position = parser.position();
+        let name = match name {
+            Some(name) => name,
+            None => parser.identifier()?,
+        };
+        locations.get(&name).cloned().ok_or(LangError::ParseError {
+            position,
+            message: format!("location {} is not declared", name),
+        })
+    };
+
+    let first = parser.identifier()?;
+    let (id, source) = match parser.accept(&Token::Colon) {
+        true => (Some(first), location(parser, None)?),
+        false => (None, location(parser, Some(first))?),
+    };
+    parser.expect(&Token::Minus, "'-' before the action")?;
+    let action = parser.identifier()?;
+    let action = if parser.accept(&Token::Question) {
+        Channel::new_input(&action)
+    } else {
+        parser.expect(&Token::Bang, "'?' or '!' after the action")?;
+        Channel::new_output(&action)
+    };
+    let guard = match parser.accept_keyword("when") {
+        true => Guard::new(&parser.expression()?),
+        false => Guard::new_true(),
+    };
+    let update = match parser.accept_keyword("do") {
+        true => Update::new(&parser.statement()?),
+        false => Update::empty(),
+    };
+    parser.expect(&Token::Arrow, "'->' before the target")?;
+    let target = location(parser, None)?;
+
+    let edge = Edge::new(&source, &action, &guard, &update, &target);
+    Ok(match id {
+        Some(id) => edge.with_id(&id),
+        None => edge,
+    })
+}
+
+/// Prints the automaton in the format read by `parse`, declaring every variable
+/// of the initial environment. Locations other than normal and initial ones are
+/// printed as normal locations, and only names made of letters, digits, `_` and
+/// `.` can be read back.
+pub(crate) fn print(automaton: &Automaton) -> String {
+    let mut lines = vec![format!("automaton {}", automaton.name)];
+    for (identifier, value) in automaton.get_initial_environment().sorted_entries() {
+        lines.push(format!("var {} = {}", identifier, value));
+    }
+    for location in automaton.locations_sorted() {
+        let mut line = format!("location {}", location.get_name());
+        if let Location::Initial { .. } = location {
+            line.push_str(" init");
+        }
+        if let Location::Normal { invariant, .. } | Location::Initial { invariant, .. } = location {
+            if invariant.node != Expression::Literal(Value::new_true()) {
+                line.push_str(&format!(" invariant {}", invariant));
+            }
+        }
+        lines.push(line);
+    }
+    for edge in automaton.edges_sorted() {
+        let mut line = String::new();
+        if let Some(id) = edge.id() {
+            line.push_str(&format!("{}: ", id));
+        }
+        line.push_str(&format!("{} -{}", edge.source.get_name(), edge.action));
+        if edge.guard != Guard::new_true() {
+            line.push_str(&format!(" when {}", edge.guard));
+        }
+        if let Some(statement) = &edge.update.node {
+            line.push_str(&format!(" do {}", statement.to_string()));
+        }
+        line.push_str(&format!("-> {}", edge.target.get_name()));
+        lines.push(line);
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, error::Error, guard::Guard,
+            invariant::Invariant, location::Location, update::Update,
+        },
+        language::{
+            environment::Environment, expression::Expression, statement::Statement, value::Value,
+        },
+    };
+
+    const LAMP: &str = "
+        # A lamp which can only be switched on while it is off
+        automaton lamp
+        var on = false
+        var presses = false
+        location off init
+        location lit invariant on || (presses && true)
+        press: off -press? when !on do on = true; presses = true-> lit
+        lit -release! do if on then on = false-> off
+    ";
+
+    #[test]
+    fn parse_automaton() {
+        let off = Location::new_initial("off", &Invariant::new_true());
+        let lit = Location::new_normal(
+            "lit",
+            &Invariant::new(&Expression::new_logical_or(
+                &Expression::new_identifier("on"),
+                &Expression::new_parenthesized(&Expression::new_logical_and(
+                    &Expression::new_identifier("presses"),
+                    &Expression::new_boolean(&true),
+                )),
+            )),
+        );
+        let edges = HashSet::from([
+            Edge::new(
+                &off,
+                &Channel::new_input("press"),
+                &Guard::new(&Expression::new_logical_not(&Expression::new_identifier(
+                    "on",
+                ))),
+                &Update::new(&Statement::new_sequence(&[
+                    Statement::new_simple_assignment("on", &Value::new_true()),
+                    Statement::new_simple_assignment("presses", &Value::new_true()),
+                ])),
+                &lit,
+            )
+            .with_id("press"),
+            Edge::new(
+                &lit,
+                &Channel::new_output("release"),
+                &Guard::new_true(),
+                &Update::new(&Statement::new_if(
+                    &Expression::new_identifier("on"),
+                    &Statement::new_simple_assignment("on", &Value::new_false()),
+                    None,
+                )),
+                &off,
+            ),
+        ]);
+        let mut environment = Environment::new_empty();
+        environment.insert("on", &Value::new_false());
+        environment.insert("presses", &Value::new_false());
+        let expected = Automaton::new("lamp", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+
+        let automaton = Automaton::parse(LAMP).ok().unwrap();
+
+        assert_eq!(automaton.name, "lamp");
+        assert!(automaton.structurally_equal(&expected));
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let automaton = Automaton::parse(LAMP).ok().unwrap();
+
+        let text = automaton.to_dsl();
+        let reparsed = Automaton::parse(&text).ok().unwrap();
+
+        assert!(reparsed.structurally_equal(&automaton));
+        assert_eq!(reparsed.to_dsl(), text);
+    }
+
+    #[test]
+    fn parse_reports_line_of_syntax_error() {
+        let result = Automaton::parse("location a init\na -go-> a");
+
+        assert!(matches!(result, Err(Error::ParseError { line: 2, .. })));
+    }
+
+    #[test]
+    fn parse_rejects_undeclared_location() {
+        let result = Automaton::parse("location a init\na -go?-> b");
+
+        assert!(matches!(result, Err(Error::ParseError { line: 2, .. })));
+    }
+
+    #[test]
+    fn parse_delegates_validation() {
+        let result = Automaton::parse("location a init\nlocation b init\na -go?-> b");
+
+        assert!(matches!(result, Err(Error::TooManyInitialLocations { .. })));
+    }
+}
@@ -1,7 +1,8 @@
 use std::fmt::Debug;
 use std::fmt::Display;
 
-use crate::language::evaluation::Evaluation;
+use crate::language::environment::Environment;
+use crate::language::error::Error;
 use crate::language::interpreter::Interpreter;
 use crate::transition_system::state::State;
 
@@ -10,8 +11,11 @@ use super::guard::Guard;
 use super::location::*;
 use super::update::Update;
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+/// Edges are ordered by their fields in declaration order, such that edges
+/// without an id come first.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Edge {
+    pub id: Option<String>,
     pub source: Location,
     pub action: Channel,
     pub guard: Guard,
@@ -28,6 +32,7 @@ impl Edge {
         target: &Location,
     ) -> Self {
         Self {
+            id: None,
             source: source.clone(),
             action: action.clone(),
             guard: guard.clone(),
@@ -38,6 +43,7 @@ impl Edge {
 
     pub fn new_loop(location: &Location, action: &Channel, guard: &Guard, update: &Update) -> Self {
         Self {
+            id: None,
             source: location.clone(),
             action: action.clone(),
             guard: guard.clone(),
@@ -46,44 +52,87 @@ impl Edge {
         }
     }
 
-    pub fn execute(&self, state: &State) -> State {
-        if let Some(update) = self.update.node.clone() {
-            let mut interpreter = Interpreter::new(&state.environment);
-            let evaluation = interpreter.eval_statement(&update);
-            if evaluation != None {
-                // TODO: Handle failed evaluations
-                panic!("Edge update execution failed");
-            }
-            State::new(&self.target, &interpreter.get_environment())
-        } else {
-            State::new(&self.target, &state.environment)
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Returns a copy of the edge labelled with the given id. Edges that are
+    /// otherwise identical but carry different ids are distinct edges.
+    pub fn with_id(&self, id: &str) -> Self {
+        Self {
+            id: Some(String::from(id)),
+            ..self.clone()
         }
     }
 
-    pub fn enabled(&self, state: &State) -> bool {
-        if self.source != state.location {
-            return false;
+    /// Returns a copy of the edge where the id is prefixed with the name of an
+    /// automaton, such that ids stay unique when automata are composed.
+    pub fn with_prefixed_id(&self, prefix: &str) -> Self {
+        match &self.id {
+            Some(id) => self.with_id(&format!("{}.{}", prefix, id)),
+            None => self.clone(),
         }
+    }
 
+    /// The state reached by taking the edge, or the error of its update when it
+    /// cannot be executed in the state.
+    pub fn execute(&self, state: &State) -> Result<State, Error> {
+        let Some(update) = &self.update.node else {
+            return Ok(State::new(&self.target, &state.environment));
+        };
         let mut interpreter = Interpreter::new(&state.environment);
-        let evaluation_result = interpreter.eval_expression(&self.guard.node);
-        if evaluation_result.is_err() {
-            return false;
+        match interpreter.eval_statement(update) {
+            Some(error) => Err(error),
+            None => Ok(State::new(&self.target, &interpreter.get_environment())),
         }
+    }
 
-        return if let Some(evaluation) = evaluation_result.ok() {
-            match evaluation {
-                Evaluation::Bool(value) => value,
-                Evaluation::Void => panic!("Guard evaluation was void should have been boolean"),
-            }
-        } else {
-            false
-        };
+    /// As `try_enabled`, where an edge whose guard fails to evaluate is disabled.
+    pub fn enabled(&self, state: &State) -> bool {
+        self.try_enabled(state).unwrap_or(false)
+    }
+
+    /// Whether the edge leaves the location of the state and its guard holds
+    /// there, or the error of a guard failing to evaluate to a boolean, such
+    /// as a void one or one reading a variable the state does not have.
+    pub fn try_enabled(&self, state: &State) -> Result<bool, Error> {
+        self.try_enabled_with(state, &mut Interpreter::empty())
+    }
+
+    /// As `try_enabled` but evaluates the guard with the interpreter, such that
+    /// one configured once, e.g. with fuel, can be reused across calls. The state
+    /// is overlaid on the environment of the interpreter rather than copied into it.
+    pub fn try_enabled_with(
+        &self,
+        state: &State,
+        interpreter: &mut Interpreter,
+    ) -> Result<bool, Error> {
+        if self.source != state.location {
+            return Ok(false);
+        }
+
+        interpreter
+            .eval_with_overlay(&self.guard.node, &state.environment)
+            .and_then(bool::try_from)
+    }
+
+    /// Whether the edge would be enabled if the variables of the overlay had
+    /// their values in it rather than in the state, e.g. for inputs supplied by
+    /// the environment of a component.
+    pub fn enabled_with_inputs(&self, state: &State, overlay: &Environment) -> bool {
+        self.source == state.location
+            && Interpreter::new(&state.environment)
+                .eval_with_overlay(&self.guard.node, overlay)
+                .and_then(bool::try_from)
+                .unwrap_or(false)
     }
 }
 
 impl Display for Edge {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(id) = &self.id {
+            f.write_fmt(format_args!("{}: ", id))?;
+        }
         f.write_fmt(format_args!(
             "{} -({}, {}, {})-> {}",
             self.source, self.action, self.guard, self.update, self.target
@@ -98,7 +147,14 @@ mod tests {
         hash::{Hash, Hasher},
     };
 
-    use crate::automatom::{channel::Channel, invariant::Invariant, location::Location};
+    use crate::{
+        automatom::{channel::Channel, invariant::Invariant, location::Location},
+        language::{
+            environment::Environment, error::Error, expression::Expression,
+            interpreter::Interpreter, statement::Statement, value::Value,
+        },
+        transition_system::state::State,
+    };
 
     use super::{Edge, Guard, Update};
 
@@ -133,4 +189,131 @@ mod tests {
         assert_eq!(in_edge, out_edge);
         assert_eq!(in_hash, out_hash);
     }
+
+    #[test]
+    fn edge_with_id() {
+        let location = Location::new_initial("initial", &Invariant::new_true());
+        let channel = Channel::new_output("channel");
+        let edge = Edge::new_loop(&location, &channel, &Guard::new_true(), &Update::default());
+        assert_eq!(edge.id(), None);
+
+        let labelled = edge.with_id("e1");
+        assert_eq!(labelled.id(), Some("e1"));
+        assert!(labelled.to_string().starts_with("e1: "));
+        assert_ne!(edge, labelled);
+    }
+
+    #[test]
+    fn edge_with_prefixed_id() {
+        let location = Location::new_initial("initial", &Invariant::new_true());
+        let channel = Channel::new_output("channel");
+        let edge = Edge::new_loop(&location, &channel, &Guard::new_true(), &Update::default());
+        assert_eq!(edge.with_prefixed_id("A").id(), None);
+        assert_eq!(edge.with_id("e1").with_prefixed_id("A").id(), Some("A.e1"));
+    }
+
+    #[test]
+    fn edge_enabled_with_inputs_overlays_state() {
+        let location = Location::new_initial("initial", &Invariant::new_true());
+        let guard = Guard::new(&Expression::new_logical_and(
+            &Expression::new_identifier("input"),
+            &Expression::new_identifier("ready"),
+        ));
+        let edge = Edge::new_loop(
+            &location,
+            &Channel::new_input("in"),
+            &guard,
+            &Update::default(),
+        );
+        let mut environment = Environment::new_empty();
+        environment.insert("input", &Value::new_false());
+        environment.insert("ready", &Value::new_true());
+        let state = State::new(&location, &environment);
+        let mut inputs = Environment::new_empty();
+        inputs.insert("input", &Value::new_true());
+        let mut not_ready = Environment::new_empty();
+        not_ready.insert("ready", &Value::new_false());
+
+        assert!(!edge.enabled(&state));
+        // The overlay shadows input while ready is still read from the state
+        assert!(edge.enabled_with_inputs(&state, &inputs));
+        assert!(!edge.enabled_with_inputs(&state, &not_ready));
+        assert_eq!(state.environment, environment);
+    }
+
+    #[test]
+    fn edge_non_boolean_guard_is_err() {
+        let location = Location::new_initial("initial", &Invariant::new_true());
+        let clock_guard = Guard::new(&Expression::new_clock(3));
+        let identifier_guard = Guard::new(&Expression::new_identifier("missing"));
+        let state = State::new(&location, &Environment::new_empty());
+
+        for guard in [clock_guard, identifier_guard] {
+            let edge = Edge::new_loop(
+                &location,
+                &Channel::new_output("out"),
+                &guard,
+                &Update::default(),
+            );
+
+            assert!(edge.try_enabled(&state).is_err());
+            assert!(!edge.enabled(&state));
+        }
+    }
+
+    #[test]
+    fn edge_execute_failing_update_is_err() {
+        let location = Location::new_initial("initial", &Invariant::new_true());
+        let update = Update::new(&Statement::new_simple_assignment(
+            "missing",
+            &Value::new_true(),
+        ));
+        let edge = Edge::new_loop(
+            &location,
+            &Channel::new_output("out"),
+            &Guard::new_true(),
+            &update,
+        );
+        let state = State::new(&location, &Environment::new_empty());
+
+        let result = edge.execute(&state);
+
+        assert!(matches!(result, Err(Error::RuntimeError { .. })));
+    }
+
+    #[test]
+    fn edge_try_enabled_with_reuses_interpreter() {
+        let location = Location::new_initial("initial", &Invariant::new_true());
+        let other = Location::new_normal("other", &Invariant::new_true());
+        let guard = Guard::new(&Expression::new_logical_not(&Expression::new_identifier(
+            "ready",
+        )));
+        let edge = Edge::new_loop(
+            &location,
+            &Channel::new_output("out"),
+            &guard,
+            &Update::default(),
+        );
+        let mut ready = Environment::new_empty();
+        ready.insert("ready", &Value::new_true());
+        let mut idle = Environment::new_empty();
+        idle.insert("ready", &Value::new_false());
+        let mut interpreter = Interpreter::with_fuel(&Environment::new_empty(), 2);
+        let mut starved = Interpreter::with_fuel(&Environment::new_empty(), 1);
+
+        let ready = State::new(&location, &ready);
+        let idle_elsewhere = State::new(&other, &idle);
+        let idle = State::new(&location, &idle);
+        assert_eq!(edge.try_enabled_with(&ready, &mut interpreter), Ok(false));
+        assert_eq!(edge.try_enabled_with(&idle, &mut interpreter), Ok(true));
+        assert_eq!(
+            edge.try_enabled_with(&idle_elsewhere, &mut interpreter),
+            Ok(false)
+        );
+        assert!(matches!(
+            edge.try_enabled_with(&idle, &mut starved),
+            Err(Error::FuelExhausted { .. })
+        ));
+        assert_eq!(interpreter.stats().lookups, 2);
+    }
 }
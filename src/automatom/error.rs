@@ -1,60 +1,1175 @@
-use crate::language::lang_type::LangType;
+use crate::language::{
+    environment::{Environment, MissingIdentifier, SUGGESTION_DISTANCE},
+    error::Severity,
+    lang_type::LangType,
+};
 
 use super::{channel::Channel, edge::Edge, location::Location};
-use std::collections::HashSet;
+use std::{collections::HashSet, fmt::Debug};
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq, Eq)]
+fn display_occurrences(occurrences: &[MissingIdentifier]) -> String {
+    occurrences
+        .iter()
+        .map(|occurrence| occurrence.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn display_errors(errors: &[Error]) -> String {
+    errors
+        .iter()
+        .map(|error| error.to_string())
+        .collect::<Vec<String>>()
+        .join("; ")
+}
+
+/// Formats the set sorted, such that messages do not depend on the iteration order.
+fn display_sorted<T: Debug + Ord>(set: &HashSet<T>) -> String {
+    let mut elements: Vec<&T> = set.iter().collect();
+    elements.sort();
+    format!("{:?}", elements)
+}
+
+#[derive(Error, Debug, Clone)]
 pub enum Error {
-    #[error("Automaton {automaton:} is missing an initial location")]
-    MissingInitialLocation { automaton: String },
-    #[error("Automaton {automaton:} is empty")]
-    EmptyAutomaton { automaton: String },
+    #[error("{code}: Automaton {automaton:} is missing an initial location", code = self.code())]
+    MissingInitialLocation {
+        automaton: String,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Automaton {automaton:} is empty", code = self.code())]
+    EmptyAutomaton {
+        automaton: String,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
     #[error(
-        "Automaton {automaton:} actions are not partitioned, violating actions is {violating:?}"
+        "{code}: Automaton {automaton:} actions are not partitioned, violating actions is {}", display_sorted(.violating), code = self.code()
     )]
     PartitionError {
         automaton: String,
         violating: HashSet<Channel>,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Automaton {automaton:} is not composable with {other:}, both output {}", display_sorted(.violating), code = self.code())]
+    OverlappingOutputs {
+        automaton: String,
+        other: String,
+        violating: HashSet<Channel>,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Automaton {automaton:} cannot be determinized as the {action:} edges from {locations:} have different updates", code = self.code())]
+    NondeterministicUpdateConflict {
+        automaton: String,
+        locations: String,
+        action: Channel,
+        source_location: Option<&'static (&'static str, u32)>,
     },
-    #[error("Automaton {automaton:} has too many initial locations: {:?}")]
+    #[error("{code}: Automaton {automaton:} has too many initial locations: {}", display_sorted(.initials), code = self.code())]
     TooManyInitialLocations {
         automaton: String,
         initials: HashSet<Location>,
+        source_location: Option<&'static (&'static str, u32)>,
     },
-    #[error("Automaton {automaton:} {location:} is an inconsistent initial location")]
+    #[error("{code}: Automaton {automaton:} {location:} is an inconsistent initial location", code = self.code())]
     InconsistentInitialLocation {
         automaton: String,
-        location: Location,
+        location: Box<Location>,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Automaton {automaton:} {location:} invariant could not be evaluated: {message:}", code = self.code())]
+    InvariantEvaluationFailed {
+        automaton: String,
+        location: Box<Location>,
+        message: String,
+        source_location: Option<&'static (&'static str, u32)>,
     },
-    #[error("Automaton {automaton:} {location:} is missing the identifiers {identifiers:?}")]
+    #[error("{code}: Automaton {automaton:} {location:} is missing the identifiers {identifiers:?}", code = self.code())]
     LocationInvariantMissingIdentifiers {
         automaton: String,
-        location: Location,
+        location: Box<Location>,
         identifiers: Vec<String>,
+        source_location: Option<&'static (&'static str, u32)>,
     },
-    #[error("Automaton {automaton:} edge {:}-{:}->{:} guard {:} is missing the identifiers {missing:?}", .edge.source, .edge.action, edge.target, .edge.guard)]
+    #[error("{code}: Automaton {automaton:} edge {:}-{:}->{:} guard {:} is missing the identifiers {missing:?} ({})", .edge.source, .edge.action, edge.target, .edge.guard, display_occurrences(.occurrences), code = self.code())]
     MissingIdentifiersInEdgeGuard {
         automaton: String,
-        edge: Edge,
+        edge: Box<Edge>,
         missing: Vec<String>,
+        occurrences: Vec<MissingIdentifier>,
+        source_location: Option<&'static (&'static str, u32)>,
     },
-    #[error("Automaton {automaton:} edge {:}-{:}->{:} guard {:} is not {:} but instead {:}", .edge.source, .edge.action, edge.target, .edge.guard, LangType::Logical, actual)]
+    #[error("{code}: Automaton {automaton:} edge {:}-{:}->{:} guard {:} is not {:} but instead {:}", .edge.source, .edge.action, edge.target, .edge.guard, LangType::Logical, actual, code = self.code())]
     EdgeGuardIsNotLogical {
         automaton: String,
-        edge: Edge,
+        edge: Box<Edge>,
         actual: LangType,
+        source_location: Option<&'static (&'static str, u32)>,
     },
-    #[error("Automaton {automaton:} edge {:}-{:}->{:} update {:} is missing the identifiers {missing:?}", .edge.source, .edge.action, edge.target, .edge.update)]
+    #[error("{code}: Automaton {automaton:} edge {:}-{:}->{:} update {:} is missing the identifiers {missing:?} ({})", .edge.source, .edge.action, edge.target, .edge.update, display_occurrences(.occurrences), code = self.code())]
     MissingIdentifiersInEdgeUpdate {
         automaton: String,
-        edge: Edge,
+        edge: Box<Edge>,
         missing: Vec<String>,
+        occurrences: Vec<MissingIdentifier>,
+        source_location: Option<&'static (&'static str, u32)>,
     },
-    #[error("Automaton {automaton:} {location:} is missing the identifiers {missing:?}")]
+    #[error("{code}: Automaton {automaton:} edge {:}-{:}->{:} update {:} assigns a {actual:} value to {identifier:} of type {expected:}", .edge.source, .edge.action, edge.target, .edge.update, code = self.code())]
+    EdgeUpdateTypeMismatch {
+        automaton: String,
+        edge: Box<Edge>,
+        identifier: String,
+        expected: LangType,
+        actual: LangType,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Automaton {automaton:} {location:} is missing the identifiers {missing:?} ({})", display_occurrences(.occurrences), code = self.code())]
     MissingIdentifiersInLocationInvariant {
         automaton: String,
-        location: Location,
+        location: Box<Location>,
         missing: Vec<String>,
+        occurrences: Vec<MissingIdentifier>,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Automata {automaton:} and {other:} both have a location named {name:}", code = self.code())]
+    DuplicateLocationName {
+        automaton: String,
+        other: String,
+        name: String,
+        source_location: Option<&'static (&'static str, u32)>,
     },
+    #[error("{code}: Automata {automaton:} and {other:} both declare the variable {identifier:}", code = self.code())]
+    DuplicateVariableName {
+        automaton: String,
+        other: String,
+        identifier: String,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Automaton {automaton:} edge {:}-{:}->{:} update {:} cannot be reversed", .edge.source, .edge.action, edge.target, .edge.update, code = self.code())]
+    IrreversibleUpdate {
+        automaton: String,
+        edge: Box<Edge>,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: {} errors: {}", .errors.len(), display_errors(.errors), code = self.code())]
+    AggregatedError {
+        errors: Vec<Error>,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Could not parse line {line:}: {message:}", code = self.code())]
+    ParseError {
+        line: usize,
+        message: String,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Automaton {automaton:} has {count:} variables, more than the {limit:} whose assignments can be enumerated", code = self.code())]
+    TooManyVariables {
+        automaton: String,
+        count: usize,
+        limit: usize,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Variable {identifier:} of automaton {automaton:} is initially {value:}, but only boolean variables can be explored symbolically", code = self.code())]
+    NonBooleanVariable {
+        automaton: String,
+        identifier: String,
+        value: String,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Automaton {automaton:} edge {:}-{:}->{:} update {:} is ill-typed: {message:}", .edge.source, .edge.action, edge.target, .edge.update, code = self.code())]
+    EdgeUpdateIsIllTyped {
+        automaton: String,
+        edge: Box<Edge>,
+        message: String,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Automaton {automaton:} edge {:}-{:}->{:} guard {:} is ill-typed: {message:}", .edge.source, .edge.action, edge.target, .edge.guard, code = self.code())]
+    EdgeGuardIsIllTyped {
+        automaton: String,
+        edge: Box<Edge>,
+        message: String,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Automaton {automaton:} {location:} invariant is not {:} but instead {:}", LangType::Logical, actual, code = self.code())]
+    LocationInvariantIsNotLogical {
+        automaton: String,
+        location: Box<Location>,
+        actual: LangType,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+    #[error("{code}: Automaton {automaton:} {location:} invariant is ill-typed: {message:}", code = self.code())]
+    LocationInvariantIsIllTyped {
+        automaton: String,
+        location: Box<Location>,
+        message: String,
+        source_location: Option<&'static (&'static str, u32)>,
+    },
+}
+
+/// Errors are equal if they report the same problem, regardless of where in
+/// this crate they were constructed.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::MissingInitialLocation {
+                    automaton: l_automaton,
+                    ..
+                },
+                Self::MissingInitialLocation {
+                    automaton: r_automaton,
+                    ..
+                },
+            ) => l_automaton == r_automaton,
+            (
+                Self::EmptyAutomaton {
+                    automaton: l_automaton,
+                    ..
+                },
+                Self::EmptyAutomaton {
+                    automaton: r_automaton,
+                    ..
+                },
+            ) => l_automaton == r_automaton,
+            (
+                Self::PartitionError {
+                    automaton: l_automaton,
+                    violating: l_violating,
+                    ..
+                },
+                Self::PartitionError {
+                    automaton: r_automaton,
+                    violating: r_violating,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_violating == r_violating,
+            (
+                Self::OverlappingOutputs {
+                    automaton: l_automaton,
+                    other: l_other,
+                    violating: l_violating,
+                    ..
+                },
+                Self::OverlappingOutputs {
+                    automaton: r_automaton,
+                    other: r_other,
+                    violating: r_violating,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_other == r_other && l_violating == r_violating,
+            (
+                Self::NondeterministicUpdateConflict {
+                    automaton: l_automaton,
+                    locations: l_locations,
+                    action: l_action,
+                    ..
+                },
+                Self::NondeterministicUpdateConflict {
+                    automaton: r_automaton,
+                    locations: r_locations,
+                    action: r_action,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_locations == r_locations && l_action == r_action,
+            (
+                Self::TooManyInitialLocations {
+                    automaton: l_automaton,
+                    initials: l_initials,
+                    ..
+                },
+                Self::TooManyInitialLocations {
+                    automaton: r_automaton,
+                    initials: r_initials,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_initials == r_initials,
+            (
+                Self::InconsistentInitialLocation {
+                    automaton: l_automaton,
+                    location: l_location,
+                    ..
+                },
+                Self::InconsistentInitialLocation {
+                    automaton: r_automaton,
+                    location: r_location,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_location == r_location,
+            (
+                Self::InvariantEvaluationFailed {
+                    automaton: l_automaton,
+                    location: l_location,
+                    message: l_message,
+                    ..
+                },
+                Self::InvariantEvaluationFailed {
+                    automaton: r_automaton,
+                    location: r_location,
+                    message: r_message,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_location == r_location && l_message == r_message,
+            (
+                Self::LocationInvariantMissingIdentifiers {
+                    automaton: l_automaton,
+                    location: l_location,
+                    identifiers: l_identifiers,
+                    ..
+                },
+                Self::LocationInvariantMissingIdentifiers {
+                    automaton: r_automaton,
+                    location: r_location,
+                    identifiers: r_identifiers,
+                    ..
+                },
+            ) => {
+                l_automaton == r_automaton
+                    && l_location == r_location
+                    && l_identifiers == r_identifiers
+            }
+            (
+                Self::MissingIdentifiersInEdgeGuard {
+                    automaton: l_automaton,
+                    edge: l_edge,
+                    missing: l_missing,
+                    occurrences: l_occurrences,
+                    ..
+                },
+                Self::MissingIdentifiersInEdgeGuard {
+                    automaton: r_automaton,
+                    edge: r_edge,
+                    missing: r_missing,
+                    occurrences: r_occurrences,
+                    ..
+                },
+            ) => {
+                l_automaton == r_automaton
+                    && l_edge == r_edge
+                    && l_missing == r_missing
+                    && l_occurrences == r_occurrences
+            }
+            (
+                Self::EdgeGuardIsNotLogical {
+                    automaton: l_automaton,
+                    edge: l_edge,
+                    actual: l_actual,
+                    ..
+                },
+                Self::EdgeGuardIsNotLogical {
+                    automaton: r_automaton,
+                    edge: r_edge,
+                    actual: r_actual,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_edge == r_edge && l_actual == r_actual,
+            (
+                Self::MissingIdentifiersInEdgeUpdate {
+                    automaton: l_automaton,
+                    edge: l_edge,
+                    missing: l_missing,
+                    occurrences: l_occurrences,
+                    ..
+                },
+                Self::MissingIdentifiersInEdgeUpdate {
+                    automaton: r_automaton,
+                    edge: r_edge,
+                    missing: r_missing,
+                    occurrences: r_occurrences,
+                    ..
+                },
+            ) => {
+                l_automaton == r_automaton
+                    && l_edge == r_edge
+                    && l_missing == r_missing
+                    && l_occurrences == r_occurrences
+            }
+            (
+                Self::EdgeUpdateTypeMismatch {
+                    automaton: l_automaton,
+                    edge: l_edge,
+                    identifier: l_identifier,
+                    expected: l_expected,
+                    actual: l_actual,
+                    ..
+                },
+                Self::EdgeUpdateTypeMismatch {
+                    automaton: r_automaton,
+                    edge: r_edge,
+                    identifier: r_identifier,
+                    expected: r_expected,
+                    actual: r_actual,
+                    ..
+                },
+            ) => {
+                l_automaton == r_automaton
+                    && l_edge == r_edge
+                    && l_identifier == r_identifier
+                    && l_expected == r_expected
+                    && l_actual == r_actual
+            }
+            (
+                Self::MissingIdentifiersInLocationInvariant {
+                    automaton: l_automaton,
+                    location: l_location,
+                    missing: l_missing,
+                    occurrences: l_occurrences,
+                    ..
+                },
+                Self::MissingIdentifiersInLocationInvariant {
+                    automaton: r_automaton,
+                    location: r_location,
+                    missing: r_missing,
+                    occurrences: r_occurrences,
+                    ..
+                },
+            ) => {
+                l_automaton == r_automaton
+                    && l_location == r_location
+                    && l_missing == r_missing
+                    && l_occurrences == r_occurrences
+            }
+            (
+                Self::DuplicateLocationName {
+                    automaton: l_automaton,
+                    other: l_other,
+                    name: l_name,
+                    ..
+                },
+                Self::DuplicateLocationName {
+                    automaton: r_automaton,
+                    other: r_other,
+                    name: r_name,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_other == r_other && l_name == r_name,
+            (
+                Self::DuplicateVariableName {
+                    automaton: l_automaton,
+                    other: l_other,
+                    identifier: l_identifier,
+                    ..
+                },
+                Self::DuplicateVariableName {
+                    automaton: r_automaton,
+                    other: r_other,
+                    identifier: r_identifier,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_other == r_other && l_identifier == r_identifier,
+            (
+                Self::IrreversibleUpdate {
+                    automaton: l_automaton,
+                    edge: l_edge,
+                    ..
+                },
+                Self::IrreversibleUpdate {
+                    automaton: r_automaton,
+                    edge: r_edge,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_edge == r_edge,
+            (
+                Self::AggregatedError {
+                    errors: l_errors, ..
+                },
+                Self::AggregatedError {
+                    errors: r_errors, ..
+                },
+            ) => l_errors == r_errors,
+            (
+                Self::ParseError {
+                    line: l_line,
+                    message: l_message,
+                    ..
+                },
+                Self::ParseError {
+                    line: r_line,
+                    message: r_message,
+                    ..
+                },
+            ) => l_line == r_line && l_message == r_message,
+            (
+                Self::TooManyVariables {
+                    automaton: l_automaton,
+                    count: l_count,
+                    limit: l_limit,
+                    ..
+                },
+                Self::TooManyVariables {
+                    automaton: r_automaton,
+                    count: r_count,
+                    limit: r_limit,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_count == r_count && l_limit == r_limit,
+            (
+                Self::NonBooleanVariable {
+                    automaton: l_automaton,
+                    identifier: l_identifier,
+                    value: l_value,
+                    ..
+                },
+                Self::NonBooleanVariable {
+                    automaton: r_automaton,
+                    identifier: r_identifier,
+                    value: r_value,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_identifier == r_identifier && l_value == r_value,
+            (
+                Self::EdgeUpdateIsIllTyped {
+                    automaton: l_automaton,
+                    edge: l_edge,
+                    message: l_message,
+                    ..
+                },
+                Self::EdgeUpdateIsIllTyped {
+                    automaton: r_automaton,
+                    edge: r_edge,
+                    message: r_message,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_edge == r_edge && l_message == r_message,
+            (
+                Self::EdgeGuardIsIllTyped {
+                    automaton: l_automaton,
+                    edge: l_edge,
+                    message: l_message,
+                    ..
+                },
+                Self::EdgeGuardIsIllTyped {
+                    automaton: r_automaton,
+                    edge: r_edge,
+                    message: r_message,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_edge == r_edge && l_message == r_message,
+            (
+                Self::LocationInvariantIsNotLogical {
+                    automaton: l_automaton,
+                    location: l_location,
+                    actual: l_actual,
+                    ..
+                },
+                Self::LocationInvariantIsNotLogical {
+                    automaton: r_automaton,
+                    location: r_location,
+                    actual: r_actual,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_location == r_location && l_actual == r_actual,
+            (
+                Self::LocationInvariantIsIllTyped {
+                    automaton: l_automaton,
+                    location: l_location,
+                    message: l_message,
+                    ..
+                },
+                Self::LocationInvariantIsIllTyped {
+                    automaton: r_automaton,
+                    location: r_location,
+                    message: r_message,
+                    ..
+                },
+            ) => l_automaton == r_automaton && l_location == r_location && l_message == r_message,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Error {}
+
+impl Error {
+    /// A stable machine readable code of the variant, which is never reused
+    /// for another variant once assigned.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::MissingInitialLocation { .. } => "AUT0001",
+            Error::EmptyAutomaton { .. } => "AUT0002",
+            Error::PartitionError { .. } => "AUT0003",
+            Error::OverlappingOutputs { .. } => "AUT0004",
+            Error::NondeterministicUpdateConflict { .. } => "AUT0005",
+            Error::TooManyInitialLocations { .. } => "AUT0006",
+            Error::InconsistentInitialLocation { .. } => "AUT0007",
+            Error::InvariantEvaluationFailed { .. } => "AUT0008",
+            Error::LocationInvariantMissingIdentifiers { .. } => "AUT0009",
+            Error::MissingIdentifiersInEdgeGuard { .. } => "AUT0010",
+            Error::EdgeGuardIsNotLogical { .. } => "AUT0011",
+            Error::MissingIdentifiersInEdgeUpdate { .. } => "AUT0012",
+            Error::EdgeUpdateTypeMismatch { .. } => "AUT0013",
+            Error::MissingIdentifiersInLocationInvariant { .. } => "AUT0014",
+            Error::DuplicateLocationName { .. } => "AUT0015",
+            Error::DuplicateVariableName { .. } => "AUT0016",
+            Error::IrreversibleUpdate { .. } => "AUT0017",
+            Error::AggregatedError { .. } => "AUT0018",
+            Error::ParseError { .. } => "AUT0019",
+            Error::TooManyVariables { .. } => "AUT0020",
+            Error::NonBooleanVariable { .. } => "AUT0021",
+            Error::EdgeUpdateIsIllTyped { .. } => "AUT0022",
+            Error::EdgeGuardIsIllTyped { .. } => "AUT0023",
+            Error::LocationInvariantIsNotLogical { .. } => "AUT0024",
+            Error::LocationInvariantIsIllTyped { .. } => "AUT0025",
+        }
+    }
+
+    /// Every error is currently fatal, warnings are reserved for lints.
+    pub fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    /// The file and line in this crate where the error was constructed, if recorded.
+    pub fn source_location(&self) -> Option<(&'static str, u32)> {
+        match self {
+            Error::MissingInitialLocation {
+                source_location, ..
+            }
+            | Error::EmptyAutomaton {
+                source_location, ..
+            }
+            | Error::PartitionError {
+                source_location, ..
+            }
+            | Error::OverlappingOutputs {
+                source_location, ..
+            }
+            | Error::NondeterministicUpdateConflict {
+                source_location, ..
+            }
+            | Error::TooManyInitialLocations {
+                source_location, ..
+            }
+            | Error::InconsistentInitialLocation {
+                source_location, ..
+            }
+            | Error::InvariantEvaluationFailed {
+                source_location, ..
+            }
+            | Error::LocationInvariantMissingIdentifiers {
+                source_location, ..
+            }
+            | Error::MissingIdentifiersInEdgeGuard {
+                source_location, ..
+            }
+            | Error::EdgeGuardIsNotLogical {
+                source_location, ..
+            }
+            | Error::MissingIdentifiersInEdgeUpdate {
+                source_location, ..
+            }
+            | Error::EdgeUpdateTypeMismatch {
+                source_location, ..
+            }
+            | Error::MissingIdentifiersInLocationInvariant {
+                source_location, ..
+            }
+            | Error::DuplicateLocationName {
+                source_location, ..
+            }
+            | Error::DuplicateVariableName {
+                source_location, ..
+            }
+            | Error::IrreversibleUpdate {
+                source_location, ..
+            }
+            | Error::AggregatedError {
+                source_location, ..
+            }
+            | Error::ParseError {
+                source_location, ..
+            }
+            | Error::TooManyVariables {
+                source_location, ..
+            }
+            | Error::NonBooleanVariable {
+                source_location, ..
+            }
+            | Error::EdgeUpdateIsIllTyped {
+                source_location, ..
+            }
+            | Error::EdgeGuardIsIllTyped {
+                source_location, ..
+            }
+            | Error::LocationInvariantIsNotLogical {
+                source_location, ..
+            }
+            | Error::LocationInvariantIsIllTyped {
+                source_location, ..
+            } => source_location.copied(),
+        }
+    }
+
+    /// Combines the errors into one, where a single error is returned as is and
+    /// several are wrapped in an `AggregatedError`. None if there are no errors.
+    pub fn try_aggregate(mut errors: Vec<Error>) -> Option<Error> {
+        match errors.len() {
+            0 => None,
+            1 => errors.pop(),
+            _ => Some(Error::AggregatedError {
+                source_location: Some(&(file!(), line!())),
+                errors,
+            }),
+        }
+    }
+}
+
+/// The source line followed by a line marking every whole word occurrence of
+/// the identifiers with carets.
+fn underline(source: &str, identifiers: &[String]) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+    let mut marks = vec![' '; source.chars().count()];
+    for identifier in identifiers {
+        for (start, _) in source.match_indices(identifier.as_str()) {
+            let end = start + identifier.len();
+            let before = source[..start].chars().next_back();
+            let after = source[end..].chars().next();
+            if before.is_some_and(is_word) || after.is_some_and(is_word) {
+                continue;
+            }
+            let offset = source[..start].chars().count();
+            for mark in marks
+                .iter_mut()
+                .skip(offset)
+                .take(identifier.chars().count())
+            {
+                *mark = '^';
+            }
+        }
+    }
+    let marks: String = marks.into_iter().collect();
+    format!("  | {}\n  | {}", source, marks.trim_end())
+}
+
+impl Error {
+    /// Renders the error for a person reading the model. Missing identifiers
+    /// are underlined in the expression or statement they occur in, along
+    /// with the declared identifiers they are likely misspellings of. Every
+    /// error of an aggregated error is rendered on its own.
+    pub fn render(&self, automaton_name: &str, environment: &Environment) -> String {
+        let (summary, source, occurrences) = match self {
+            Error::AggregatedError { errors, .. } => {
+                let rendered: Vec<String> = errors
+                    .iter()
+                    .map(|error| error.render(automaton_name, environment))
+                    .collect();
+                return rendered.join("\n");
+            }
+            Error::MissingIdentifiersInEdgeGuard {
+                edge, occurrences, ..
+            } => (
+                format!("unknown identifiers in the guard of {}", display_edge(edge)),
+                edge.guard.to_string(),
+                occurrences,
+            ),
+            Error::MissingIdentifiersInEdgeUpdate {
+                edge, occurrences, ..
+            } => (
+                format!(
+                    "unknown identifiers in the update of {}",
+                    display_edge(edge)
+                ),
+                edge.update.to_string(),
+                occurrences,
+            ),
+            Error::MissingIdentifiersInLocationInvariant {
+                location,
+                occurrences,
+                ..
+            } => (
+                format!(
+                    "unknown identifiers in the invariant of {}",
+                    location.get_name()
+                ),
+                invariant_of(location),
+                occurrences,
+            ),
+            _ => return format!("{}: {}", self.severity(), self),
+        };
+
+        let mut missing: Vec<String> = Vec::new();
+        for occurrence in occurrences {
+            if !missing.contains(&occurrence.identifier) {
+                missing.push(occurrence.identifier.clone());
+            }
+        }
+        let mut lines = vec![
+            format!(
+                "{}: {}: {} in automaton {}",
+                self.severity(),
+                self.code(),
+                summary,
+                automaton_name
+            ),
+            underline(&source, &missing),
+        ];
+        for identifier in missing.iter() {
+            let close = environment.closest_identifiers(identifier, SUGGESTION_DISTANCE);
+            if !close.is_empty() {
+                let close: Vec<String> = close.iter().map(|name| format!("'{}'", name)).collect();
+                lines.push(format!(
+                    "  = help: '{}' is not declared, did you mean {}?",
+                    identifier,
+                    close.join(" or ")
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+fn display_edge(edge: &Edge) -> String {
+    format!(
+        "{} -{}-> {}",
+        edge.source.get_name(),
+        edge.action,
+        edge.target.get_name()
+    )
+}
+
+fn invariant_of(location: &Location) -> String {
+    match location {
+        Location::Normal { invariant, .. }
+        | Location::Initial { invariant, .. }
+        | Location::Conjunction { invariant, .. } => invariant.to_string(),
+        Location::Inconsistent { .. } => String::from("false"),
+        Location::Universal { .. } => String::from("true"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        language::{environment::Environment, error::Severity, lang_type::LangType, value::Value},
+    };
+
+    use super::Error;
+
+    /// One error of every variant.
+    fn every_variant() -> Vec<Error> {
+        every_variant_at(None)
+    }
+
+    fn every_variant_at(source_location: Option<&'static (&'static str, u32)>) -> Vec<Error> {
+        let automaton = String::from("a");
+        let other = String::from("b");
+        let location = Location::new_initial("l", &Invariant::new_true());
+        let action = Channel::new_output("out");
+        let edge = Edge::new_loop(&location, &action, &Guard::new_true(), &Update::empty());
+        vec![
+            Error::MissingInitialLocation {
+                automaton: automaton.clone(),
+                source_location,
+            },
+            Error::EmptyAutomaton {
+                automaton: automaton.clone(),
+                source_location,
+            },
+            Error::PartitionError {
+                automaton: automaton.clone(),
+                violating: HashSet::from([action.clone()]),
+                source_location,
+            },
+            Error::OverlappingOutputs {
+                automaton: automaton.clone(),
+                other: other.clone(),
+                violating: HashSet::from([action.clone()]),
+                source_location,
+            },
+            Error::NondeterministicUpdateConflict {
+                automaton: automaton.clone(),
+                locations: String::from("l"),
+                action: action.clone(),
+                source_location,
+            },
+            Error::TooManyInitialLocations {
+                automaton: automaton.clone(),
+                initials: HashSet::from([location.clone()]),
+                source_location,
+            },
+            Error::InconsistentInitialLocation {
+                automaton: automaton.clone(),
+                location: Box::new(location.clone()),
+                source_location,
+            },
+            Error::InvariantEvaluationFailed {
+                automaton: automaton.clone(),
+                location: Box::new(location.clone()),
+                message: String::from("void"),
+                source_location,
+            },
+            Error::LocationInvariantMissingIdentifiers {
+                automaton: automaton.clone(),
+                location: Box::new(location.clone()),
+                identifiers: vec![String::from("x")],
+                source_location,
+            },
+            Error::MissingIdentifiersInEdgeGuard {
+                automaton: automaton.clone(),
+                edge: Box::new(edge.clone()),
+                missing: vec![String::from("x")],
+                occurrences: Vec::new(),
+                source_location,
+            },
+            Error::EdgeGuardIsNotLogical {
+                automaton: automaton.clone(),
+                edge: Box::new(edge.clone()),
+                actual: LangType::Clock,
+                source_location,
+            },
+            Error::MissingIdentifiersInEdgeUpdate {
+                automaton: automaton.clone(),
+                edge: Box::new(edge.clone()),
+                missing: vec![String::from("x")],
+                occurrences: Vec::new(),
+                source_location,
+            },
+            Error::EdgeUpdateTypeMismatch {
+                automaton: automaton.clone(),
+                edge: Box::new(edge.clone()),
+                identifier: String::from("x"),
+                expected: LangType::Logical,
+                actual: LangType::Clock,
+                source_location,
+            },
+            Error::MissingIdentifiersInLocationInvariant {
+                automaton: automaton.clone(),
+                location: Box::new(location.clone()),
+                missing: vec![String::from("x")],
+                occurrences: Vec::new(),
+                source_location,
+            },
+            Error::DuplicateLocationName {
+                automaton: automaton.clone(),
+                other: other.clone(),
+                name: String::from("l"),
+                source_location,
+            },
+            Error::DuplicateVariableName {
+                automaton: automaton.clone(),
+                other,
+                identifier: String::from("x"),
+                source_location,
+            },
+            Error::IrreversibleUpdate {
+                automaton: automaton.clone(),
+                edge: Box::new(edge.clone()),
+                source_location,
+            },
+            Error::AggregatedError {
+                errors: Vec::new(),
+                source_location,
+            },
+            Error::ParseError {
+                line: 1,
+                message: String::from("expected a location"),
+                source_location,
+            },
+            Error::TooManyVariables {
+                automaton: automaton.clone(),
+                count: 64,
+                limit: 20,
+                source_location,
+            },
+            Error::NonBooleanVariable {
+                automaton: automaton.clone(),
+                identifier: String::from("x"),
+                value: String::from("0"),
+                source_location,
+            },
+            Error::EdgeUpdateIsIllTyped {
+                automaton: automaton.clone(),
+                edge: Box::new(edge.clone()),
+                message: String::from("Can only compare clocks"),
+                source_location,
+            },
+            Error::EdgeGuardIsIllTyped {
+                automaton: automaton.clone(),
+                edge: Box::new(edge),
+                message: String::from("Can only compare clocks"),
+                source_location,
+            },
+            Error::LocationInvariantIsNotLogical {
+                automaton: automaton.clone(),
+                location: Box::new(location.clone()),
+                actual: LangType::Clock,
+                source_location,
+            },
+            Error::LocationInvariantIsIllTyped {
+                automaton,
+                location: Box::new(location),
+                message: String::from("Can only compare clocks"),
+                source_location,
+            },
+        ]
+    }
+
+    #[test]
+    fn error_codes_are_unique() {
+        let errors = every_variant();
+
+        let codes: HashSet<&str> = errors.iter().map(Error::code).collect();
+
+        assert_eq!(codes.len(), errors.len());
+        assert!(codes.iter().all(|code| code.starts_with("AUT")));
+    }
+
+    #[test]
+    fn error_display_is_prefixed_by_code() {
+        for error in every_variant() {
+            assert!(
+                error
+                    .to_string()
+                    .starts_with(&format!("{}: ", error.code())),
+                "{}",
+                error
+            );
+            assert_eq!(error.severity(), Severity::Error);
+        }
+    }
+
+    #[test]
+    fn error_clone_is_equal() {
+        let errors = every_variant();
+
+        let cloned = errors.clone();
+
+        assert_eq!(cloned, errors);
+    }
+
+    #[test]
+    fn error_equality_ignores_source_location() {
+        let errors = every_variant();
+
+        let located = every_variant_at(Some(&(file!(), line!())));
+
+        assert_eq!(located, errors);
+        for (i, error) in errors.iter().enumerate() {
+            for (j, other) in located.iter().enumerate() {
+                assert_eq!(i == j, error == other);
+            }
+        }
+    }
+
+    /// The error of parsing the model, along with the variables it declares.
+    fn render_error(model: &str) -> String {
+        let mut environment = Environment::new_empty();
+        environment.insert("ready", &Value::new_false());
+        environment.insert("done", &Value::new_false());
+        environment.insert("mode", &Value::new_false());
+        let error = Automaton::parse(model).err().unwrap();
+        error.render("producer", &environment)
+    }
+
+    #[test]
+    fn error_render_missing_identifiers_in_edge_guard() {
+        let rendered = render_error(
+            "
+            var ready = false
+            var done = false
+            location a init
+            location b
+            a -out! when redy && !done || redy-> b
+            ",
+        );
+
+        assert_eq!(
+            rendered,
+            concat!(
+                "error: AUT0010: unknown identifiers in the guard of a -out!-> b in automaton producer\n",
+                "  | redy && !done || redy\n",
+                "  | ^^^^             ^^^^\n",
+                "  = help: 'redy' is not declared, did you mean 'ready'?",
+            )
+        );
+    }
+
+    #[test]
+    fn error_render_missing_identifiers_in_edge_update() {
+        let rendered = render_error(
+            "
+            var ready = false
+            var done = false
+            location a init
+            location b
+            a -out! do ready = dne; done = unrelated-> b
+            ",
+        );
+
+        assert_eq!(
+            rendered,
+            concat!(
+                "error: AUT0012: unknown identifiers in the update of a -out!-> b in automaton producer\n",
+                "  | ready = dne; done = unrelated\n",
+                "  |         ^^^         ^^^^^^^^^\n",
+                "  = help: 'dne' is not declared, did you mean 'done'?",
+            )
+        );
+    }
+
+    #[test]
+    fn error_render_missing_identifiers_in_location_invariant() {
+        let rendered = render_error(
+            "
+            var ready = false
+            var mode = false
+            location a init invariant node || ready
+            location b
+            a -out!-> b
+            ",
+        );
+
+        assert_eq!(
+            rendered,
+            concat!(
+                "error: AUT0014: unknown identifiers in the invariant of a in automaton producer\n",
+                "  | node || ready\n",
+                "  | ^^^^\n",
+                "  = help: 'node' is not declared, did you mean 'mode' or 'done'?",
+            )
+        );
+    }
+
+    #[test]
+    fn error_render_aggregated_errors() {
+        let rendered = render_error(
+            "
+            var ready = false
+            var mode = false
+            location a init invariant node || ready
+            location b
+            a -out! when redy-> b
+            ",
+        );
+
+        assert_eq!(
+            rendered,
+            concat!(
+                "error: AUT0010: unknown identifiers in the guard of a -out!-> b in automaton producer\n",
+                "  | redy\n",
+                "  | ^^^^\n",
+                "  = help: 'redy' is not declared, did you mean 'ready'?\n",
+                "error: AUT0014: unknown identifiers in the invariant of a in automaton producer\n",
+                "  | node || ready\n",
+                "  | ^^^^\n",
+                "  = help: 'node' is not declared, did you mean 'mode' or 'done'?",
+            )
+        );
+    }
+
+    #[test]
+    fn error_render_other_variants_as_display() {
+        let error = Error::EmptyAutomaton {
+            automaton: String::from("producer"),
+            source_location: None,
+        };
+
+        let rendered = error.render("producer", &Environment::new_empty());
+
+        assert_eq!(rendered, "error: AUT0002: Automaton producer is empty");
+    }
 }
@@ -0,0 +1,27 @@
+//! Automata and edges shared by the tests of several modules.
+
+use super::{channel::Channel, edge::Edge, guard::Guard, location::Location, update::Update};
+
+/// An edge which is always enabled and does not update any variable.
+pub fn new_edge(source: &Location, action: &Channel, target: &Location) -> Edge {
+    Edge::new(source, action, &Guard::new_true(), &Update::empty(), target)
+}
+
+/// Six locations where a reaches f through b and c by an input then an output,
+/// and through d and e by an output then an input.
+pub const DIRECTED_DIAMOND: &str = "
+    location a init
+    location b
+    location c
+    location d
+    location e
+    location f
+    a -in?-> b
+    a -in?-> c
+    a -out!-> d
+    a -out!-> e
+    b -out!-> f
+    c -out!-> f
+    d -in?-> f
+    e -in?-> f
+";
@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use crate::language::{expression::Expression, value::Value};
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Guard {
     pub node: Expression,
 }
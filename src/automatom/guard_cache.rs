@@ -0,0 +1,193 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    thread::{self, ThreadId},
+};
+
+use crate::{
+    language::{environment::Environment, error::Error},
+    transition_system::state::State,
+};
+
+use super::edge::Edge;
+
+type Results = HashMap<(Edge, Environment), Result<bool, Error>>;
+
+/// Remembers whether the guard of an edge held in an environment, or the error
+/// of evaluating it, such that
+/// repeated enabledness checks of the same edge and state only evaluate once.
+/// The cache is shareable between threads, where every thread fills its own
+/// shard such that threads exploring in parallel do not wait on each other.
+#[derive(Debug, Default)]
+pub struct GuardCache {
+    shards: RwLock<HashMap<ThreadId, Arc<Mutex<Results>>>>,
+    evaluations: AtomicUsize,
+}
+
+impl Clone for GuardCache {
+    fn clone(&self) -> Self {
+        let shards = self
+            .shards
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(thread, shard)| {
+                let results = shard.lock().unwrap().clone();
+                (*thread, Arc::new(Mutex::new(results)))
+            })
+            .collect();
+        GuardCache {
+            shards: RwLock::new(shards),
+            evaluations: AtomicUsize::new(self.evaluations()),
+        }
+    }
+}
+
+impl GuardCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// As `try_enabled`, where an edge whose guard fails to evaluate is disabled.
+    pub fn enabled(&self, edge: &Edge, state: &State) -> bool {
+        self.try_enabled(edge, state).unwrap_or(false)
+    }
+
+    /// As `Edge::try_enabled`, but only evaluates the guard the first time.
+    pub fn try_enabled(&self, edge: &Edge, state: &State) -> Result<bool, Error> {
+        if edge.source != state.location {
+            return Ok(false);
+        }
+
+        let shard = self.shard();
+        let mut results = shard.lock().unwrap();
+        let key = (edge.clone(), state.environment.clone());
+        if let Some(enabled) = results.get(&key) {
+            return enabled.clone();
+        }
+
+        self.evaluations.fetch_add(1, Ordering::Relaxed);
+        let enabled = edge.try_enabled(state);
+        results.insert(key, enabled.clone());
+        enabled
+    }
+
+    /// The shard of the current thread, which only the current thread locks
+    /// except while the cache is inspected or cleared.
+    fn shard(&self) -> Arc<Mutex<Results>> {
+        let thread = thread::current().id();
+        if let Some(shard) = self.shards.read().unwrap().get(&thread) {
+            return shard.clone();
+        }
+        self.shards
+            .write()
+            .unwrap()
+            .entry(thread)
+            .or_default()
+            .clone()
+    }
+
+    /// The number of guards which have been evaluated rather than looked up.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations.load(Ordering::Relaxed)
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .read()
+            .unwrap()
+            .values()
+            .map(|shard| shard.lock().unwrap().len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        for shard in self.shards.read().unwrap().values() {
+            shard.lock().unwrap().clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        automatom::{
+            channel::Channel, edge::Edge, guard::Guard, invariant::Invariant, location::Location,
+            update::Update,
+        },
+        language::{environment::Environment, expression::Expression, value::Value},
+        transition_system::state::State,
+    };
+
+    use super::GuardCache;
+
+    #[test]
+    fn guard_cache_second_check_does_not_evaluate() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let guard = Guard::new(&Expression::new_identifier("x"));
+        let edge = Edge::new(&a, &Channel::new_input("in"), &guard, &Update::empty(), &b);
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_true());
+        let state = State::new(&a, &environment);
+        let cache = GuardCache::new();
+
+        let first = cache.enabled(&edge, &state);
+        let second = cache.enabled(&edge, &state);
+
+        assert!(first);
+        assert!(second);
+        assert_eq!(cache.evaluations(), 1);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn guard_cache_distinguishes_environments() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let guard = Guard::new(&Expression::new_identifier("x"));
+        let edge = Edge::new_loop(&a, &Channel::new_input("in"), &guard, &Update::empty());
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_true());
+        let enabling = State::new(&a, &environment);
+        environment.set("x", &Value::new_false());
+        let disabling = State::new(&a, &environment);
+        let cache = GuardCache::new();
+
+        assert!(cache.enabled(&edge, &enabling));
+        assert!(!cache.enabled(&edge, &disabling));
+        assert!(cache.enabled(&edge, &enabling));
+        assert_eq!(cache.evaluations(), 2);
+    }
+
+    #[test]
+    fn guard_cache_shards_per_thread() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let guard = Guard::new(&Expression::new_identifier("x"));
+        let edge = Edge::new_loop(&a, &Channel::new_input("in"), &guard, &Update::empty());
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_true());
+        let state = State::new(&a, &environment);
+        let cache = GuardCache::new();
+
+        std::thread::scope(|scope| {
+            for _ in 0..2 {
+                scope.spawn(|| assert!(cache.enabled(&edge, &state)));
+            }
+        });
+
+        assert_eq!(cache.evaluations(), 2);
+        assert_eq!(cache.len(), 2);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -5,7 +5,7 @@ use crate::language::{
     value::Value,
 };
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub struct Invariant {
     pub node: Expression,
 }
@@ -23,19 +23,51 @@ impl Invariant {
         Self::new(&Expression::Literal(Value::Bool(false)))
     }
 
+    /// Conjoins the invariants, leaving out literal true operands. The result is
+    /// false if any operand is literal false and true if no operands are left.
     pub fn new_conjunction(invariants: HashSet<Invariant>) -> Invariant {
-        if invariants.len() < 2 {
-            panic!("Cannto conjoin less than two invariants")
+        let mut operands: Vec<Expression> = Vec::new();
+        for invariant in invariants {
+            match invariant.node {
+                Expression::Literal(Value::Bool(true)) => continue,
+                Expression::Literal(Value::Bool(false)) => return Invariant::new_false(),
+                node => operands.push(node),
+            }
         }
+        // The set iterates in arbitrary order, so sort to make the result deterministic
+        operands.sort_by_key(|operand| operand.to_string());
+        operands.dedup();
 
-        let mut invariant_iter = invariants.iter().map(|invariant| invariant.node.clone());
-        let mut lhs = invariant_iter.next().unwrap();
-        for rhs in invariant_iter {
+        let mut operand_iter = operands.into_iter();
+        let Some(mut lhs) = operand_iter.next() else {
+            return Invariant::new_true();
+        };
+        for rhs in operand_iter {
             lhs = Expression::new_binary_expression(&lhs, &BinaryOperator::LogicalAnd, &rhs);
         }
 
         Invariant::new(&lhs)
     }
+
+    /// The invariant which also requires the extra expression to hold.
+    pub fn strengthen(&self, extra: &Expression) -> Invariant {
+        Invariant::new(&Expression::new_conjunction(&[
+            self.node.clone(),
+            extra.clone(),
+        ]))
+    }
+
+    /// The invariant which also holds where the alternative expression holds.
+    /// The disjunction is simplified, folding away literal operands.
+    pub fn weaken(&self, alt: &Expression) -> Invariant {
+        Invariant::new(
+            &Expression::new_logical_or(
+                &self.node.parenthesized_if_binary(),
+                &alt.parenthesized_if_binary(),
+            )
+            .simplify(),
+        )
+    }
 }
 
 impl Display for Invariant {
@@ -43,3 +75,124 @@ impl Display for Invariant {
         f.write_str(&self.node.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::language::{
+        environment::Environment, expression::Expression, interpreter::Interpreter, value::Value,
+    };
+
+    use super::Invariant;
+
+    fn identifier(name: &str) -> Invariant {
+        Invariant::new(&Expression::new_identifier(name))
+    }
+
+    #[test]
+    fn invariant_conjunction_of_one_is_itself() {
+        let x = identifier("x");
+
+        let conjunction = Invariant::new_conjunction(HashSet::from([x.clone()]));
+
+        assert_eq!(conjunction, x);
+    }
+
+    #[test]
+    fn invariant_conjunction_of_none_is_true() {
+        let conjunction = Invariant::new_conjunction(HashSet::new());
+
+        assert_eq!(conjunction, Invariant::new_true());
+    }
+
+    #[test]
+    fn invariant_conjunction_drops_true() {
+        let x = identifier("x");
+
+        let conjunction =
+            Invariant::new_conjunction(HashSet::from([x.clone(), Invariant::new_true()]));
+
+        assert_eq!(conjunction, x);
+    }
+
+    #[test]
+    fn invariant_conjunction_with_false_is_false() {
+        let conjunction = Invariant::new_conjunction(HashSet::from([
+            identifier("x"),
+            identifier("y"),
+            Invariant::new_false(),
+        ]));
+
+        assert_eq!(conjunction, Invariant::new_false());
+    }
+
+    #[test]
+    fn invariant_conjunction_deduplicates_operands() {
+        let x = identifier("x");
+        let y = identifier("y");
+        let expected = Invariant::new(&Expression::new_logical_and(&x.node, &y.node));
+
+        let conjunction = Invariant::new_conjunction(HashSet::from([
+            x.clone(),
+            y.clone(),
+            identifier("x"),
+            Invariant::new_true(),
+        ]));
+
+        assert_eq!(conjunction, expected);
+    }
+
+    fn holds(invariant: &Invariant, x: bool, y: bool) -> bool {
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_boolean(x));
+        environment.insert("y", &Value::new_boolean(y));
+        Interpreter::new(&environment)
+            .eval_expression(&invariant.node)
+            .unwrap()
+            .is_true()
+    }
+
+    #[test]
+    fn invariant_strengthen_conjoins() {
+        let invariant = Invariant::new(&Expression::new_logical_or(
+            &Expression::new_identifier("x"),
+            &Expression::new_identifier("y"),
+        ));
+
+        let strengthened = invariant.strengthen(&Expression::new_identifier("y"));
+
+        assert_eq!(strengthened.to_string(), "(x || y) && y");
+        assert!(holds(&strengthened, false, true));
+        assert!(!holds(&strengthened, true, false));
+        assert_eq!(
+            Invariant::new_true().strengthen(&Expression::new_identifier("x")),
+            identifier("x")
+        );
+    }
+
+    #[test]
+    fn invariant_weaken_disjoins() {
+        let invariant = Invariant::new(&Expression::new_logical_and(
+            &Expression::new_identifier("x"),
+            &Expression::new_identifier("y"),
+        ));
+
+        let weakened = invariant.weaken(&Expression::new_logical_not(&Expression::new_identifier(
+            "x",
+        )));
+
+        assert_eq!(weakened.to_string(), "(x && y) || !x");
+        assert!(holds(&weakened, false, false));
+        assert!(holds(&weakened, true, true));
+        assert!(!holds(&weakened, true, false));
+        assert_eq!(
+            Invariant::new_false().weaken(&Expression::new_identifier("x")),
+            identifier("x")
+        );
+        assert_eq!(
+            invariant.weaken(&Expression::new_boolean(&true)),
+            Invariant::new_true()
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use std::fmt::Display;
+
+use super::{channel::Channel, edge::Edge, location::Location};
+
+/// A suspicious part of an automaton which is valid but likely unintended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lint {
+    /// The variable is declared but neither read nor written.
+    UnreadVariable { identifier: String },
+    /// The variable is written by the edges but never read.
+    WriteOnlyVariable {
+        identifier: String,
+        edges: Vec<Edge>,
+    },
+    /// The channel is not matched by the opposite direction in the interface.
+    UnmatchedChannel { channel: Channel },
+    /// No edge leaves the location, such that it potentially deadlocks.
+    NoOutgoingEdges { location: Location },
+    /// The guard of the edge is literally false, such that it never fires.
+    FalseGuard { edge: Box<Edge> },
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Lint::UnreadVariable { identifier } => f.write_fmt(format_args!(
+                "The variable {} is declared but never used",
+                identifier
+            )),
+            Lint::WriteOnlyVariable { identifier, edges } => {
+                f.write_fmt(format_args!(
+                    "The variable {} is written but never read by",
+                    identifier
+                ))?;
+                for edge in edges {
+                    f.write_fmt(format_args!("\n  {}", edge))?;
+                }
+                Ok(())
+            }
+            Lint::UnmatchedChannel { channel } => f.write_fmt(format_args!(
+                "The channel {} is not matched by the interface",
+                channel
+            )),
+            Lint::NoOutgoingEdges { location } => f.write_fmt(format_args!(
+                "The location {} has no outgoing edges and may deadlock",
+                location.get_name()
+            )),
+            Lint::FalseGuard { edge } => {
+                f.write_fmt(format_args!("The edge {} can never fire", edge))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        language::{
+            environment::Environment, expression::Expression, statement::Statement, value::Value,
+        },
+    };
+
+    use super::Lint;
+
+    #[test]
+    fn lint_report_has_each_lint_once() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let d = Location::new_normal("d", invariant);
+        let go = Edge::new(
+            &a,
+            &Channel::new_output("go"),
+            &Guard::new(&Expression::new_identifier("read")),
+            &Update::new(&Statement::new_simple_assignment(
+                "written",
+                &Value::new_true(),
+            )),
+            &b,
+        );
+        let back = Edge::new(
+            &b,
+            &Channel::new_input("back"),
+            &Guard::new_false(),
+            &Update::empty(),
+            &a,
+        );
+        let stop = Edge::new(
+            &a,
+            &Channel::new_output("stop"),
+            &Guard::new_true(),
+            &Update::empty(),
+            &d,
+        );
+        let mut environment = Environment::new_empty();
+        for identifier in ["read", "written", "unused"] {
+            environment.insert(identifier, &Value::new_true());
+        }
+        let automaton = Automaton::new(
+            "linted",
+            &HashSet::from([go.clone(), back.clone(), stop]),
+            Some(&environment),
+        )
+        .ok()
+        .unwrap();
+        let x = Location::new_initial("x", invariant);
+        let interface = Automaton::new(
+            "interface",
+            &HashSet::from([
+                Edge::new_loop(
+                    &x,
+                    &Channel::new_input("go"),
+                    &Guard::new_true(),
+                    &Update::empty(),
+                ),
+                Edge::new_loop(
+                    &x,
+                    &Channel::new_output("back"),
+                    &Guard::new_true(),
+                    &Update::empty(),
+                ),
+            ]),
+            None,
+        )
+        .ok()
+        .unwrap();
+
+        let lints = automaton.lints_with_interface(&interface);
+
+        assert_eq!(
+            lints,
+            vec![
+                Lint::UnreadVariable {
+                    identifier: String::from("unused")
+                },
+                Lint::WriteOnlyVariable {
+                    identifier: String::from("written"),
+                    edges: vec![go]
+                },
+                Lint::NoOutgoingEdges { location: d },
+                Lint::FalseGuard {
+                    edge: Box::new(back)
+                },
+                Lint::UnmatchedChannel {
+                    channel: Channel::new_output("stop")
+                },
+            ]
+        );
+        assert_eq!(
+            lints[2].to_string(),
+            "The location d has no outgoing edges and may deadlock"
+        );
+        assert_eq!(automaton.lints(), lints[..4].to_vec());
+    }
+}
@@ -2,7 +2,9 @@ use std::{collections::HashSet, fmt::Display, hash::Hash};
 
 use super::invariant::*;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Locations are ordered by variant in declaration order and then by name,
+/// invariant or operands.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Location {
     Normal {
         name: String,
@@ -98,6 +100,38 @@ impl Location {
         }
     }
 
+    /// The name of the location, where conjunctions are named by their operands.
+    pub fn get_name(&self) -> String {
+        match self {
+            Location::Normal { name, .. }
+            | Location::Initial { name, .. }
+            | Location::Inconsistent { name }
+            | Location::Universal { name } => name.clone(),
+            Location::Conjunction { locations, .. } => locations
+                .iter()
+                .map(|location| location.get_name())
+                .collect::<Vec<String>>()
+                .join(" && "),
+        }
+    }
+
+    /// A copy of the location where its invariant, and those of the operands of
+    /// a conjunction, are true.
+    pub fn without_invariants(&self) -> Location {
+        match self {
+            Location::Normal { name, .. } => Location::new_normal(name, &Invariant::new_true()),
+            Location::Initial { name, .. } => Location::new_initial(name, &Invariant::new_true()),
+            Location::Conjunction { locations, .. } => Location::Conjunction {
+                locations: locations
+                    .iter()
+                    .map(|location| location.without_invariants())
+                    .collect(),
+                invariant: Invariant::new_true(),
+            },
+            Location::Inconsistent { .. } | Location::Universal { .. } => self.clone(),
+        }
+    }
+
     pub fn new_inconsistent(name: &str) -> Location {
         Location::Inconsistent {
             name: String::from(name),
@@ -1,9 +1,20 @@
+pub mod analysis;
+#[cfg(any(test, feature = "arbitrary"))]
+pub mod arbitrary;
 pub mod automaton;
 pub mod channel;
 pub mod conjunction;
+pub mod dsl;
 pub mod edge;
 pub mod error;
+#[cfg(test)]
+pub mod fixtures;
 pub mod guard;
+pub mod guard_cache;
 pub mod invariant;
+pub mod lint;
 pub mod location;
+pub mod statistics;
+pub mod symbolic;
+pub mod testing;
 pub mod update;
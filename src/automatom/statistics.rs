@@ -0,0 +1,209 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{language::expression::Expression, transition_system::trace_json::json_string};
+
+/// Counts describing the size and shape of an automaton.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AutomatonStats {
+    pub normal_locations: usize,
+    pub initial_locations: usize,
+    pub conjunction_locations: usize,
+    pub inconsistent_locations: usize,
+    pub universal_locations: usize,
+    /// The number of edges of each action, keyed by the displayed channel.
+    pub edges_per_action: BTreeMap<String, usize>,
+    pub inputs: usize,
+    pub outputs: usize,
+    pub variables: usize,
+    pub max_guard_depth: usize,
+    /// The static bound on the number of states, none if it overflows.
+    pub state_space_bound: Option<u128>,
+    /// Only known if the state space was explored.
+    pub reachable_states: Option<usize>,
+    /// The largest number of steps needed to reach a reachable state, only
+    /// known if the state space was explored.
+    pub diameter: Option<usize>,
+}
+
+impl AutomatonStats {
+    pub fn locations(&self) -> usize {
+        self.normal_locations
+            + self.initial_locations
+            + self.conjunction_locations
+            + self.inconsistent_locations
+            + self.universal_locations
+    }
+
+    pub fn edges(&self) -> usize {
+        self.edges_per_action.values().sum()
+    }
+
+    /// The depth of the expression tree, where parentheses do not count.
+    pub(crate) fn depth(expression: &Expression) -> usize {
+        match expression {
+            Expression::Literal(_) => 1,
+            Expression::Parenthesized(expression) => Self::depth(expression),
+            Expression::Binary(lhs, _, rhs) => 1 + Self::depth(lhs).max(Self::depth(rhs)),
+            Expression::Unary(_, expression) => 1 + Self::depth(expression),
+        }
+    }
+
+    /// The statistics as a flat JSON object, where unknown counts are null.
+    pub fn to_json(&self) -> String {
+        let optional = |count: Option<usize>| match count {
+            Some(count) => count.to_string(),
+            None => String::from("null"),
+        };
+        let edges_per_action: Vec<String> = self
+            .edges_per_action
+            .iter()
+            .map(|(action, count)| format!("{}:{}", json_string(action), count))
+            .collect();
+        format!(
+            "{{\"locations\":{{\"normal\":{},\"initial\":{},\"conjunction\":{},\"inconsistent\":{},\"universal\":{}}},\"edges_per_action\":{{{}}},\"inputs\":{},\"outputs\":{},\"variables\":{},\"max_guard_depth\":{},\"state_space_bound\":{},\"reachable_states\":{},\"diameter\":{}}}",
+            self.normal_locations,
+            self.initial_locations,
+            self.conjunction_locations,
+            self.inconsistent_locations,
+            self.universal_locations,
+            edges_per_action.join(","),
+            self.inputs,
+            self.outputs,
+            self.variables,
+            self.max_guard_depth,
+            match self.state_space_bound {
+                Some(bound) => bound.to_string(),
+                None => String::from("null"),
+            },
+            optional(self.reachable_states),
+            optional(self.diameter)
+        )
+    }
+}
+
+impl Display for AutomatonStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Locations: {} (normal: {}, initial: {}, conjunction: {}, inconsistent: {}, universal: {})\n",
+            self.locations(),
+            self.normal_locations,
+            self.initial_locations,
+            self.conjunction_locations,
+            self.inconsistent_locations,
+            self.universal_locations
+        ))?;
+        f.write_fmt(format_args!("Edges: {}\n", self.edges()))?;
+        for (action, count) in self.edges_per_action.iter() {
+            f.write_fmt(format_args!("  {}: {}\n", action, count))?;
+        }
+        f.write_fmt(format_args!(
+            "Channels: {} (inputs: {}, outputs: {})\n",
+            self.inputs + self.outputs,
+            self.inputs,
+            self.outputs
+        ))?;
+        f.write_fmt(format_args!("Variables: {}\n", self.variables))?;
+        f.write_fmt(format_args!(
+            "Maximum guard depth: {}\n",
+            self.max_guard_depth
+        ))?;
+        match self.state_space_bound {
+            Some(bound) => f.write_fmt(format_args!("State space bound: {}", bound))?,
+            None => f.write_str("State space bound: at least 2^128")?,
+        }
+        if let Some(reachable_states) = self.reachable_states {
+            f.write_fmt(format_args!("\nReachable states: {}", reachable_states))?;
+        }
+        if let Some(diameter) = self.diameter {
+            f.write_fmt(format_args!("\nDiameter: {}", diameter))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashSet};
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        language::{expression::Expression, statement::Statement, value::Value},
+    };
+
+    use super::AutomatonStats;
+
+    fn new_fixture() -> Automaton {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let go = Channel::new_output("go");
+        let guard = Guard::new(&Expression::new_logical_and(
+            &Expression::new_identifier("x"),
+            &Expression::new_logical_not(&Expression::new_identifier("y")),
+        ));
+        let set_y = Update::new(&Statement::new_simple_assignment("y", &Value::new_true()));
+        let edges = HashSet::from([
+            Edge::new(&a, &go, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&b, &go, &guard, &set_y, &c),
+            Edge::new(
+                &c,
+                &Channel::new_input("reset"),
+                &Guard::new_true(),
+                &Update::empty(),
+                &a,
+            ),
+        ]);
+        Automaton::new("fixture", &edges, None).ok().unwrap()
+    }
+
+    #[test]
+    fn statistics_report() {
+        let automaton = new_fixture();
+
+        let statistics = automaton.statistics();
+
+        assert_eq!(
+            statistics.to_string(),
+            "Locations: 3 (normal: 2, initial: 1, conjunction: 0, inconsistent: 0, universal: 0)\n\
+             Edges: 3\n  go!: 2\n  reset?: 1\n\
+             Channels: 2 (inputs: 1, outputs: 1)\n\
+             Variables: 2\n\
+             Maximum guard depth: 3\n\
+             State space bound: 12"
+        );
+        assert_eq!(
+            statistics.to_json(),
+            "{\"locations\":{\"normal\":2,\"initial\":1,\"conjunction\":0,\"inconsistent\":0,\"universal\":0},\"edges_per_action\":{\"go!\":2,\"reset?\":1},\"inputs\":1,\"outputs\":1,\"variables\":2,\"max_guard_depth\":3,\"state_space_bound\":12,\"reachable_states\":null,\"diameter\":null}"
+        );
+    }
+
+    #[test]
+    fn statistics_explored() {
+        let automaton = new_fixture();
+
+        let statistics = automaton.explored_statistics();
+
+        // x is never set, so the guard of b never holds
+        assert_eq!(statistics.reachable_states, Some(2));
+        assert_eq!(statistics.diameter, Some(1));
+        assert!(statistics
+            .to_string()
+            .ends_with("\nReachable states: 2\nDiameter: 1"));
+    }
+
+    #[test]
+    fn statistics_to_json_escapes_actions() {
+        let statistics = AutomatonStats {
+            edges_per_action: BTreeMap::from([(String::from("say \"hi\"\u{7f}!"), 1)]),
+            ..AutomatonStats::default()
+        };
+
+        assert!(statistics
+            .to_json()
+            .contains("\"edges_per_action\":{\"say \\\"hi\\\"\\u007f!\":1}"));
+    }
+}
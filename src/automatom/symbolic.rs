@@ -0,0 +1,502 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::{
+    language::{
+        environment::Environment, expression::Expression, statement::Statement, value::Value,
+    },
+    transition_system::state::State,
+};
+
+use super::{automaton::Automaton, channel::Channel, error::Error, location::Location};
+
+/// The most variables for which `SymbolicState::concretize` enumerates the
+/// assignments.
+pub const MAX_CONCRETIZED_VARIABLES: usize = 20;
+
+/// A set of states sharing a location, represented by a constraint on the
+/// variables which holds in exactly the environments of the set.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SymbolicState {
+    pub location: Location,
+    pub constraint: Expression,
+}
+
+impl SymbolicState {
+    pub fn new(location: &Location, constraint: &Expression) -> Self {
+        SymbolicState {
+            location: location.clone(),
+            constraint: constraint.clone(),
+        }
+    }
+
+    /// The initial state of the automaton, where the constraint pins every
+    /// variable to its initial value.
+    pub fn new_initial(automaton: &Automaton) -> Self {
+        let constraints: Vec<Expression> = automaton
+            .get_initial_environment()
+            .sorted_entries()
+            .into_iter()
+            .map(|(identifier, value)| {
+                let variable = Expression::new_identifier(identifier.as_str());
+                match value {
+                    Value::Bool(true) => variable,
+                    Value::Bool(false) => Expression::new_logical_not(&variable),
+                    value => {
+                        Expression::new_logical_equal(&variable, &Expression::new_literal(value))
+                    }
+                }
+            })
+            .collect();
+        SymbolicState::new(
+            automaton.get_initial_location(),
+            &Expression::new_conjunction(&constraints),
+        )
+    }
+
+    /// True if no environment satisfies the constraint.
+    pub fn is_empty(&self) -> bool {
+        normalize(&self.constraint) == Expression::new_boolean(&false)
+    }
+
+    /// The concrete states of the set, where every variable of the initial
+    /// environment of the automaton holds a boolean. Every assignment is tried,
+    /// so automata with more than `MAX_CONCRETIZED_VARIABLES` are rejected.
+    pub fn concretize(&self, automaton: &Automaton) -> Result<HashSet<State>, Error> {
+        let variables = boolean_variables(automaton)?;
+        if variables.len() > MAX_CONCRETIZED_VARIABLES {
+            return Err(Error::TooManyVariables {
+                source_location: Some(&(file!(), line!())),
+                automaton: automaton.name.clone(),
+                count: variables.len(),
+                limit: MAX_CONCRETIZED_VARIABLES,
+            });
+        }
+        Ok((0..1u64 << variables.len())
+            .filter_map(|assignment| {
+                let mut environment = Environment::new_empty();
+                let mut constraint = self.constraint.clone();
+                for (index, variable) in variables.iter().enumerate() {
+                    let value = assignment >> index & 1 == 1;
+                    environment.insert(variable, &Value::Bool(value));
+                    constraint = constraint.substitute(variable, &Expression::new_boolean(&value));
+                }
+                match constraint.simplify() == Expression::new_boolean(&true) {
+                    true => Some(State::new(&self.location, &environment)),
+                    false => None,
+                }
+            })
+            .collect())
+    }
+}
+
+impl Display for SymbolicState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{} {{ {} }}",
+            self.location.get_name(),
+            self.constraint.to_string()
+        ))
+    }
+}
+
+/// The successors of the symbolic state by the edges of the action, one for
+/// each edge whose guard can hold. The constraint of a successor is the guard
+/// conjoined with the constraint, transformed by the update.
+pub fn post(automaton: &Automaton, state: &SymbolicState, action: &Channel) -> Vec<SymbolicState> {
+    let actions = HashSet::from([action.clone()]);
+    let mut edges: Vec<_> = automaton
+        .outgoing_edges(&state.location, &actions)
+        .collect();
+    edges.sort();
+
+    let mut successors = Vec::new();
+    for edge in edges {
+        let enabled =
+            Expression::new_conjunction(&[state.constraint.clone(), edge.guard.node.clone()]);
+        let constraint = match &edge.update.node {
+            Some(update) => post_statement(&enabled, update),
+            None => enabled,
+        };
+        let constraint = normalize(&constraint);
+        if constraint != Expression::new_boolean(&false) {
+            successors.push(SymbolicState::new(&edge.target, &constraint));
+        }
+    }
+    successors
+}
+
+/// The variables of the automaton, sorted. Constraints only describe boolean
+/// variables, so an automaton with any other variable, such as a clock, is
+/// rejected rather than explored unsoundly.
+fn boolean_variables(automaton: &Automaton) -> Result<Vec<String>, Error> {
+    automaton
+        .get_initial_environment()
+        .sorted_entries()
+        .into_iter()
+        .map(|(identifier, value)| match value {
+            Value::Bool(_) => Ok(identifier.to_string()),
+            value => Err(Error::NonBooleanVariable {
+                source_location: Some(&(file!(), line!())),
+                automaton: automaton.name.clone(),
+                identifier: identifier.to_string(),
+                value: value.to_string(),
+            }),
+        })
+        .collect()
+}
+
+/// Explores the state space symbolically from the initial state, merging all
+/// states of a location into one. The result holds a symbolic state for every
+/// reachable location, ordered by location.
+pub fn reachable(automaton: &Automaton) -> Result<Vec<SymbolicState>, Error> {
+    explore(automaton, None, post)
+}
+
+/// The symbolic states reachable in at most `max_depth` steps.
+pub fn reachable_within(
+    automaton: &Automaton,
+    max_depth: usize,
+) -> Result<Vec<SymbolicState>, Error> {
+    explore(automaton, Some(max_depth), post)
+}
+
+/// Explores the state space breadth first using the given successor function,
+/// such that alternative implementations of `post` can be checked. Every layer
+/// holds the states first reached at its depth, merged by location, and the
+/// exploration stops after `max_depth` layers if given. Fails if a variable of
+/// the automaton is not a boolean.
+pub fn explore(
+    automaton: &Automaton,
+    max_depth: Option<usize>,
+    post: impl Fn(&Automaton, &SymbolicState, &Channel) -> Vec<SymbolicState>,
+) -> Result<Vec<SymbolicState>, Error> {
+    boolean_variables(automaton)?;
+    let disjunction = |lhs: &Expression, rhs: &Expression| {
+        normalize(&Expression::new_logical_or(
+            &lhs.parenthesized_if_binary(),
+            &rhs.parenthesized_if_binary(),
+        ))
+    };
+    let initial = SymbolicState::new_initial(automaton);
+    let constraint = normalize(&initial.constraint);
+    let mut reached: HashMap<Location, Expression> =
+        HashMap::from([(initial.location.clone(), constraint.clone())]);
+    let mut layer: Vec<SymbolicState> = vec![SymbolicState::new(&initial.location, &constraint)];
+    let mut depth = 0;
+
+    while !layer.is_empty() && max_depth.is_none_or(|max_depth| depth < max_depth) {
+        let mut next: HashMap<Location, Expression> = HashMap::new();
+        for state in layer.iter() {
+            for action in automaton.actions_sorted() {
+                for successor in post(automaton, state, &action) {
+                    let old = reached
+                        .get(&successor.location)
+                        .cloned()
+                        .unwrap_or(Expression::new_boolean(&false));
+                    // Only the states not reached before need to be explored further
+                    let new = normalize(&Expression::new_conjunction(&[
+                        successor.constraint.clone(),
+                        Expression::new_logical_not(&old.parenthesized_if_binary()),
+                    ]));
+                    if new == Expression::new_boolean(&false) {
+                        continue;
+                    }
+                    reached.insert(
+                        successor.location.clone(),
+                        disjunction(&old, &successor.constraint),
+                    );
+                    let pending = match next.remove(&successor.location) {
+                        Some(pending) => disjunction(&pending, &new),
+                        None => new,
+                    };
+                    next.insert(successor.location, pending);
+                }
+            }
+        }
+        layer = next
+            .into_iter()
+            .map(|(location, constraint)| SymbolicState::new(&location, &constraint))
+            .collect();
+        layer.sort_by(|lhs, rhs| lhs.location.cmp(&rhs.location));
+        depth += 1;
+    }
+
+    let mut states: Vec<SymbolicState> = reached
+        .into_iter()
+        .map(|(location, constraint)| SymbolicState::new(&location, &constraint))
+        .collect();
+    states.sort_by(|lhs, rhs| lhs.location.cmp(&rhs.location));
+    Ok(states)
+}
+
+/// The strongest postcondition of the statement, where an assignment to `x`
+/// existentially quantifies the previous value of `x` by trying both booleans.
+fn post_statement(constraint: &Expression, statement: &Statement) -> Expression {
+    match statement {
+        Statement::Assigment { identifier, value } => {
+            identifier
+                .identifiers()
+                .iter()
+                .fold(constraint.clone(), |constraint, name| {
+                    let variable = Expression::new_identifier(name);
+                    let before = |previous: bool| {
+                        let previous = Expression::new_boolean(&previous);
+                        Expression::new_conjunction(&[
+                            constraint.substitute(name, &previous),
+                            Expression::new_logical_equal(
+                                &variable,
+                                &value.substitute(name, &previous).parenthesized_if_binary(),
+                            ),
+                        ])
+                        .simplify()
+                    };
+                    Expression::new_logical_or(
+                        &before(true).parenthesized_if_binary(),
+                        &before(false).parenthesized_if_binary(),
+                    )
+                    .simplify()
+                })
+        }
+        Statement::ClockReset { clock } => post_statement(
+            constraint,
+            &Statement::new_assignment(
+                &Expression::new_identifier(clock),
+                &Expression::new_clock(0),
+            ),
+        ),
+        Statement::Sequence(statements) => statements
+            .iter()
+            .fold(constraint.clone(), |constraint, statement| {
+                post_statement(&constraint, statement)
+            }),
+        Statement::If {
+            condition,
+            then,
+            otherwise,
+        } => {
+            let then = post_statement(
+                &Expression::new_conjunction(&[constraint.clone(), condition.clone()]),
+                then,
+            );
+            let skipped = Expression::new_conjunction(&[
+                constraint.clone(),
+                Expression::new_logical_not(&condition.parenthesized_if_binary()),
+            ]);
+            let otherwise = match otherwise {
+                Some(otherwise) => post_statement(&skipped, otherwise),
+                None => skipped,
+            };
+            Expression::new_logical_or(
+                &then.parenthesized_if_binary(),
+                &otherwise.parenthesized_if_binary(),
+            )
+            .simplify()
+        }
+    }
+}
+
+/// A small equivalent constraint obtained by Shannon expansion over its
+/// variables in sorted order, such that unsatisfiable constraints become
+/// literal false and valid constraints literal true.
+fn normalize(constraint: &Expression) -> Expression {
+    let mut variables = constraint.identifiers();
+    variables.sort();
+    variables.dedup();
+    expand(&constraint.simplify(), &variables, &mut HashMap::new())
+}
+
+/// Expands the constraint over the variables, where constraints already
+/// expanded over the same number of remaining variables are looked up, as the
+/// cofactors of different branches are often equal.
+fn expand(
+    constraint: &Expression,
+    variables: &[String],
+    expanded: &mut HashMap<(usize, Expression), Expression>,
+) -> Expression {
+    let Some((variable, rest)) = variables.split_first() else {
+        return constraint.clone();
+    };
+    if let Expression::Literal(Value::Bool(_)) = constraint {
+        return constraint.clone();
+    }
+    let key = (variables.len(), constraint.clone());
+    if let Some(result) = expanded.get(&key) {
+        return result.clone();
+    }
+    if !constraint.identifiers().contains(variable) {
+        let result = expand(constraint, rest, expanded);
+        expanded.insert(key, result.clone());
+        return result;
+    }
+
+    let mut cofactor = |value: bool| {
+        let substituted = constraint.substitute(variable, &Expression::new_boolean(&value));
+        expand(&substituted.simplify(), rest, expanded)
+    };
+    let (high, low) = (cofactor(true), cofactor(false));
+    let x = Expression::new_identifier(variable);
+    let not_x = Expression::new_logical_not(&x);
+    let (truth, falsity) = (
+        Expression::new_boolean(&true),
+        Expression::new_boolean(&false),
+    );
+    let result = if high == low {
+        high
+    } else if high == truth && low == falsity {
+        x
+    } else if high == falsity && low == truth {
+        not_x
+    } else if low == falsity {
+        Expression::new_conjunction(&[x, high])
+    } else if high == falsity {
+        Expression::new_conjunction(&[not_x, low])
+    } else if high == truth {
+        Expression::new_logical_or(&x, &low.parenthesized_if_binary())
+    } else if low == truth {
+        Expression::new_logical_or(&not_x, &high.parenthesized_if_binary())
+    } else {
+        Expression::new_logical_or(
+            &Expression::new_conjunction(&[x, high]).parenthesized_if_binary(),
+            &Expression::new_conjunction(&[not_x, low]).parenthesized_if_binary(),
+        )
+    };
+    expanded.insert(key, result.clone());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{automaton::Automaton, channel::Channel},
+        language::expression::Expression,
+        transition_system::{
+            state::State,
+            transition_system_breadth_first_search::TransitionSystemBreadthFirstSearch,
+        },
+    };
+
+    use super::{post, reachable, Error, SymbolicState, MAX_CONCRETIZED_VARIABLES};
+
+    #[test]
+    fn symbolic_post_drops_disabled_edges() {
+        let automaton = Automaton::parse(
+            "var x = false\nvar y = true\nlocation a init\nlocation b\n\
+             a -go! when x-> b\na -go! do x = !x; y = x-> b",
+        )
+        .ok()
+        .unwrap();
+        let initial = SymbolicState::new_initial(&automaton);
+
+        let successors = post(&automaton, &initial, &Channel::new_output("go"));
+
+        assert_eq!(successors.len(), 1);
+        assert_eq!(successors[0].location.get_name(), "b");
+        assert_eq!(successors[0].constraint.to_string(), "x && y");
+    }
+
+    #[test]
+    fn symbolic_reachable_matches_concrete_exploration() {
+        let automaton = Automaton::parse(
+            "var x = false\nvar y = false\nlocation a init\nlocation b\nlocation c\n\
+             a -go! do x = !x-> b\nb -back? when x do y = !y-> a\nb -stop! when y-> c",
+        )
+        .ok()
+        .unwrap();
+        let concrete: HashSet<State> =
+            TransitionSystemBreadthFirstSearch::new(automaton.get_actions(), &automaton).collect();
+
+        let symbolic = reachable(&automaton).ok().unwrap();
+
+        let concretized: HashSet<State> = symbolic
+            .iter()
+            .flat_map(|state| state.concretize(&automaton).ok().unwrap())
+            .collect();
+        assert_eq!(concretized, concrete);
+        assert_eq!(symbolic.len(), 3);
+    }
+
+    #[test]
+    fn symbolic_reachable_independent_flags() {
+        let edges: Vec<String> = (0..10)
+            .map(|flag| format!("a -set{}! do f{} = true-> a", flag, flag))
+            .collect();
+        let variables: Vec<String> = (0..10)
+            .map(|flag| format!("var f{} = false", flag))
+            .collect();
+        let automaton = Automaton::parse(&format!(
+            "{}\nlocation a init\n{}",
+            variables.join("\n"),
+            edges.join("\n")
+        ))
+        .ok()
+        .unwrap();
+        let concrete = automaton.explored_statistics().reachable_states;
+
+        let symbolic = reachable(&automaton).ok().unwrap();
+
+        assert_eq!(concrete, Some(1024));
+        assert_eq!(symbolic.len(), 1);
+        assert_eq!(symbolic[0].constraint, Expression::new_boolean(&true));
+    }
+
+    #[test]
+    fn symbolic_empty_state() {
+        let automaton = Automaton::parse("location a init\na -go! when x-> a")
+            .ok()
+            .unwrap();
+        let location = automaton.get_initial_location();
+
+        let empty = SymbolicState::new(
+            location,
+            &Expression::new_logical_and(
+                &Expression::new_identifier("x"),
+                &Expression::new_logical_not(&Expression::new_identifier("x")),
+            ),
+        );
+
+        assert!(empty.is_empty());
+        assert!(!SymbolicState::new_initial(&automaton).is_empty());
+        assert!(empty.concretize(&automaton).ok().unwrap().is_empty());
+    }
+
+    #[test]
+    fn symbolic_concretize_rejects_too_many_variables() {
+        let variables: Vec<String> = (0..=MAX_CONCRETIZED_VARIABLES)
+            .map(|flag| format!("var f{} = false", flag))
+            .collect();
+        let automaton = Automaton::parse(&format!(
+            "{}\nlocation a init\na -go! do f0 = true-> a",
+            variables.join("\n")
+        ))
+        .ok()
+        .unwrap();
+
+        let result = SymbolicState::new_initial(&automaton).concretize(&automaton);
+
+        assert!(matches!(result, Err(Error::TooManyVariables { .. })));
+    }
+
+    #[test]
+    fn symbolic_reachable_rejects_clocks() {
+        let automaton = Automaton::parse(
+            "var x = 0\nlocation a init invariant x <= 3\nlocation b\na -go! when x >= 2-> b",
+        )
+        .ok()
+        .unwrap();
+
+        let result = reachable(&automaton);
+
+        assert!(matches!(
+            result,
+            Err(Error::NonBooleanVariable { identifier, .. }) if identifier == "x"
+        ));
+        assert!(SymbolicState::new_initial(&automaton)
+            .concretize(&automaton)
+            .is_err());
+    }
+}
@@ -0,0 +1,165 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::transition_system::{
+    state::State, transition_system_breadth_first_search::TransitionSystemBreadthFirstSearch,
+};
+
+use super::{
+    automaton::Automaton,
+    channel::Channel,
+    error::Error,
+    symbolic::{explore, post, SymbolicState},
+};
+
+/// The differences between the states reached concretely and symbolically
+/// within a depth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossCheckReport {
+    pub depth: usize,
+    pub concrete_states: usize,
+    pub symbolic_states: usize,
+    /// Concrete states which no symbolic state covers.
+    pub uncovered: Vec<State>,
+    /// Models of symbolic states which are not reached concretely, along with
+    /// the symbolic state they are a model of.
+    pub spurious: Vec<(SymbolicState, State)>,
+}
+
+impl CrossCheckReport {
+    pub fn is_consistent(&self) -> bool {
+        self.uncovered.is_empty() && self.spurious.is_empty()
+    }
+}
+
+impl Display for CrossCheckReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Depth {}: {} concrete states, {} symbolic states",
+            self.depth, self.concrete_states, self.symbolic_states
+        ))?;
+        for state in self.uncovered.iter() {
+            f.write_fmt(format_args!("\nUncovered: {}", state))?;
+        }
+        for (symbolic, state) in self.spurious.iter() {
+            f.write_fmt(format_args!("\nSpurious: {} of {}", state, symbolic))?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the states reached by the concrete breadth first search within
+/// the depth are exactly the models of the symbolic states reached within it.
+/// Fails if the automaton has a variable which is not a boolean, or if the
+/// models cannot be enumerated, see `SymbolicState::concretize`.
+pub fn cross_check(automaton: &Automaton, depth: usize) -> Result<CrossCheckReport, Error> {
+    cross_check_with(automaton, depth, post)
+}
+
+/// Cross checks the concrete exploration against a symbolic exploration using
+/// the given successor function in place of `symbolic::post`.
+pub fn cross_check_with(
+    automaton: &Automaton,
+    depth: usize,
+    post: impl Fn(&Automaton, &SymbolicState, &Channel) -> Vec<SymbolicState>,
+) -> Result<CrossCheckReport, Error> {
+    let concrete: Vec<State> = TransitionSystemBreadthFirstSearch::with_limits(
+        automaton.get_actions(),
+        automaton,
+        Some(depth),
+        None,
+    )
+    .collect();
+    let reached: HashSet<&State> = concrete.iter().collect();
+    let symbolic = explore(automaton, Some(depth), post)?;
+
+    let mut covered: HashSet<State> = HashSet::new();
+    let mut spurious: Vec<(SymbolicState, State)> = Vec::new();
+    for state in symbolic.iter() {
+        let mut models: Vec<State> = state.concretize(automaton)?.into_iter().collect();
+        models.sort_by_key(|model| model.to_string());
+        for model in models {
+            if !reached.contains(&model) {
+                spurious.push((state.clone(), model.clone()));
+            }
+            covered.insert(model);
+        }
+    }
+    let uncovered: Vec<State> = concrete
+        .iter()
+        .filter(|state| !covered.contains(state))
+        .cloned()
+        .collect();
+
+    Ok(CrossCheckReport {
+        depth,
+        concrete_states: concrete.len(),
+        symbolic_states: symbolic.len(),
+        uncovered,
+        spurious,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{automaton::Automaton, channel::Channel, symbolic::SymbolicState},
+        language::expression::Expression,
+    };
+
+    use super::{cross_check, cross_check_with};
+
+    const COUNTER: &str = "
+        var low = false
+        var high = false
+        location a init
+        location b
+        a -tick! do if low then high = !high; low = !low-> b
+        b -tock? when low || high-> a
+        b -reset? do low = false; high = false-> a
+    ";
+
+    #[test]
+    fn testing_cross_check_consistent() {
+        let automaton = Automaton::parse(COUNTER).ok().unwrap();
+
+        for depth in 0..6 {
+            let report = cross_check(&automaton, depth).ok().unwrap();
+
+            assert!(report.is_consistent(), "{}", report);
+        }
+    }
+
+    #[test]
+    fn testing_cross_check_detects_skipped_updates() {
+        let automaton = Automaton::parse(COUNTER).ok().unwrap();
+        // A broken post which takes the guard into account but not the update
+        let skip_updates = |automaton: &Automaton, state: &SymbolicState, action: &Channel| {
+            let actions = HashSet::from([action.clone()]);
+            automaton
+                .outgoing_edges(&state.location, &actions)
+                .map(|edge| {
+                    let constraint = Expression::new_conjunction(&[
+                        state.constraint.clone(),
+                        edge.guard.node.clone(),
+                    ]);
+                    SymbolicState::new(&edge.target, &constraint)
+                })
+                .filter(|successor| !successor.is_empty())
+                .collect()
+        };
+
+        let report = cross_check_with(&automaton, 3, skip_updates).ok().unwrap();
+
+        assert!(!report.is_consistent());
+        // Tick sets low, which the broken post never does
+        assert!(report
+            .uncovered
+            .iter()
+            .any(|state| state.location.get_name() == "b"));
+        assert!(report
+            .to_string()
+            .contains("Uncovered: b { high:=false, low:=true }"));
+    }
+}
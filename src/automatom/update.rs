@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use crate::language::statement::Statement;
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Update {
     pub node: Option<Statement>,
 }
@@ -0,0 +1,225 @@
+use std::fmt::Display;
+
+use crate::transition_system::{
+    search_observer::SearchObserver, state::State, transition::Transition,
+};
+
+/// The number of visited states between two frontier events of a search.
+pub const FRONTIER_EVENT_INTERVAL: usize = 1024;
+
+/// Counters aggregated from the callbacks of a search, which are collected
+/// whether or not the `trace` feature is enabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    pub states_visited: usize,
+    pub max_depth: usize,
+    pub max_frontier: usize,
+    pub transitions_traversed: usize,
+}
+
+impl SearchObserver for SearchStats {
+    fn on_state_visited(&mut self, _state: &State, depth: usize) {
+        self.states_visited += 1;
+        self.max_depth = self.max_depth.max(depth);
+    }
+
+    fn on_frontier_size(&mut self, size: usize) {
+        self.max_frontier = self.max_frontier.max(size);
+    }
+
+    fn on_transition_traversed(&mut self, _transition: &Transition) {
+        self.transitions_traversed += 1;
+    }
+}
+
+impl Display for SearchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{} states visited, max depth {}, max frontier {}, {} transitions traversed",
+            self.states_visited, self.max_depth, self.max_frontier, self.transitions_traversed
+        ))
+    }
+}
+
+/// Receives the spans and events of the instrumented operations.
+#[cfg(feature = "trace")]
+pub trait Subscriber {
+    fn enter(&self, span: &'static str);
+    fn exit(&self, span: &'static str);
+    fn event(&self, name: &'static str, message: &str);
+}
+
+#[cfg(feature = "trace")]
+thread_local! {
+    static SUBSCRIBER: std::cell::RefCell<Option<std::rc::Rc<dyn Subscriber>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Reports the spans and events of the current thread to the subscriber while
+/// the closure runs.
+#[cfg(feature = "trace")]
+pub fn with_subscriber<R>(subscriber: std::rc::Rc<dyn Subscriber>, f: impl FnOnce() -> R) -> R {
+    let previous = SUBSCRIBER.with(|current| current.replace(Some(subscriber)));
+    let result = f();
+    SUBSCRIBER.with(|current| current.replace(previous));
+    result
+}
+
+#[cfg(feature = "trace")]
+fn with_current(f: impl FnOnce(&dyn Subscriber)) {
+    SUBSCRIBER.with(|current| {
+        if let Some(subscriber) = current.borrow().as_ref() {
+            f(subscriber.as_ref())
+        }
+    })
+}
+
+/// Marks a span as entered until it is dropped. Without the `trace` feature
+/// it is empty and entering it does nothing.
+#[must_use]
+pub struct SpanGuard {
+    #[cfg(feature = "trace")]
+    name: &'static str,
+}
+
+#[cfg(feature = "trace")]
+pub fn enter(name: &'static str) -> SpanGuard {
+    with_current(|subscriber| subscriber.enter(name));
+    SpanGuard { name }
+}
+
+#[cfg(not(feature = "trace"))]
+#[inline(always)]
+pub fn enter(_name: &'static str) -> SpanGuard {
+    SpanGuard {}
+}
+
+#[cfg(feature = "trace")]
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        with_current(|subscriber| subscriber.exit(self.name));
+    }
+}
+
+#[cfg(feature = "trace")]
+pub fn event(name: &'static str, message: std::fmt::Arguments) {
+    with_current(|subscriber| subscriber.event(name, &message.to_string()));
+}
+
+/// Enters a span until the end of the enclosing scope.
+macro_rules! trace_span {
+    ($name:expr) => {
+        let _span = $crate::diagnostics::enter($name);
+    };
+}
+
+/// Reports an event, whose message is only formatted with the `trace` feature.
+#[cfg(feature = "trace")]
+macro_rules! trace_event {
+    ($name:expr, $($arg:tt)*) => {
+        $crate::diagnostics::event($name, format_args!($($arg)*))
+    };
+}
+
+#[cfg(not(feature = "trace"))]
+macro_rules! trace_event {
+    ($name:expr, $($arg:tt)*) => {};
+}
+
+pub(crate) use trace_event;
+pub(crate) use trace_span;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::automaton::Automaton,
+        transition_system::transition_system_breadth_first_search::TransitionSystemBreadthFirstSearch,
+    };
+
+    use super::SearchStats;
+
+    const CHAIN: &str = "
+        location a init
+        location b
+        location c
+        a -in?-> b
+        a -out!-> c
+        b -out!-> c
+    ";
+
+    #[test]
+    fn diagnostics_search_stats_counts_states() {
+        let automaton = Automaton::parse(CHAIN).ok().unwrap();
+        let mut search =
+            TransitionSystemBreadthFirstSearch::new(automaton.get_actions(), &automaton)
+                .with_observer(SearchStats::default());
+
+        let states: HashSet<_> = search.by_ref().collect();
+        let stats = *search.get_observer();
+
+        assert_eq!(stats.states_visited, states.len());
+        assert_eq!(stats.max_depth, 1);
+        assert_eq!(stats.max_frontier, 2);
+        assert_eq!(stats.transitions_traversed, 3);
+        assert_eq!(
+            stats.to_string(),
+            "3 states visited, max depth 1, max frontier 2, 3 transitions traversed"
+        );
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn diagnostics_trace_spans_fire_during_exploration() {
+        use std::{cell::RefCell, rc::Rc};
+
+        use crate::transition_system::transition_system::TransitionSystem;
+
+        use super::{with_subscriber, Subscriber};
+
+        #[derive(Default)]
+        struct Recorder(RefCell<Vec<String>>);
+
+        impl Subscriber for Recorder {
+            fn enter(&self, span: &'static str) {
+                self.0.borrow_mut().push(format!("enter {}", span));
+            }
+
+            fn exit(&self, span: &'static str) {
+                self.0.borrow_mut().push(format!("exit {}", span));
+            }
+
+            fn event(&self, name: &'static str, _message: &str) {
+                self.0.borrow_mut().push(format!("event {}", name));
+            }
+        }
+        let recorder = Rc::new(Recorder::default());
+
+        let states = with_subscriber(recorder.clone(), || {
+            let automaton = Automaton::parse(CHAIN).ok().unwrap();
+            automaton.reachable_states(automaton.get_actions()).len()
+        });
+
+        let records = recorder.0.borrow();
+        assert_eq!(states, 3);
+        for expected in [
+            "enter automaton::new",
+            "event automaton::edge",
+            "exit automaton::new",
+            "enter breadth_first_search::next",
+            "exit breadth_first_search::next",
+        ] {
+            assert!(
+                records.iter().any(|record| record == expected),
+                "{}",
+                expected
+            );
+        }
+        let edges = records
+            .iter()
+            .filter(|record| *record == "event automaton::edge")
+            .count();
+        assert_eq!(edges, 3);
+    }
+}
@@ -1,13 +1,59 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     fmt::Display,
+    hash::Hash,
 };
 
-use super::{expression::Expression, statement::Statement, value::Value};
+use super::{expression::Expression, identifier::Identifier, statement::Statement, value::Value};
+
+/// The largest edit distance at which a declared identifier is suggested in
+/// place of an undeclared one.
+pub const SUGGESTION_DISTANCE: usize = 2;
+
+/// An undeclared identifier and the innermost subexpression it occurs in.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MissingIdentifier {
+    pub identifier: String,
+    pub context: Expression,
+    /// The closest declared identifier, if any is close enough to be a likely
+    /// misspelling. Only filled in by `Environment::suggest`, as finding it
+    /// compares against every declared identifier.
+    pub suggestion: Option<String>,
+}
+
+impl Display for MissingIdentifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "'{}' in '{}'",
+            self.identifier,
+            self.context.to_string()
+        ))?;
+        if let Some(suggestion) = &self.suggestion {
+            f.write_fmt(format_args!(" (did you mean '{}'?)", suggestion))?;
+        }
+        Ok(())
+    }
+}
+
+/// The number of single character insertions, deletions and substitutions
+/// needed to turn one string into the other.
+fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let rhs: Vec<char> = rhs.chars().collect();
+    let mut previous: Vec<usize> = (0..=rhs.len()).collect();
+    for (i, left) in lhs.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, right) in rhs.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left != *right);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[rhs.len()]
+}
 
 #[derive(Default, Debug, Clone, Eq, PartialEq)]
 pub struct Environment {
-    map: HashMap<String, Value>,
+    map: HashMap<Identifier, Value>,
 }
 
 impl Environment {
@@ -35,12 +81,48 @@ impl Environment {
         self.map.get(identifier)
     }
 
+    /// The declared identifiers within the edit distance of the identifier,
+    /// closest first and otherwise sorted by name. At most half of the
+    /// identifier may be edited, such that short identifiers are not taken
+    /// for misspellings of arbitrary others.
+    pub fn closest_identifiers(&self, identifier: &str, max_distance: usize) -> Vec<String> {
+        let max_distance = max_distance.min(identifier.chars().count() / 2);
+        let mut close: Vec<(usize, String)> = self
+            .map
+            .keys()
+            .map(|declared| {
+                (
+                    edit_distance(identifier, declared.as_str()),
+                    declared.to_string(),
+                )
+            })
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect();
+        close.sort();
+        close.into_iter().map(|(_, declared)| declared).collect()
+    }
+
+    /// The occurrences with the closest declared identifier suggested for each,
+    /// which is only worth finding once the occurrences are reported.
+    pub fn suggest(&self, occurrences: Vec<MissingIdentifier>) -> Vec<MissingIdentifier> {
+        occurrences
+            .into_iter()
+            .map(|occurrence| MissingIdentifier {
+                suggestion: self
+                    .closest_identifiers(&occurrence.identifier, SUGGESTION_DISTANCE)
+                    .into_iter()
+                    .next(),
+                ..occurrence
+            })
+            .collect()
+    }
+
     pub fn insert(&mut self, identifier: &str, value: &Value) -> bool {
         if self.contains(identifier) {
             return false;
         }
-        self.map.insert(String::from(identifier), value.clone());
-        return true;
+        self.map.insert(Identifier::new(identifier), value.clone());
+        true
     }
 
     pub fn concat(&mut self, other: &Environment) -> bool {
@@ -53,11 +135,11 @@ impl Environment {
     }
 
     pub fn set(&mut self, identifier: &str, value: &Value) -> bool {
-        if !self.contains(identifier) {
-            return false;
+        if let Some(slot) = self.map.get_mut(identifier) {
+            *slot = value.clone();
+            return true;
         }
-        self.map.insert(String::from(identifier), value.clone());
-        true
+        false
     }
 
     pub fn count(&self) -> usize {
@@ -66,9 +148,7 @@ impl Environment {
 
     pub fn contains_identifiers_in_expression(&self, expression: &Expression) -> bool {
         match expression {
-            Expression::Literal(literal) => {
-                return self.contains_identifiers_in_value(literal);
-            }
+            Expression::Literal(literal) => self.contains_identifiers_in_value(literal),
             Expression::Parenthesized(expr) => self.contains_identifiers_in_expression(expr),
             Expression::Binary(lhs, _, rhs) => {
                 self.contains_identifiers_in_expression(lhs)
@@ -81,9 +161,24 @@ impl Environment {
     pub fn contains_identifiers_in_statement(&self, statement: &Statement) -> bool {
         match statement {
             Statement::Assigment { identifier, value } => {
-                return self.contains_identifiers_in_expression(identifier)
-                    && self.contains_identifiers_in_expression(value);
+                self.contains_identifiers_in_expression(identifier)
+                    && self.contains_identifiers_in_expression(value)
+            }
+            Statement::Sequence(statements) => statements
+                .iter()
+                .all(|statement| self.contains_identifiers_in_statement(statement)),
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                self.contains_identifiers_in_expression(condition)
+                    && self.contains_identifiers_in_statement(then)
+                    && otherwise
+                        .as_ref()
+                        .is_none_or(|otherwise| self.contains_identifiers_in_statement(otherwise))
             }
+            Statement::ClockReset { clock } => self.contains(clock),
         }
     }
 
@@ -95,27 +190,43 @@ impl Environment {
     }
 
     pub fn missing_identifiers_in_expression(&self, expression: &Expression) -> Vec<String> {
+        self.missing_identifier_occurrences_in_expression(expression)
+            .into_iter()
+            .map(|occurrence| occurrence.identifier)
+            .collect()
+    }
+
+    /// Finds the undeclared identifiers along with the innermost subexpression
+    /// they occur in, such that the offending operand can be reported.
+    pub fn missing_identifier_occurrences_in_expression(
+        &self,
+        expression: &Expression,
+    ) -> Vec<MissingIdentifier> {
         let mut missing = Vec::new();
-        let mut worklist: VecDeque<&Expression> = VecDeque::new();
-        worklist.push_back(expression);
+        let mut worklist: VecDeque<(&Expression, &Expression)> = VecDeque::new();
+        worklist.push_back((expression, expression));
 
-        let mut contains_value_or_add = |value: &Value| {
+        let mut contains_value_or_add = |value: &Value, context: &Expression| {
             if let Value::Identifier(identifier) = value {
                 if !self.contains(identifier) {
-                    missing.push(identifier.clone());
+                    missing.push(MissingIdentifier {
+                        identifier: identifier.to_string(),
+                        context: context.clone(),
+                        suggestion: None,
+                    });
                 }
             }
         };
 
-        while !worklist.is_empty() {
-            match worklist.pop_front().unwrap() {
-                Expression::Literal(literal) => contains_value_or_add(literal),
-                Expression::Parenthesized(expr) => worklist.push_back(expr),
+        while let Some((current, parent)) = worklist.pop_front() {
+            match current {
+                Expression::Literal(literal) => contains_value_or_add(literal, parent),
+                Expression::Parenthesized(expr) => worklist.push_back((expr, current)),
                 Expression::Binary(lhs, _, rhs) => {
-                    worklist.push_back(lhs);
-                    worklist.push_back(rhs);
+                    worklist.push_back((lhs, current));
+                    worklist.push_back((rhs, current));
                 }
-                Expression::Unary(_, expr) => worklist.push_back(expr),
+                Expression::Unary(_, expr) => worklist.push_back((expr, current)),
             }
         }
 
@@ -123,15 +234,42 @@ impl Environment {
     }
 
     pub fn missing_identifiers_in_statement(&self, statement: &Statement) -> Vec<String> {
+        self.missing_identifier_occurrences_in_statement(statement)
+            .into_iter()
+            .map(|occurrence| occurrence.identifier)
+            .collect()
+    }
+
+    pub fn missing_identifier_occurrences_in_statement(
+        &self,
+        statement: &Statement,
+    ) -> Vec<MissingIdentifier> {
         let mut missing = Vec::new();
         let mut worklist: VecDeque<&Statement> = VecDeque::new();
         worklist.push_back(statement);
 
-        while !worklist.is_empty() {
-            match worklist.pop_front().unwrap() {
+        while let Some(current) = worklist.pop_front() {
+            match current {
                 Statement::Assigment { identifier, value } => {
-                    missing.extend(self.missing_identifiers_in_expression(identifier));
-                    missing.extend(self.missing_identifiers_in_expression(value));
+                    missing.extend(self.missing_identifier_occurrences_in_expression(identifier));
+                    missing.extend(self.missing_identifier_occurrences_in_expression(value));
+                }
+                Statement::ClockReset { clock } => {
+                    missing.extend(self.missing_identifier_occurrences_in_expression(
+                        &Expression::new_identifier(clock),
+                    ))
+                }
+                Statement::Sequence(statements) => worklist.extend(statements),
+                Statement::If {
+                    condition,
+                    then,
+                    otherwise,
+                } => {
+                    missing.extend(self.missing_identifier_occurrences_in_expression(condition));
+                    worklist.push_back(then);
+                    if let Some(otherwise) = otherwise {
+                        worklist.push_back(otherwise);
+                    }
                 }
             }
         }
@@ -139,6 +277,55 @@ impl Environment {
         missing
     }
 
+    /// The identifiers whose values differ between the two environments, sorted
+    /// by identifier, along with the value in this and in the other environment.
+    pub fn differences(&self, other: &Environment) -> Vec<(String, Option<Value>, Option<Value>)> {
+        let mut identifiers: Vec<&Identifier> = self.map.keys().collect();
+        identifiers.extend(other.map.keys().filter(|key| !self.contains(key)));
+        identifiers.sort_by(|lhs, rhs| lhs.as_str().cmp(rhs.as_str()));
+
+        identifiers
+            .into_iter()
+            .filter(|identifier| self.get_value(identifier) != other.get_value(identifier))
+            .map(|identifier| {
+                (
+                    identifier.to_string(),
+                    self.get_value(identifier).cloned(),
+                    other.get_value(identifier).cloned(),
+                )
+            })
+            .collect()
+    }
+
+    /// The environment restricted to the identifiers in `keep`.
+    pub fn project(&self, keep: &HashSet<String>) -> Environment {
+        Environment {
+            map: self
+                .map
+                .iter()
+                .filter(|(identifier, _)| keep.contains(identifier.as_str()))
+                .map(|(identifier, value)| (identifier.clone(), value.clone()))
+                .collect(),
+        }
+    }
+
+    /// True if the environments agree on every identifier not in `ignore`.
+    pub fn equal_except(&self, other: &Environment, ignore: &HashSet<String>) -> bool {
+        let agrees = |lhs: &Environment, rhs: &Environment| {
+            lhs.map.iter().all(|(identifier, value)| {
+                ignore.contains(identifier.as_str()) || rhs.get_value(identifier) == Some(value)
+            })
+        };
+        agrees(self, other) && agrees(other, self)
+    }
+
+    /// The identifiers and their values sorted by identifier.
+    pub fn sorted_entries(&self) -> Vec<(&Identifier, &Value)> {
+        let mut entries: Vec<(&Identifier, &Value)> = self.map.iter().collect();
+        entries.sort();
+        entries
+    }
+
     pub fn is_disjoint(&self, other: &Environment) -> bool {
         for key in self.map.keys() {
             if other.contains(key) {
@@ -146,16 +333,36 @@ impl Environment {
             }
         }
 
-        return true;
+        true
+    }
+}
+
+impl Hash for Environment {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        // Hash in identifier order such that equal maps hash equally
+        self.sorted_entries().hash(state);
+    }
+}
+
+/// Environments are ordered lexicographically by their bindings sorted by identifier.
+impl Ord for Environment {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.sorted_entries().cmp(&other.sorted_entries())
+    }
+}
+
+impl PartialOrd for Environment {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 impl Display for Environment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let displays: Vec<&str> = self
-            .map
-            .iter()
-            .map(|(key, value)| format_args!("{} := {}", key, value).as_str().unwrap())
+        let displays: Vec<String> = self
+            .sorted_entries()
+            .into_iter()
+            .map(|(key, value)| format!("{} := {}", key, value))
             .collect();
         f.write_fmt(format_args!("{:#?}", displays))
     }
@@ -163,6 +370,8 @@ impl Display for Environment {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use crate::language::{expression::Expression, statement::Statement, value::Value};
 
     use super::Environment;
@@ -211,7 +420,7 @@ mod tests {
     fn environment_contains_identifiers_in_value_has_identifier() {
         let mut environment = Environment::new_empty();
         environment.insert("a", &Value::Bool(true));
-        let identifier = Value::Identifier(String::from("a"));
+        let identifier = Value::new_identifier("a");
 
         let contains = environment.contains_identifiers_in_value(&identifier);
 
@@ -222,7 +431,7 @@ mod tests {
     fn environment_contains_identifiers_in_value_does_not_have_identifier() {
         let mut environment = Environment::new_empty();
         environment.insert("a", &Value::Bool(true));
-        let identifier = Value::Identifier(String::from("b"));
+        let identifier = Value::new_identifier("b");
 
         let contains = environment.contains_identifiers_in_value(&identifier);
 
@@ -233,7 +442,7 @@ mod tests {
     fn environment_contains_identifiers_in_node_literal_has_identifier() {
         let mut environment = Environment::new_empty();
         environment.insert("a", &Value::Bool(true));
-        let identifier = Expression::Literal(Value::Identifier(String::from("a")));
+        let identifier = Expression::Literal(Value::new_identifier("a"));
 
         let contains = environment.contains_identifiers_in_expression(&identifier);
 
@@ -244,7 +453,7 @@ mod tests {
     fn environment_contains_identifiers_in_node_literal_does_not_have_identifier() {
         let mut environment = Environment::new_empty();
         environment.insert("a", &Value::Bool(true));
-        let identifier = Expression::Literal(Value::Identifier(String::from("b")));
+        let identifier = Expression::Literal(Value::new_identifier("b"));
 
         let contains = environment.contains_identifiers_in_expression(&identifier);
 
@@ -372,6 +581,43 @@ mod tests {
         assert!(!disjoint);
     }
 
+    #[test]
+    fn environment_display_does_not_panic() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::Bool(true));
+
+        let display = environment.to_string();
+
+        assert!(display.contains("a := true"));
+    }
+
+    #[test]
+    fn environment_differences() {
+        let mut left = Environment::new_empty();
+        left.insert("b", &Value::Bool(false));
+        left.insert("a", &Value::Bool(true));
+        left.insert("same", &Value::Bool(true));
+        let mut right = Environment::new_empty();
+        right.insert("b", &Value::Bool(true));
+        right.insert("same", &Value::Bool(true));
+        right.insert("c", &Value::Bool(true));
+
+        let differences = left.differences(&right);
+
+        assert_eq!(
+            differences,
+            vec![
+                (String::from("a"), Some(Value::Bool(true)), None),
+                (
+                    String::from("b"),
+                    Some(Value::Bool(false)),
+                    Some(Value::Bool(true))
+                ),
+                (String::from("c"), None, Some(Value::Bool(true))),
+            ]
+        );
+    }
+
     #[test]
     fn environment_missing_identifiers_in_node() {
         let mut environment = Environment::new_empty();
@@ -383,4 +629,118 @@ mod tests {
         assert_eq!(missing.len(), 1);
         assert_eq!(missing[0], "b");
     }
+
+    #[test]
+    fn environment_missing_identifier_occurrences_in_expression() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::Bool(false));
+        let operand = Expression::new_logical_or(
+            &Expression::new_identifier("a"),
+            &Expression::new_identifier("b"),
+        );
+        let expression = Expression::new_logical_and(&Expression::new_boolean(&true), &operand);
+
+        let missing = environment.missing_identifier_occurrences_in_expression(&expression);
+
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].identifier, "b");
+        assert_eq!(missing[0].context, operand);
+        assert_eq!(missing[0].to_string(), "'b' in 'a || b'");
+        assert_eq!(missing[0].suggestion, None);
+    }
+
+    #[test]
+    fn environment_suggest_closest_identifier() {
+        let mut environment = Environment::new_empty();
+        environment.insert("ready", &Value::Bool(false));
+        let expression = Expression::new_logical_and(
+            &Expression::new_identifier("read"),
+            &Expression::new_identifier("unrelated"),
+        );
+        let missing = environment.missing_identifier_occurrences_in_expression(&expression);
+
+        let suggested = environment.suggest(missing);
+
+        let suggestions: Vec<Option<&str>> = suggested
+            .iter()
+            .map(|occurrence| occurrence.suggestion.as_deref())
+            .collect();
+        assert_eq!(suggestions, vec![Some("ready"), None]);
+    }
+
+    #[test]
+    fn environment_equal_except_ignored_difference() {
+        let mut lhs = Environment::new_empty();
+        lhs.insert("a", &Value::Bool(false));
+        lhs.insert("b", &Value::Bool(false));
+        let mut rhs = Environment::new_empty();
+        rhs.insert("a", &Value::Bool(false));
+        rhs.insert("b", &Value::Bool(true));
+        rhs.insert("c", &Value::Bool(true));
+        let ignore = HashSet::from([String::from("b"), String::from("c")]);
+
+        let equal = lhs.equal_except(&rhs, &ignore);
+
+        assert!(equal);
+    }
+
+    #[test]
+    fn environment_equal_except_non_ignored_difference() {
+        let mut lhs = Environment::new_empty();
+        lhs.insert("a", &Value::Bool(false));
+        lhs.insert("b", &Value::Bool(false));
+        let mut rhs = Environment::new_empty();
+        rhs.insert("a", &Value::Bool(true));
+        rhs.insert("b", &Value::Bool(true));
+        let ignore = HashSet::from([String::from("b")]);
+
+        let equal = lhs.equal_except(&rhs, &ignore);
+
+        assert!(!equal);
+    }
+
+    #[test]
+    fn environment_project_keeps_only_given_identifiers() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::Bool(false));
+        environment.insert("b", &Value::Bool(true));
+        let keep = HashSet::from([String::from("b"), String::from("c")]);
+
+        let projection = environment.project(&keep);
+
+        assert_eq!(projection.count(), 1);
+        assert_eq!(projection.get_value("b"), Some(&Value::Bool(true)));
+        assert!(!projection.contains("a"));
+    }
+
+    #[test]
+    fn environment_equal_environments_share_hash_set_entry() {
+        let names: Vec<String> = (0..32).map(|index| format!("v{}", index)).collect();
+        let mut forward = Environment::new_empty();
+        for (index, name) in names.iter().enumerate() {
+            forward.insert(name, &Value::new_boolean(index % 3 == 0));
+        }
+        let mut backward = Environment::new_empty();
+        for (index, name) in names.iter().enumerate().rev() {
+            backward.insert(name, &Value::new_boolean(index % 3 == 0));
+        }
+
+        let set = HashSet::from([forward, backward]);
+
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn environment_closest_identifiers() {
+        let mut environment = Environment::new_empty();
+        environment.insert("ready", &Value::new_false());
+        environment.insert("read", &Value::new_false());
+        environment.insert("done", &Value::new_false());
+
+        let close = environment.closest_identifiers("redy", 2);
+
+        assert_eq!(close, vec![String::from("ready"), String::from("read")]);
+        assert!(environment.closest_identifiers("unrelated", 2).is_empty());
+        assert!(environment.closest_identifiers("do", 2).is_empty());
+    }
 }
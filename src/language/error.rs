@@ -1,9 +1,96 @@
+use std::fmt::Display;
+
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq, PartialOrd)]
+use super::lang_type::LangType;
+
+/// Whether a diagnostic prevents the model from being used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq, PartialOrd)]
 pub enum Error {
-    #[error("Encountered a runtime error: {message:}")]
+    #[error("{code}: Encountered a runtime error: {message:}", code = self.code())]
     RuntimeError { message: String },
-    #[error("Encountered a type checking error: {message:}")]
+    #[error("{code}: Encountered a type checking error: {message:}", code = self.code())]
     TypeCheckingError { message: String },
+    #[error("{code}: Cannot assign a {actual:} value to {identifier:} of type {expected:}", code = self.code())]
+    AssignmentTypeMismatch {
+        identifier: String,
+        expected: LangType,
+        actual: LangType,
+    },
+    #[error("{code}: Could not parse at {position:}: {message:}", code = self.code())]
+    ParseError { position: usize, message: String },
+    #[error("{code}: Ran out of fuel after {consumed:} evaluation steps", code = self.code())]
+    FuelExhausted { consumed: usize },
+}
+
+impl Error {
+    /// A stable machine readable code of the variant, which is never reused
+    /// for another variant once assigned.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::RuntimeError { .. } => "LNG0001",
+            Error::TypeCheckingError { .. } => "LNG0002",
+            Error::AssignmentTypeMismatch { .. } => "LNG0003",
+            Error::ParseError { .. } => "LNG0004",
+            Error::FuelExhausted { .. } => "LNG0005",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        Severity::Error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::language::lang_type::LangType;
+
+    use super::Error;
+
+    #[test]
+    fn language_error_codes_are_unique_and_prefix_display() {
+        let errors = [
+            Error::RuntimeError {
+                message: String::from("failed"),
+            },
+            Error::TypeCheckingError {
+                message: String::from("failed"),
+            },
+            Error::AssignmentTypeMismatch {
+                identifier: String::from("x"),
+                expected: LangType::Logical,
+                actual: LangType::Clock,
+            },
+            Error::ParseError {
+                position: 0,
+                message: String::from("failed"),
+            },
+            Error::FuelExhausted { consumed: 3 },
+        ];
+
+        let codes: HashSet<&str> = errors.iter().map(Error::code).collect();
+
+        assert_eq!(codes.len(), errors.len());
+        assert_eq!(
+            errors[2].to_string(),
+            "LNG0003: Cannot assign a clock value to x of type logical"
+        );
+    }
 }
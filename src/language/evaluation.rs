@@ -1,10 +1,11 @@
 use std::fmt::Display;
 
-use super::value::Value;
+use super::{error::Error, value::Value};
 
 #[derive(Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub enum Evaluation {
     Bool(bool),
+    Clock(u32),
     Void,
 }
 
@@ -27,52 +28,109 @@ impl Evaluation {
 
     pub fn is_false(&self) -> bool {
         match self {
-            Evaluation::Bool(value) => return *value == false,
+            Evaluation::Bool(value) => !*value,
             _ => false,
         }
     }
 
     pub fn is_true(&self) -> bool {
         match self {
-            Evaluation::Bool(value) => return *value == true,
+            Evaluation::Bool(value) => *value,
             _ => false,
         }
     }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Evaluation::Bool(value) => Some(*value),
+            Evaluation::Clock(_) | Evaluation::Void => None,
+        }
+    }
+
+    pub fn as_clock(&self) -> Option<u32> {
+        match self {
+            Evaluation::Clock(time) => Some(*time),
+            Evaluation::Bool(_) | Evaluation::Void => None,
+        }
+    }
+
+    pub fn as_value(&self) -> Option<Value> {
+        match self {
+            Evaluation::Bool(value) => Some(Value::new_boolean(*value)),
+            Evaluation::Clock(time) => Some(Value::new_clock(*time)),
+            Evaluation::Void => None,
+        }
+    }
 }
 
 impl Display for Evaluation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Evaluation::Bool(value) => f.write_fmt(format_args!("{}", value)),
+            Evaluation::Clock(time) => f.write_fmt(format_args!("{}", time)),
             Evaluation::Void => f.write_str("void"),
         }
     }
 }
 
-impl From<Value> for Evaluation {
-    fn from(value: Value) -> Self {
+impl TryFrom<&Evaluation> for bool {
+    type Error = Error;
+
+    fn try_from(evaluation: &Evaluation) -> Result<Self, Self::Error> {
+        evaluation.as_bool().ok_or_else(|| Error::RuntimeError {
+            message: format!("Evaluation {} is not boolean", evaluation),
+        })
+    }
+}
+
+impl TryFrom<&Evaluation> for Value {
+    type Error = Error;
+
+    fn try_from(evaluation: &Evaluation) -> Result<Self, Self::Error> {
+        evaluation.as_value().ok_or_else(|| Error::RuntimeError {
+            message: format!("Evaluation {} cannot be converted to a value", evaluation),
+        })
+    }
+}
+
+impl TryFrom<&Value> for Evaluation {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
         match value {
-            Value::Bool(value) => return Evaluation::Bool(value),
-            Value::Identifier(_) => panic!("Identifier cannot be converted to an evaluation"),
+            Value::Bool(value) => Ok(Evaluation::Bool(*value)),
+            Value::Clock(time) => Ok(Evaluation::Clock(*time)),
+            Value::Identifier(identifier) => Err(Error::RuntimeError {
+                message: format!(
+                    "Identifier {} cannot be converted to an evaluation",
+                    identifier
+                ),
+            }),
         }
     }
 }
 
-impl Into<Value> for Evaluation {
-    fn into(self) -> Value {
-        match self {
-            Evaluation::Bool(boolean) => Value::new_boolean(boolean),
-            Evaluation::Void => panic!("Void evaluation cannot be converted to a value"),
-        }
+impl TryFrom<Evaluation> for bool {
+    type Error = Error;
+
+    fn try_from(evaluation: Evaluation) -> Result<Self, Self::Error> {
+        bool::try_from(&evaluation)
     }
 }
 
-impl Into<bool> for Evaluation {
-    fn into(self) -> bool {
-        match self {
-            Evaluation::Bool(value) => value,
-            _ => panic!("Evaluation is not boolean"),
-        }
+impl TryFrom<Evaluation> for Value {
+    type Error = Error;
+
+    fn try_from(evaluation: Evaluation) -> Result<Self, Self::Error> {
+        Value::try_from(&evaluation)
+    }
+}
+
+impl TryFrom<Value> for Evaluation {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        Evaluation::try_from(&value)
     }
 }
 
@@ -81,3 +139,65 @@ impl From<bool> for Evaluation {
         Evaluation::new_boolean(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::language::{error::Error, value::Value};
+
+    use super::Evaluation;
+
+    #[test]
+    fn evaluation_as_bool() {
+        assert_eq!(Evaluation::new_true().as_bool(), Some(true));
+        assert_eq!(Evaluation::new_false().as_bool(), Some(false));
+        assert_eq!(Evaluation::new_void().as_bool(), None);
+    }
+
+    #[test]
+    fn evaluation_as_value() {
+        assert_eq!(Evaluation::new_true().as_value(), Some(Value::new_true()));
+        assert_eq!(Evaluation::new_void().as_value(), None);
+    }
+
+    #[test]
+    fn evaluation_try_from_void_is_err() {
+        let void = Evaluation::new_void();
+
+        let boolean = bool::try_from(&void);
+        let value = Value::try_from(&void);
+
+        assert!(matches!(boolean, Err(Error::RuntimeError { .. })));
+        assert!(matches!(value, Err(Error::RuntimeError { .. })));
+    }
+
+    #[test]
+    fn evaluation_try_from_identifier_is_err() {
+        let identifier = Value::new_identifier("ident");
+
+        let evaluation = Evaluation::try_from(&identifier);
+
+        assert!(matches!(evaluation, Err(Error::RuntimeError { .. })));
+        assert_eq!(
+            Evaluation::try_from(&Value::new_true()),
+            Ok(Evaluation::new_true())
+        );
+    }
+
+    #[test]
+    fn evaluation_owned_conversions_report_errors() {
+        let boolean = bool::try_from(Evaluation::new_void());
+        let value = Value::try_from(Evaluation::new_void());
+        let evaluation = Evaluation::try_from(Value::new_identifier("ident"));
+        let clock = bool::try_from(Evaluation::Clock(3));
+
+        assert!(matches!(boolean, Err(Error::RuntimeError { .. })));
+        assert!(matches!(value, Err(Error::RuntimeError { .. })));
+        assert!(matches!(evaluation, Err(Error::RuntimeError { .. })));
+        assert!(matches!(clock, Err(Error::RuntimeError { .. })));
+        assert_eq!(
+            Value::try_from(Evaluation::Clock(3)),
+            Ok(Value::new_clock(3))
+        );
+        assert_eq!(Evaluation::from(true), Evaluation::new_true());
+    }
+}
@@ -1,8 +1,17 @@
-use std::collections::VecDeque;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, OnceLock},
+};
 
-use super::value::Value;
+use super::{
+    environment::Environment, evaluation::Evaluation, interpreter::Interpreter, value::Value,
+};
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+/// The most free variables for which `Expression::implies` tries every
+/// assignment.
+pub const MAX_IMPLICATION_VARIABLES: usize = 20;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub enum BinaryOperator {
     LogicalAnd,
     LogicalOr,
@@ -10,6 +19,47 @@ pub enum BinaryOperator {
     NotEqual,
     Implication,
     BiImplication,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+impl BinaryOperator {
+    /// True for the operators comparing clocks, which are neither logical
+    /// operators nor commutative.
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            BinaryOperator::Less
+                | BinaryOperator::LessEqual
+                | BinaryOperator::Greater
+                | BinaryOperator::GreaterEqual
+        )
+    }
+
+    /// How tightly the operator binds in the parser, from `<-->` binding the
+    /// loosest to the clock comparisons binding the tightest.
+    fn precedence(&self) -> u8 {
+        match self {
+            BinaryOperator::BiImplication => 0,
+            BinaryOperator::Implication => 1,
+            BinaryOperator::LogicalOr => 2,
+            BinaryOperator::LogicalAnd => 3,
+            BinaryOperator::Equal | BinaryOperator::NotEqual => 4,
+            BinaryOperator::Less
+            | BinaryOperator::LessEqual
+            | BinaryOperator::Greater
+            | BinaryOperator::GreaterEqual => 5,
+        }
+    }
+
+    fn is_right_associative(&self) -> bool {
+        matches!(
+            self,
+            BinaryOperator::BiImplication | BinaryOperator::Implication
+        )
+    }
 }
 
 impl ToString for BinaryOperator {
@@ -21,11 +71,15 @@ impl ToString for BinaryOperator {
             BinaryOperator::NotEqual => String::from("!="),
             BinaryOperator::Implication => String::from("-->"),
             BinaryOperator::BiImplication => String::from("<-->"),
+            BinaryOperator::Less => String::from("<"),
+            BinaryOperator::LessEqual => String::from("<="),
+            BinaryOperator::Greater => String::from(">"),
+            BinaryOperator::GreaterEqual => String::from(">="),
         }
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub enum UnaryOperator {
     Negation,
 }
@@ -38,12 +92,90 @@ impl ToString for UnaryOperator {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+/// Expressions are ordered structurally, first by variant in declaration order
+/// and then by their operands.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub enum Expression {
     Literal(Value),
-    Parenthesized(Box<Expression>),
-    Binary(Box<Expression>, BinaryOperator, Box<Expression>),
-    Unary(UnaryOperator, Box<Expression>),
+    Parenthesized(Operand),
+    Binary(Operand, BinaryOperator, Operand),
+    Unary(UnaryOperator, Operand),
+}
+
+/// A shared operand of an expression. The last reference to a nested operand
+/// releases the operands below it iteratively rather than recursively, such
+/// that dropping a deeply nested expression does not exhaust the call stack.
+#[derive(Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct Operand(Arc<Expression>);
+
+impl Operand {
+    pub fn new(expression: Expression) -> Operand {
+        Operand(Arc::new(expression))
+    }
+
+    /// True if both operands refer to the same expression.
+    pub fn ptr_eq(&self, other: &Operand) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// The number of operands referring to the same expression as this one.
+    pub fn strong_count(&self) -> usize {
+        Arc::strong_count(&self.0)
+    }
+
+    /// Takes the expression if this is the last reference to a nested one,
+    /// leaving a shared literal in its place.
+    fn take_unshared(&mut self) -> Option<Arc<Expression>> {
+        static EMPTY: OnceLock<Arc<Expression>> = OnceLock::new();
+        if Arc::strong_count(&self.0) > 1 || matches!(*self.0, Expression::Literal(_)) {
+            return None;
+        }
+        let empty = EMPTY.get_or_init(|| Arc::new(Expression::Literal(Value::Bool(true))));
+        Some(std::mem::replace(&mut self.0, empty.clone()))
+    }
+}
+
+impl std::ops::Deref for Operand {
+    type Target = Expression;
+
+    fn deref(&self) -> &Expression {
+        &self.0
+    }
+}
+
+impl AsRef<Expression> for Operand {
+    fn as_ref(&self) -> &Expression {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Drop for Operand {
+    fn drop(&mut self) {
+        // Operands shared with other expressions are only released. The left
+        // operand is released next, such that chains need no pending operands
+        let mut pending: Vec<Arc<Expression>> = Vec::new();
+        let mut next = self.take_unshared();
+        while let Some(expression) = next.take().or_else(|| pending.pop()) {
+            if let Ok(mut expression) = Arc::try_unwrap(expression) {
+                match &mut expression {
+                    Expression::Literal(_) => (),
+                    Expression::Parenthesized(operand) | Expression::Unary(_, operand) => {
+                        next = operand.take_unshared()
+                    }
+                    Expression::Binary(lhs, _, rhs) => {
+                        next = lhs.take_unshared();
+                        pending.extend(rhs.take_unshared());
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl Expression {
@@ -61,9 +193,9 @@ impl Expression {
         rhs: &Expression,
     ) -> Expression {
         Expression::Binary(
-            Box::new(lhs.to_owned()),
+            Operand::new(lhs.to_owned()),
             op.to_owned(),
-            Box::new(rhs.to_owned()),
+            Operand::new(rhs.to_owned()),
         )
     }
 
@@ -72,7 +204,11 @@ impl Expression {
     }
 
     pub fn new_parenthesized(expression: &Expression) -> Expression {
-        Self::Parenthesized(Box::new(expression.clone()))
+        Self::Parenthesized(Operand::new(expression.clone()))
+    }
+
+    pub fn new_logical_not(operand: &Expression) -> Expression {
+        Expression::Unary(UnaryOperator::Negation, Operand::new(operand.clone()))
     }
 
     pub fn new_logical_and(lhs: &Expression, rhs: &Expression) -> Expression {
@@ -99,45 +235,404 @@ impl Expression {
         Expression::new_binary_expression(lhs, &BinaryOperator::NotEqual, rhs)
     }
 
+    pub fn new_clock(time: u32) -> Expression {
+        Self::new_literal(&Value::new_clock(time))
+    }
+
+    /// The clock constraint comparing the clock to the bound, such as `x <= 5`.
+    pub fn new_clock_constraint(clock: &str, op: &BinaryOperator, bound: u32) -> Expression {
+        Expression::new_binary_expression(
+            &Expression::new_identifier(clock),
+            op,
+            &Expression::new_clock(bound),
+        )
+    }
+
+    /// Conjoins the operands without duplicates and simplifies the result, such
+    /// that it is false if any operand is literal false and true if no operands
+    /// are left. Operands which are not conjunctions themselves are
+    /// parenthesized, such that the printed form keeps the structure.
+    pub fn new_conjunction(operands: &[Expression]) -> Expression {
+        let mut kept: Vec<&Expression> = Vec::new();
+        for operand in operands {
+            if !kept.contains(&operand) {
+                kept.push(operand);
+            }
+        }
+        kept.into_iter()
+            .fold(Expression::new_boolean(&true), |conjunction, operand| {
+                Expression::new_logical_and(&conjunction, &operand.parenthesized_if_binary())
+            })
+            .simplify()
+    }
+
+    /// Wraps binary expressions in parentheses and leaves other expressions as
+    /// they are.
+    pub fn parenthesized_if_binary(&self) -> Expression {
+        match self {
+            Expression::Binary(..) => Expression::new_parenthesized(self),
+            _ => self.clone(),
+        }
+    }
+
+    /// The expression where every occurrence of the identifier is replaced by
+    /// the replacement, which is parenthesized if it is a binary expression.
+    pub fn substitute(&self, identifier: &str, replacement: &Expression) -> Expression {
+        match self {
+            Expression::Literal(Value::Identifier(name)) if name.as_str() == identifier => {
+                replacement.parenthesized_if_binary()
+            }
+            Expression::Literal(_) => self.clone(),
+            Expression::Parenthesized(inner) => {
+                Expression::new_parenthesized(&inner.substitute(identifier, replacement))
+            }
+            Expression::Binary(lhs, operator, rhs) => Expression::new_binary_expression(
+                &lhs.substitute(identifier, replacement),
+                operator,
+                &rhs.substitute(identifier, replacement),
+            ),
+            Expression::Unary(operator, operand) => Expression::Unary(
+                operator.clone(),
+                Operand::new(operand.substitute(identifier, replacement)),
+            ),
+        }
+    }
+
+    /// The expression with the operands of commutative operators in sorted
+    /// order, such that expressions equal up to commutativity are equal. Chains
+    /// of `&&` or `||`, including parenthesized ones, are flattened and rebuilt
+    /// left associated.
+    pub fn canonicalize(&self) -> Expression {
+        match self {
+            Expression::Literal(_) => self.clone(),
+            Expression::Parenthesized(inner) => {
+                Expression::new_parenthesized(&inner.canonicalize())
+            }
+            Expression::Unary(operator, operand) => {
+                Expression::Unary(operator.clone(), Operand::new(operand.canonicalize()))
+            }
+            Expression::Binary(
+                _,
+                operator @ (BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr),
+                _,
+            ) => {
+                let mut operands = Vec::new();
+                self.chain_operands(operator, &mut operands);
+                let mut operands: Vec<Expression> = operands
+                    .iter()
+                    .map(|operand| operand.canonicalize())
+                    .collect();
+                operands.sort();
+                let mut operands = operands.into_iter();
+                let first = operands.next().unwrap();
+                operands.fold(first, |lhs, rhs| {
+                    Expression::new_binary_expression(&lhs, operator, &rhs)
+                })
+            }
+            Expression::Binary(lhs, operator, rhs)
+                if *operator == BinaryOperator::Implication || operator.is_comparison() =>
+            {
+                Expression::new_binary_expression(
+                    &lhs.canonicalize(),
+                    operator,
+                    &rhs.canonicalize(),
+                )
+            }
+            Expression::Binary(lhs, operator, rhs) => {
+                let (lhs, rhs) = (lhs.canonicalize(), rhs.canonicalize());
+                match lhs <= rhs {
+                    true => Expression::new_binary_expression(&lhs, operator, &rhs),
+                    false => Expression::new_binary_expression(&rhs, operator, &lhs),
+                }
+            }
+        }
+    }
+
+    /// Collects the operands of a chain of the associative operator, looking
+    /// through parentheses around nested chains of the same operator.
+    fn chain_operands<'a>(&'a self, operator: &BinaryOperator, operands: &mut Vec<&'a Expression>) {
+        match self {
+            Expression::Binary(lhs, nested, rhs) if nested == operator => {
+                lhs.chain_operands(operator, operands);
+                rhs.chain_operands(operator, operands);
+            }
+            Expression::Parenthesized(inner) if matches!(inner.as_ref(), Expression::Binary(_, nested, _) if nested == operator) => {
+                inner.chain_operands(operator, operands)
+            }
+            _ => operands.push(self),
+        }
+    }
+
+    /// The expression with literal booleans folded away, such as `x && true`
+    /// to `x` and `false --> x` to `true`, along with double negations and
+    /// operators applied to equal operands. Comparisons of two clock constants
+    /// are folded too. Parentheses are kept around binary expressions and
+    /// dropped elsewhere.
+    pub fn simplify(&self) -> Expression {
+        match self {
+            Expression::Literal(_) => self.clone(),
+            Expression::Parenthesized(inner) => inner.simplify().parenthesized_if_binary(),
+            Expression::Unary(UnaryOperator::Negation, operand) => operand.simplify().negated(),
+            Expression::Binary(lhs, operator, rhs) => {
+                let (lhs, rhs) = (lhs.simplify(), rhs.simplify());
+                if let (Some(lhs), Some(rhs)) = (lhs.as_clock(), rhs.as_clock()) {
+                    if let Some(value) = Self::compare_clocks(lhs, operator, rhs) {
+                        return Expression::new_boolean(&value);
+                    }
+                }
+                let (lhs_value, rhs_value) = (lhs.as_boolean(), rhs.as_boolean());
+                match operator {
+                    BinaryOperator::LogicalAnd => match (lhs_value, rhs_value) {
+                        (Some(false), _) | (_, Some(false)) => Expression::new_boolean(&false),
+                        (Some(true), _) => rhs,
+                        (_, Some(true)) => lhs,
+                        _ if lhs == rhs => lhs,
+                        _ => Self::new_simplified_binary(&lhs, operator, &rhs),
+                    },
+                    BinaryOperator::LogicalOr => match (lhs_value, rhs_value) {
+                        (Some(true), _) | (_, Some(true)) => Expression::new_boolean(&true),
+                        (Some(false), _) => rhs,
+                        (_, Some(false)) => lhs,
+                        _ if lhs == rhs => lhs,
+                        _ => Self::new_simplified_binary(&lhs, operator, &rhs),
+                    },
+                    BinaryOperator::Equal | BinaryOperator::BiImplication => {
+                        match (lhs_value, rhs_value) {
+                            (Some(lhs), Some(rhs)) => Expression::new_boolean(&(lhs == rhs)),
+                            (Some(true), _) => rhs,
+                            (_, Some(true)) => lhs,
+                            (Some(false), _) => rhs.negated(),
+                            (_, Some(false)) => lhs.negated(),
+                            _ if lhs == rhs => Expression::new_boolean(&true),
+                            _ => Self::new_simplified_binary(&lhs, operator, &rhs),
+                        }
+                    }
+                    BinaryOperator::NotEqual => match (lhs_value, rhs_value) {
+                        (Some(lhs), Some(rhs)) => Expression::new_boolean(&(lhs != rhs)),
+                        (Some(false), _) => rhs,
+                        (_, Some(false)) => lhs,
+                        (Some(true), _) => rhs.negated(),
+                        (_, Some(true)) => lhs.negated(),
+                        _ if lhs == rhs => Expression::new_boolean(&false),
+                        _ => Self::new_simplified_binary(&lhs, operator, &rhs),
+                    },
+                    BinaryOperator::Less
+                    | BinaryOperator::LessEqual
+                    | BinaryOperator::Greater
+                    | BinaryOperator::GreaterEqual => {
+                        Self::new_simplified_binary(&lhs, operator, &rhs)
+                    }
+                    BinaryOperator::Implication => match (lhs_value, rhs_value) {
+                        (Some(false), _) | (_, Some(true)) => Expression::new_boolean(&true),
+                        (Some(true), _) => rhs,
+                        (_, Some(false)) => lhs.negated(),
+                        _ if lhs == rhs => Expression::new_boolean(&true),
+                        _ => Self::new_simplified_binary(&lhs, operator, &rhs),
+                    },
+                }
+            }
+        }
+    }
+
+    /// The literal boolean of the expression, looking through parentheses.
+    fn as_boolean(&self) -> Option<bool> {
+        match self {
+            Expression::Literal(Value::Bool(value)) => Some(*value),
+            Expression::Parenthesized(inner) => inner.as_boolean(),
+            _ => None,
+        }
+    }
+
+    /// The literal clock of the expression, looking through parentheses.
+    fn as_clock(&self) -> Option<u32> {
+        match self {
+            Expression::Literal(Value::Clock(time)) => Some(*time),
+            Expression::Parenthesized(inner) => inner.as_clock(),
+            _ => None,
+        }
+    }
+
+    /// The outcome of comparing two clock constants, where logical operators
+    /// have none.
+    fn compare_clocks(lhs: u32, operator: &BinaryOperator, rhs: u32) -> Option<bool> {
+        match operator {
+            BinaryOperator::Equal => Some(lhs == rhs),
+            BinaryOperator::NotEqual => Some(lhs != rhs),
+            BinaryOperator::Less => Some(lhs < rhs),
+            BinaryOperator::LessEqual => Some(lhs <= rhs),
+            BinaryOperator::Greater => Some(lhs > rhs),
+            BinaryOperator::GreaterEqual => Some(lhs >= rhs),
+            _ => None,
+        }
+    }
+
+    /// The negation of a simplified expression without double negations.
+    fn negated(&self) -> Expression {
+        match self {
+            Expression::Literal(Value::Bool(value)) => Expression::new_boolean(&!value),
+            Expression::Unary(UnaryOperator::Negation, operand) => match operand.as_ref() {
+                Expression::Parenthesized(inner) => inner.as_ref().clone(),
+                operand => operand.clone(),
+            },
+            _ => Expression::new_logical_not(&self.parenthesized_if_binary()),
+        }
+    }
+
+    /// Combines simplified operands, parenthesizing binary operands unless they
+    /// chain the same associative operator.
+    fn new_simplified_binary(
+        lhs: &Expression,
+        operator: &BinaryOperator,
+        rhs: &Expression,
+    ) -> Expression {
+        let operand = |operand: &Expression| match operand {
+            Expression::Binary(_, nested, _)
+                if nested == operator
+                    && matches!(
+                        operator,
+                        BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr
+                    ) =>
+            {
+                operand.clone()
+            }
+            _ => operand.parenthesized_if_binary(),
+        };
+        Expression::new_binary_expression(&operand(lhs), operator, &operand(rhs))
+    }
+
     pub fn identifiers(&self) -> Vec<String> {
         let mut identifiers: Vec<String> = Vec::new();
 
-        let mut worklist: VecDeque<Expression> = VecDeque::new();
-        worklist.push_back(self.clone());
+        let mut worklist: VecDeque<&Expression> = VecDeque::new();
+        worklist.push_back(self);
 
-        let mut visit_value = |value: Value| {
+        let mut visit_value = |value: &Value| {
             if let Value::Identifier(ident) = value {
-                identifiers.push(ident.clone());
+                identifiers.push(ident.to_string());
             }
         };
 
-        while worklist.len() > 0 {
-            match worklist.pop_back().unwrap() {
+        while let Some(expression) = worklist.pop_back() {
+            match expression {
                 Expression::Literal(literal) => {
                     visit_value(literal);
                 }
-                Expression::Parenthesized(expr) => worklist.push_back(*expr),
+                Expression::Parenthesized(expr) => worklist.push_back(expr),
                 Expression::Binary(lhs, _, rhs) => {
-                    worklist.push_back(*lhs);
-                    worklist.push_back(*rhs);
+                    worklist.push_back(lhs);
+                    worklist.push_back(rhs);
                 }
-                Expression::Unary(_, expr) => worklist.push_back(*expr),
+                Expression::Unary(_, expr) => worklist.push_back(expr),
             }
         }
 
         identifiers
     }
+
+    /// The times of the clock literals in the expression, such as the bounds of
+    /// its clock constraints.
+    pub fn clock_constants(&self) -> Vec<u32> {
+        match self {
+            Expression::Literal(Value::Clock(time)) => vec![*time],
+            Expression::Literal(_) => Vec::new(),
+            Expression::Parenthesized(expr) | Expression::Unary(_, expr) => expr.clock_constants(),
+            Expression::Binary(lhs, _, rhs) => {
+                let mut constants = lhs.clock_constants();
+                constants.extend(rhs.clock_constants());
+                constants
+            }
+        }
+    }
+
+    /// True if the implication `self --> other` is a tautology, that is if other
+    /// holds under every assignment of booleans to the free variables of both
+    /// where self holds. Assignments under which either fails to evaluate to a
+    /// boolean count as counterexamples. Every assignment is tried, so None is
+    /// returned rather than deciding it for more than `MAX_IMPLICATION_VARIABLES`.
+    pub fn implies(&self, other: &Expression) -> Option<bool> {
+        let mut variables = self.identifiers();
+        variables.extend(other.identifiers());
+        variables.sort();
+        variables.dedup();
+        if variables.len() > MAX_IMPLICATION_VARIABLES {
+            return None;
+        }
+
+        let evaluate = |expression: &Expression, environment: &Environment| match Interpreter::new(
+            environment,
+        )
+        .eval_expression(expression)
+        {
+            Ok(Evaluation::Bool(value)) => Some(value),
+            _ => None,
+        };
+        let all = (0..1u64 << variables.len()).all(|assignment| {
+            let mut environment = Environment::new_empty();
+            for (index, variable) in variables.iter().enumerate() {
+                environment.insert(variable, &Value::Bool(assignment >> index & 1 == 1));
+            }
+            match evaluate(self, &environment) {
+                Some(false) => true,
+                Some(true) => evaluate(other, &environment) == Some(true),
+                None => false,
+            }
+        });
+        Some(all)
+    }
 }
 
+/// Prints the expression such that it parses back to the same expression, up
+/// to parentheses. Operands binding looser than their operator are enclosed in
+/// parentheses even if the expression has no parenthesized node for them.
 impl ToString for Expression {
     fn to_string(&self) -> String {
         match self {
             Expression::Literal(value) => value.to_string(),
             Expression::Parenthesized(expr) => "(".to_owned() + &expr.to_string() + ")",
             Expression::Binary(lhs, op, rhs) => {
-                lhs.to_string() + " " + &op.to_string() + " " + &rhs.to_string()
+                let right_associative = op.is_right_associative();
+                let lhs = lhs.operand_to_string(op.precedence(), right_associative);
+                let rhs = rhs.operand_to_string(op.precedence(), !right_associative);
+                lhs + " " + &op.to_string() + " " + &rhs
+            }
+            Expression::Unary(op, expr) => match expr.as_ref() {
+                Expression::Binary(..) => op.to_string() + "(" + &expr.to_string() + ")",
+                expr => op.to_string() + &expr.to_string(),
+            },
+        }
+    }
+}
+
+impl Expression {
+    /// Prints the operand of a binary operator with the precedence, enclosed in
+    /// parentheses if it binds looser, or equally tight on the side which the
+    /// operator does not associate to.
+    fn operand_to_string(&self, precedence: u8, enclose_equal: bool) -> String {
+        match self {
+            Expression::Binary(_, op, _)
+                if op.precedence() < precedence
+                    || (op.precedence() == precedence && enclose_equal) =>
+            {
+                "(".to_owned() + &self.to_string() + ")"
+            }
+            _ => self.to_string(),
+        }
+    }
+
+    /// The expression with every parenthesized node replaced by its contents,
+    /// such that expressions differing only in redundant parentheses are equal.
+    pub fn without_parentheses(&self) -> Expression {
+        match self {
+            Expression::Literal(_) => self.clone(),
+            Expression::Parenthesized(inner) => inner.without_parentheses(),
+            Expression::Binary(lhs, op, rhs) => Expression::new_binary_expression(
+                &lhs.without_parentheses(),
+                op,
+                &rhs.without_parentheses(),
+            ),
+            Expression::Unary(_, operand) => {
+                Expression::new_logical_not(&operand.without_parentheses())
             }
-            Expression::Unary(op, expr) => op.to_string() + &expr.to_string(),
         }
     }
 }
@@ -192,4 +687,136 @@ mod tests {
         let identifiers = node.identifiers();
         assert_eq!(identifiers, vec![String::from(ident)])
     }
+
+    #[test]
+    fn node_clone_shares_subexpressions() {
+        let mut expression = Expression::new_identifier("a");
+        for _ in 0..100 {
+            expression = Expression::new_logical_and(&expression, &Expression::new_boolean(&true));
+        }
+
+        let clone = expression.clone();
+
+        match (&expression, &clone) {
+            (Expression::Binary(lhs, _, rhs), Expression::Binary(clone_lhs, _, clone_rhs)) => {
+                assert!(lhs.ptr_eq(clone_lhs));
+                assert!(rhs.ptr_eq(clone_rhs));
+                assert_eq!(lhs.strong_count(), 2);
+            }
+            _ => panic!("Expected a binary expression"),
+        }
+        assert_eq!(expression, clone);
+    }
+
+    #[test]
+    fn expression_implies() {
+        let a = Expression::new_identifier("a");
+        let b = Expression::new_identifier("b");
+        let a_and_b = Expression::new_logical_and(&a, &b);
+
+        assert_eq!(a_and_b.implies(&a), Some(true));
+        assert_eq!(a.implies(&a_and_b), Some(false));
+        assert_eq!(a.implies(&Expression::new_logical_or(&a, &b)), Some(true));
+        assert_eq!(Expression::new_boolean(&false).implies(&b), Some(true));
+        assert_eq!(Expression::new_boolean(&true).implies(&b), Some(false));
+    }
+
+    #[test]
+    fn expression_implies_gives_up_on_many_variables() {
+        let operands: Vec<Expression> = (0..64)
+            .map(|index| Expression::new_identifier(&format!("v{}", index)))
+            .collect();
+        let conjunction = Expression::new_conjunction(&operands);
+
+        assert_eq!(conjunction.implies(&operands[0]), None);
+    }
+
+    #[test]
+    fn expression_new_conjunction_simplifies() {
+        let a = Expression::new_identifier("a");
+        let b_or_c = Expression::new_logical_or(
+            &Expression::new_identifier("b"),
+            &Expression::new_identifier("c"),
+        );
+        let t = Expression::new_boolean(&true);
+        let f = Expression::new_boolean(&false);
+
+        assert_eq!(Expression::new_conjunction(&[t.clone(), t.clone()]), t);
+        assert_eq!(Expression::new_conjunction(&[a.clone(), f.clone()]), f);
+        assert_eq!(
+            Expression::new_conjunction(&[a.clone(), t.clone(), b_or_c.clone(), a.clone()])
+                .to_string(),
+            "a && (b || c)"
+        );
+        // Operands are simplified too, not only literal ones
+        assert_eq!(
+            Expression::new_conjunction(&[
+                Expression::new_logical_or(&b_or_c, &t),
+                Expression::new_logical_not(&Expression::new_logical_not(&a)),
+            ]),
+            a
+        );
+    }
+
+    #[test]
+    fn expression_substitute() {
+        let expression = Expression::new_logical_and(
+            &Expression::new_identifier("x"),
+            &Expression::new_logical_not(&Expression::new_identifier("x")),
+        );
+        let replacement = Expression::new_logical_or(
+            &Expression::new_identifier("y"),
+            &Expression::new_boolean(&false),
+        );
+
+        let substituted = expression.substitute("x", &replacement);
+
+        assert_eq!(substituted.to_string(), "(y || false) && !(y || false)");
+        assert_eq!(expression.substitute("z", &replacement), expression);
+    }
+
+    #[test]
+    fn expression_canonicalize_commutative_operands() {
+        let a = Expression::new_identifier("a");
+        let b = Expression::new_identifier("b");
+        let c = Expression::new_identifier("c");
+
+        let a_and_b = Expression::new_logical_and(&a, &b).canonicalize();
+        let b_and_a = Expression::new_logical_and(&b, &a).canonicalize();
+        let nested = Expression::new_logical_and(
+            &c,
+            &Expression::new_parenthesized(&Expression::new_logical_and(&b, &a)),
+        )
+        .canonicalize();
+        let equality = Expression::new_logical_equal(
+            &Expression::new_logical_not(&b),
+            &Expression::new_logical_or(&c, &a),
+        )
+        .canonicalize();
+
+        assert_eq!(a_and_b, b_and_a);
+        assert_eq!(a_and_b.to_string(), "a && b");
+        assert_eq!(nested.to_string(), "a && b && c");
+        assert_eq!(equality.to_string(), "(a || c) == !b");
+        assert_eq!(equality, equality.canonicalize());
+    }
+
+    #[test]
+    fn expression_simplify_folds_literals() {
+        let simplify = |input: &str| {
+            crate::language::parser::parse_expression(input)
+                .unwrap()
+                .simplify()
+                .to_string()
+        };
+
+        assert_eq!(simplify("(a && true) || false"), "a");
+        assert_eq!(simplify("!!(a || b) && (c == false)"), "(a || b) && !c");
+        assert_eq!(simplify("false --> a"), "true");
+        assert_eq!(simplify("a --> false"), "!a");
+        assert_eq!(simplify("(a != b) != true"), "!(a != b)");
+        assert_eq!(simplify("a && (b && c) && a == a"), "a && (b && c)");
+        assert_eq!(simplify("(3 < 5) && a"), "a");
+        assert_eq!(simplify("2 == 4 || a"), "a");
+    }
 }
@@ -0,0 +1,146 @@
+use std::{
+    borrow::Borrow, cell::RefCell, collections::HashSet, fmt::Display, hash::Hash, ops::Deref,
+    sync::Arc,
+};
+
+/// An interned identifier. Identifiers with the same text created on the same
+/// thread share the same storage, such that cloning is a reference count bump
+/// and equality is usually decided by a pointer comparison. Identifiers from
+/// different threads compare equal by their text.
+#[derive(Debug, Clone, Eq)]
+pub struct Identifier(Arc<str>);
+
+thread_local! {
+    // One interner per thread, such that threads never wait on each other
+    static INTERNER: RefCell<HashSet<Arc<str>>> = RefCell::new(HashSet::new());
+}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Identifier {
+    pub fn new(name: &str) -> Self {
+        INTERNER.with(|interner| {
+            let mut interned = interner.borrow_mut();
+            if let Some(existing) = interned.get(name) {
+                return Identifier(existing.clone());
+            }
+            let symbol: Arc<str> = Arc::from(name);
+            interned.insert(symbol.clone());
+            Identifier(symbol)
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn shares_storage_with(&self, other: &Identifier) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl PartialEq for Identifier {
+    fn eq(&self, other: &Self) -> bool {
+        self.shares_storage_with(other) || self.0 == other.0
+    }
+}
+
+impl PartialEq<str> for Identifier {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Identifier {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Identifier {
+    fn eq(&self, other: &String) -> bool {
+        &*self.0 == other.as_str()
+    }
+}
+
+impl Hash for Identifier {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        // Must agree with the hash of `str` for the `Borrow<str>` lookups
+        self.0.hash(state)
+    }
+}
+
+impl Deref for Identifier {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Identifier {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Identifier {
+    fn from(name: &str) -> Self {
+        Identifier::new(name)
+    }
+}
+
+impl From<Identifier> for String {
+    fn from(identifier: Identifier) -> Self {
+        String::from(identifier.as_str())
+    }
+}
+
+impl Display for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Identifier;
+
+    #[test]
+    fn identifier_same_text_shares_storage() {
+        let a = Identifier::new("ident");
+        let b = Identifier::new(&String::from("ident"));
+        assert!(a.shares_storage_with(&b));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn identifier_different_text_is_not_equal() {
+        let a = Identifier::new("a");
+        let b = Identifier::new("b");
+        assert!(!a.shares_storage_with(&b));
+        assert_ne!(a, b);
+        assert_eq!(a, "a");
+    }
+
+    #[test]
+    fn identifier_from_other_thread_is_equal() {
+        let local = Identifier::new("shared");
+        let remote = std::thread::spawn(|| Identifier::new("shared"))
+            .join()
+            .unwrap();
+
+        assert!(!local.shares_storage_with(&remote));
+        assert_eq!(local, remote);
+    }
+}
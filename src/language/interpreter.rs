@@ -5,103 +5,218 @@ use super::{
     value::Value,
 };
 
-#[derive(Default, Clone, Eq, PartialEq)]
+#[derive(Default, Clone)]
 pub struct Interpreter {
     environment: Environment,
+    fuel: Option<usize>,
+    consumed: usize,
+    stats: EvaluationStats,
+}
+
+/// Interpreters are equal if their environments are, regardless of the fuel
+/// and the work they have performed so far.
+impl PartialEq for Interpreter {
+    fn eq(&self, other: &Self) -> bool {
+        self.environment == other.environment
+    }
+}
+
+impl Eq for Interpreter {}
+
+/// Counters of the work performed by an interpreter across all evaluations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvaluationStats {
+    pub nodes_evaluated: usize,
+    pub lookups: usize,
+}
+
+/// A step of the post-order evaluation of an expression: either evaluating a
+/// subexpression or applying an operator to the evaluated operands.
+enum Task<'a> {
+    Evaluate(&'a Expression),
+    Binary(&'a BinaryOperator),
+    Unary(&'a UnaryOperator),
+}
+
+/// Applies the operator, where clocks are compared and everything else is
+/// logical.
+fn eval_binary(
+    op: &BinaryOperator,
+    lhs: &Evaluation,
+    rhs: &Evaluation,
+) -> Result<Evaluation, Error> {
+    let evaluation = match op {
+        BinaryOperator::Equal if lhs.as_clock().is_some() => lhs == rhs,
+        BinaryOperator::NotEqual if lhs.as_clock().is_some() => lhs != rhs,
+        op if op.is_comparison() => {
+            let (lhs, rhs) = (clock(lhs)?, clock(rhs)?);
+            match op {
+                BinaryOperator::Less => lhs < rhs,
+                BinaryOperator::LessEqual => lhs <= rhs,
+                BinaryOperator::Greater => lhs > rhs,
+                _ => lhs >= rhs,
+            }
+        }
+        op => {
+            let (lhs, rhs) = (bool::try_from(lhs)?, bool::try_from(rhs)?);
+            match op {
+                BinaryOperator::LogicalAnd => lhs && rhs,
+                BinaryOperator::LogicalOr => lhs || rhs,
+                BinaryOperator::Equal | BinaryOperator::BiImplication => lhs == rhs,
+                BinaryOperator::NotEqual => lhs != rhs,
+                _ => !lhs || rhs,
+            }
+        }
+    };
+    Ok(Evaluation::Bool(evaluation))
+}
+
+/// The time of a clock evaluation, where other evaluations are an error.
+fn clock(evaluation: &Evaluation) -> Result<u32, Error> {
+    evaluation.as_clock().ok_or_else(|| Error::RuntimeError {
+        message: format!("Evaluation {} is not a clock", evaluation),
+    })
 }
 
 impl Interpreter {
     pub fn new(environment: &Environment) -> Self {
         Self {
             environment: environment.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// An interpreter where every evaluation of an expression or statement may
+    /// take at most `max_steps` steps, where each expression node and each
+    /// statement is a step, before failing with `Error::FuelExhausted`.
+    pub fn with_fuel(environment: &Environment, max_steps: usize) -> Self {
+        Self {
+            fuel: Some(max_steps),
+            ..Self::new(environment)
         }
     }
 
+    pub fn stats(&self) -> EvaluationStats {
+        self.stats
+    }
+
     pub fn empty() -> Self {
         Interpreter::default()
     }
 
     pub fn eval_expression(&mut self, expression: &Expression) -> Result<Evaluation, Error> {
-        let mut stack: VecDeque<Value> = VecDeque::new();
-        let mut worklist: VecDeque<&Expression> = VecDeque::new();
-        worklist.push_back(expression);
-
-        while !worklist.is_empty() {
-            match worklist.pop_front().unwrap() {
-                Expression::Literal(literal) => {
-                    if let Value::Identifier(ident) = literal {
-                        if let Some(value) = self.environment.get_value(ident) {
-                            stack.push_back(value.clone());
-                        } else {
+        self.consumed = 0;
+        self.eval_expression_in(expression, None)
+    }
+
+    /// Evaluates the expression where identifiers are looked up in the overlay
+    /// before the environment of the interpreter, such that the overlay
+    /// shadows it. Neither environment is changed.
+    pub fn eval_with_overlay(
+        &mut self,
+        expression: &Expression,
+        overlay: &Environment,
+    ) -> Result<Evaluation, Error> {
+        self.consumed = 0;
+        self.eval_expression_in(expression, Some(overlay))
+    }
+
+    /// Takes a step of the current evaluation, failing once the fuel is spent.
+    fn consume(&mut self) -> Result<(), Error> {
+        self.consumed += 1;
+        match self.fuel {
+            Some(fuel) if self.consumed > fuel => Err(Error::FuelExhausted {
+                consumed: self.consumed - 1,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Evaluates the expression in post-order with an explicit stack of tasks,
+    /// such that deeply nested expressions do not exhaust the call stack.
+    fn eval_expression_in(
+        &mut self,
+        expression: &Expression,
+        overlay: Option<&Environment>,
+    ) -> Result<Evaluation, Error> {
+        let mut tasks: Vec<Task> = vec![Task::Evaluate(expression)];
+        let mut operands: Vec<Evaluation> = Vec::new();
+
+        while let Some(task) = tasks.pop() {
+            if let Task::Evaluate(_) = task {
+                self.consume()?;
+                self.stats.nodes_evaluated += 1;
+            }
+            match task {
+                Task::Evaluate(Expression::Literal(Value::Identifier(ident))) => {
+                    self.stats.lookups += 1;
+                    let value = overlay
+                        .and_then(|overlay| overlay.get_value(ident))
+                        .or_else(|| self.environment.get_value(ident));
+                    match value {
+                        Some(value) => operands.push(Evaluation::try_from(value)?),
+                        None => {
                             return Err(Error::RuntimeError {
                                 message: String::from("Unknown identifier"),
-                            });
+                            })
                         }
-                    } else {
-                        stack.push_back(literal.clone())
                     }
                 }
-                Expression::Parenthesized(expr) => worklist.push_back(expr),
-                Expression::Binary(lhs, op, rhs) => {
-                    let lhs_evaluation = self.eval_expression(&lhs).ok().unwrap();
-                    let rhs_evaluation = self.eval_expression(&rhs).ok().unwrap();
-                    let evaluation: Value = match op {
-                        BinaryOperator::LogicalAnd => {
-                            let lhs_bool: bool = lhs_evaluation.into();
-                            let rhs_bool: bool = rhs_evaluation.into();
-                            Value::Bool(lhs_bool && rhs_bool)
-                        }
-                        BinaryOperator::LogicalOr => {
-                            let lhs_bool: bool = lhs_evaluation.into();
-                            let rhs_bool: bool = rhs_evaluation.into();
-                            Value::Bool(lhs_bool || rhs_bool)
-                        }
-                        BinaryOperator::Equal | BinaryOperator::BiImplication => {
-                            let lhs_bool: bool = lhs_evaluation.into();
-                            let rhs_bool: bool = rhs_evaluation.into();
-                            Value::Bool(lhs_bool == rhs_bool)
-                        }
-                        BinaryOperator::NotEqual => {
-                            let lhs_bool: bool = lhs_evaluation.into();
-                            let rhs_bool: bool = rhs_evaluation.into();
-                            Value::Bool(lhs_bool != rhs_bool)
-                        }
-                        BinaryOperator::Implication => {
-                            let lhs_bool: bool = lhs_evaluation.into();
-                            let rhs_bool: bool = rhs_evaluation.into();
-                            Value::Bool(!lhs_bool || rhs_bool)
-                        }
+                Task::Evaluate(Expression::Literal(literal)) => {
+                    operands.push(Evaluation::try_from(literal)?)
+                }
+                Task::Evaluate(Expression::Parenthesized(expr)) => tasks.push(Task::Evaluate(expr)),
+                Task::Evaluate(Expression::Binary(lhs, op, rhs)) => {
+                    // Pushed in reverse such that the left operand is evaluated first
+                    tasks.push(Task::Binary(op));
+                    tasks.push(Task::Evaluate(rhs));
+                    tasks.push(Task::Evaluate(lhs));
+                }
+                Task::Evaluate(Expression::Unary(op, expr)) => {
+                    tasks.push(Task::Unary(op));
+                    tasks.push(Task::Evaluate(expr));
+                }
+                Task::Binary(op) => {
+                    let (Some(rhs), Some(lhs)) = (operands.pop(), operands.pop()) else {
+                        return Err(Error::RuntimeError {
+                            message: String::from("Missing operands of a binary expression"),
+                        });
                     };
-                    stack.push_back(evaluation);
+                    operands.push(eval_binary(op, &lhs, &rhs)?);
                 }
-                Expression::Unary(op, expr) => {
-                    let expr_evaluation = self.eval_expression(&expr).ok().unwrap();
-                    let evaluation: Value = match op {
-                        UnaryOperator::Negation => {
-                            let expr_bool: bool = expr_evaluation.into();
-                            Value::Bool(expr_bool)
-                        }
+                Task::Unary(op) => {
+                    let Some(operand) = operands.pop() else {
+                        return Err(Error::RuntimeError {
+                            message: String::from("Missing operand of a unary expression"),
+                        });
                     };
-                    stack.push_back(evaluation);
+                    let operand = bool::try_from(&operand)?;
+                    operands.push(match op {
+                        UnaryOperator::Negation => Evaluation::Bool(!operand),
+                    });
                 }
             }
         }
 
-        if stack.len() > 1 {
+        if operands.len() > 1 {
             return Err(Error::RuntimeError {
                 message: String::from("More than one element on the stack"),
             });
         }
+        Ok(operands.pop().unwrap_or(Evaluation::Void))
+    }
 
-        match stack.pop_front() {
-            Some(value) => match value {
-                Value::Bool(value) => Ok(Evaluation::Bool(value)),
-                Value::Identifier(_) => Err(Error::RuntimeError {
-                    message: String::from("An identifier cannot be an evaluation"),
-                }),
-            },
-            None => Ok(Evaluation::Void),
+    /// Partially evaluates the expression by replacing the identifiers bound in
+    /// the environment with their values and simplifying the result, such that
+    /// only the unbound identifiers remain.
+    pub fn partial_eval(&self, expression: &Expression) -> Expression {
+        let mut result = expression.clone();
+        for identifier in expression.identifiers() {
+            if let Some(value) = self.environment.get_value(&identifier) {
+                result = result.substitute(&identifier, &Expression::new_literal(value));
+            }
         }
+        result.simplify()
     }
 
     pub fn eval_expression_identifier(&mut self, expression: &Expression) -> Result<String, Error> {
@@ -110,7 +225,10 @@ impl Interpreter {
                 Value::Bool(_) => Err(Error::RuntimeError {
                     message: String::from("Boolean is not an identifier"),
                 }),
-                Value::Identifier(ident) => Ok(String::from(ident)),
+                Value::Clock(_) => Err(Error::RuntimeError {
+                    message: String::from("Clock value is not an identifier"),
+                }),
+                Value::Identifier(ident) => Ok(ident.to_string()),
             },
             Expression::Parenthesized(expr) => self.eval_expression_identifier(expr),
             _ => Err(Error::RuntimeError {
@@ -122,17 +240,24 @@ impl Interpreter {
     pub fn eval_statement(&mut self, statement: &Statement) -> Option<Error> {
         let mut worklist: VecDeque<&Statement> = VecDeque::new();
         worklist.push_back(statement);
+        self.consumed = 0;
 
-        while !worklist.is_empty() {
-            match worklist.pop_front().unwrap() {
+        while let Some(statement) = worklist.pop_front() {
+            if let Err(error) = self.consume() {
+                return Some(error);
+            }
+            match statement {
                 Statement::Assigment { identifier, value } => {
                     let ident = match self.eval_expression_identifier(identifier) {
                         Ok(ident_value) => ident_value,
                         Err(error) => return Some(error),
                     };
 
-                    let val: Value = match self.eval_expression(value) {
-                        Ok(evaulation) => evaulation.into(),
+                    let val: Value = match self
+                        .eval_expression_in(value, None)
+                        .and_then(Value::try_from)
+                    {
+                        Ok(val) => val,
                         Err(error) => return Some(error),
                     };
 
@@ -142,6 +267,40 @@ impl Interpreter {
                         });
                     }
                 }
+                Statement::ClockReset { clock } => match self.environment.get_value(clock) {
+                    Some(Value::Clock(_)) => {
+                        self.environment.set(clock, &Value::new_clock(0));
+                    }
+                    _ => {
+                        return Some(Error::RuntimeError {
+                            message: format!("{} is not a clock", clock),
+                        })
+                    }
+                },
+                Statement::Sequence(statements) => {
+                    // Pushed to the front in reverse to execute before any remaining work
+                    for statement in statements.iter().rev() {
+                        worklist.push_front(statement);
+                    }
+                }
+                Statement::If {
+                    condition,
+                    then,
+                    otherwise,
+                } => {
+                    let taken = match self
+                        .eval_expression_in(condition, None)
+                        .and_then(bool::try_from)
+                    {
+                        Ok(taken) => taken,
+                        Err(error) => return Some(error),
+                    };
+                    if taken {
+                        worklist.push_front(then);
+                    } else if let Some(otherwise) = otherwise {
+                        worklist.push_front(otherwise);
+                    }
+                }
             }
         }
 
@@ -157,8 +316,10 @@ impl Interpreter {
 mod tests {
     use crate::language::{
         environment::Environment,
+        error::Error,
         evaluation::Evaluation,
-        expression::{BinaryOperator, Expression},
+        expression::{BinaryOperator, Expression, Operand, UnaryOperator},
+        parser::parse_expression,
         statement::Statement,
         value::Value,
     };
@@ -391,7 +552,7 @@ mod tests {
         let mut environment = Environment::new_empty();
         environment.insert("a", &Value::Bool(true));
         let mut interpreter = Interpreter::new(&environment);
-        let identifier = Expression::Literal(Value::Identifier(String::from("a")));
+        let identifier = Expression::Literal(Value::new_identifier("a"));
 
         let result = interpreter.eval_expression(&identifier).unwrap();
 
@@ -402,13 +563,51 @@ mod tests {
     fn interpreter_eval_unknown_identifier_panics() {
         let environment = Environment::new_empty();
         let mut interpreter = Interpreter::new(&environment);
-        let literal = Expression::Literal(Value::Identifier(String::from("unknown identifier")));
+        let literal = Expression::Literal(Value::new_identifier("unknown identifier"));
 
         let result = interpreter.eval_expression(&literal);
 
         assert!(result.is_err());
     }
 
+    #[test]
+    fn interpreter_eval_identifier_bound_to_identifier_is_error() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_identifier("b"));
+        let mut interpreter = Interpreter::new(&environment);
+        let identifier = Expression::new_identifier("a");
+
+        let result = interpreter.eval_expression(&identifier);
+
+        assert!(matches!(result, Err(Error::RuntimeError { .. })));
+    }
+
+    #[test]
+    fn interpreter_eval_negation() {
+        let mut interpreter = Interpreter::default();
+        let negation = Expression::Unary(
+            UnaryOperator::Negation,
+            Operand::new(Expression::new_boolean(&true)),
+        );
+
+        let result = interpreter.eval_expression(&negation).unwrap();
+
+        assert_eq!(result, Evaluation::new_false());
+    }
+
+    #[test]
+    fn interpreter_eval_binary_unknown_identifier_is_err() {
+        let mut interpreter = Interpreter::default();
+        let expression = Expression::new_logical_and(
+            &Expression::new_boolean(&true),
+            &Expression::new_identifier("unknown"),
+        );
+
+        let result = interpreter.eval_expression(&expression);
+
+        assert!(result.is_err());
+    }
+
     #[test]
     fn interpreter_eval_correct_assignment_returns_none() {
         let mut environment = Environment::new_empty();
@@ -421,6 +620,92 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn interpreter_eval_sequence_observes_earlier_assignments() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::Bool(false));
+        environment.insert("b", &Value::Bool(false));
+        let mut interpreter = Interpreter::new(&environment);
+        let sequence = Statement::new_sequence(&[
+            Statement::new_simple_assignment("a", &Value::new_true()),
+            Statement::new_sequence(&[Statement::new_simple_assignment(
+                "b",
+                &Value::new_identifier("a"),
+            )]),
+            Statement::new_simple_assignment("a", &Value::new_false()),
+        ]);
+
+        let result = interpreter.eval_statement(&sequence);
+
+        assert_eq!(result, None);
+        let environment = interpreter.get_environment();
+        assert_eq!(environment.get_value("a"), Some(&Value::new_false()));
+        assert_eq!(environment.get_value("b"), Some(&Value::new_true()));
+    }
+
+    fn new_if_environment(ready: bool) -> Environment {
+        let mut environment = Environment::new_empty();
+        environment.insert("ready", &Value::Bool(ready));
+        environment.insert("a", &Value::Bool(false));
+        environment
+    }
+
+    #[test]
+    fn interpreter_eval_if_taken() {
+        let mut interpreter = Interpreter::new(&new_if_environment(true));
+        let statement = Statement::new_if(
+            &Expression::new_identifier("ready"),
+            &Statement::new_simple_assignment("a", &Value::new_true()),
+            Some(&Statement::new_simple_assignment(
+                "ready",
+                &Value::new_false(),
+            )),
+        );
+
+        let result = interpreter.eval_statement(&statement);
+
+        assert_eq!(result, None);
+        let environment = interpreter.get_environment();
+        assert_eq!(environment.get_value("a"), Some(&Value::new_true()));
+        assert_eq!(environment.get_value("ready"), Some(&Value::new_true()));
+    }
+
+    #[test]
+    fn interpreter_eval_if_not_taken() {
+        let mut interpreter = Interpreter::new(&new_if_environment(false));
+        let statement = Statement::new_if(
+            &Expression::new_identifier("ready"),
+            &Statement::new_simple_assignment("a", &Value::new_true()),
+            Some(&Statement::new_simple_assignment(
+                "ready",
+                &Value::new_true(),
+            )),
+        );
+
+        let result = interpreter.eval_statement(&statement);
+
+        assert_eq!(result, None);
+        let environment = interpreter.get_environment();
+        assert_eq!(environment.get_value("a"), Some(&Value::new_false()));
+        assert_eq!(environment.get_value("ready"), Some(&Value::new_true()));
+    }
+
+    #[test]
+    fn interpreter_eval_if_without_else_not_taken() {
+        let environment = new_if_environment(false);
+        let mut interpreter = Interpreter::new(&environment);
+        let statement = Statement::new_if(
+            &Expression::new_identifier("ready"),
+            &Statement::new_simple_assignment("a", &Value::new_true()),
+            None,
+        );
+
+        let result = interpreter.eval_statement(&statement);
+
+        assert_eq!(result, None);
+        assert_eq!(interpreter.get_environment(), environment);
+    }
+
     #[test]
     fn interpreter_eval_assignment_unknown_identifier() {
         let mut environment = Environment::new_empty();
@@ -447,4 +732,256 @@ mod tests {
 
         assert_ne!(result, None);
     }
+
+    #[test]
+    fn interpreter_eval_clock_constraint() {
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_clock(5));
+        let mut interpreter = Interpreter::new(&environment);
+        let inputs = [
+            (BinaryOperator::Less, false),
+            (BinaryOperator::LessEqual, true),
+            (BinaryOperator::Greater, false),
+            (BinaryOperator::GreaterEqual, true),
+            (BinaryOperator::Equal, true),
+            (BinaryOperator::NotEqual, false),
+        ];
+
+        for (op, expected) in inputs {
+            let constraint = Expression::new_clock_constraint("x", &op, 5);
+
+            let result = interpreter.eval_expression(&constraint);
+
+            assert_eq!(result, Ok(Evaluation::Bool(expected)), "x {:?} 5", op);
+        }
+    }
+
+    #[test]
+    fn interpreter_eval_clock_reset() {
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_clock(7));
+        let mut interpreter = Interpreter::new(&environment);
+        let reset = Statement::new_simple_assignment("x", &Value::new_clock(0));
+
+        let result = interpreter.eval_statement(&reset);
+
+        assert_eq!(result, None);
+        let environment = interpreter.get_environment();
+        assert_eq!(environment.get_value("x"), Some(&Value::new_clock(0)));
+    }
+
+    #[test]
+    fn interpreter_partial_eval_keeps_unbound_identifiers() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_true());
+        let interpreter = Interpreter::new(&environment);
+        let conjunction = Expression::new_logical_and(
+            &Expression::new_identifier("a"),
+            &Expression::new_identifier("b"),
+        );
+
+        let result = interpreter.partial_eval(&conjunction);
+
+        assert_eq!(result, Expression::new_identifier("b"));
+    }
+
+    #[test]
+    fn interpreter_partial_eval_folds_bound_clocks() {
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_clock(3));
+        environment.insert("a", &Value::new_false());
+        let interpreter = Interpreter::new(&environment);
+        let below = Expression::new_logical_or(
+            &Expression::new_clock_constraint("x", &BinaryOperator::Less, 2),
+            &Expression::new_identifier("b"),
+        );
+        let disabled = Expression::new_logical_and(
+            &Expression::new_identifier("a"),
+            &Expression::new_identifier("b"),
+        );
+
+        assert_eq!(
+            interpreter.partial_eval(&below),
+            Expression::new_identifier("b")
+        );
+        assert_eq!(
+            interpreter.partial_eval(&disabled),
+            Expression::new_boolean(&false)
+        );
+    }
+
+    #[test]
+    fn interpreter_fuel_limits_evaluation_steps() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_true());
+        environment.insert("b", &Value::new_false());
+        environment.insert("c", &Value::new_false());
+        // Seven nodes: the two binaries, the parentheses, the negation and three identifiers
+        let expression = parse_expression("a && (b || !c)").unwrap();
+        let size = 7;
+        let mut starved = Interpreter::with_fuel(&environment, size - 1);
+        let mut fueled = Interpreter::with_fuel(&environment, size);
+
+        let starved_result = starved.eval_expression(&expression);
+        let fueled_result = fueled.eval_expression(&expression);
+
+        assert_eq!(
+            starved_result,
+            Err(Error::FuelExhausted { consumed: size - 1 })
+        );
+        assert_eq!(fueled_result, Ok(Evaluation::Bool(true)));
+        // The fuel is per evaluation, such that the interpreter can be reused
+        assert_eq!(
+            fueled.eval_expression(&expression),
+            Ok(Evaluation::Bool(true))
+        );
+    }
+
+    #[test]
+    fn interpreter_fuel_limits_statements() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_false());
+        let assignment = Statement::new_simple_assignment("a", &Value::new_true());
+        let sequence = Statement::new_sequence(&[assignment.clone(), assignment]);
+        // The sequence, both assignments and their two literals
+        let mut starved = Interpreter::with_fuel(&environment, 4);
+        let mut fueled = Interpreter::with_fuel(&environment, 5);
+
+        assert_eq!(
+            starved.eval_statement(&sequence),
+            Some(Error::FuelExhausted { consumed: 4 })
+        );
+        assert_eq!(fueled.eval_statement(&sequence), None);
+    }
+
+    #[test]
+    fn interpreter_stats_count_nodes_and_lookups() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_true());
+        environment.insert("b", &Value::new_false());
+        let mut interpreter = Interpreter::new(&environment);
+        let expression = Expression::new_logical_or(
+            &Expression::new_identifier("a"),
+            &Expression::new_logical_not(&Expression::new_identifier("b")),
+        );
+
+        interpreter.eval_expression(&expression).unwrap();
+        interpreter.eval_expression(&expression).unwrap();
+
+        let stats = interpreter.stats();
+        assert_eq!(stats.nodes_evaluated, 8);
+        assert_eq!(stats.lookups, 4);
+    }
+
+    #[test]
+    fn interpreter_eval_with_overlay_shadows_environment() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_false());
+        environment.insert("b", &Value::new_true());
+        let mut overlay = Environment::new_empty();
+        overlay.insert("a", &Value::new_true());
+        overlay.insert("c", &Value::new_true());
+        let mut interpreter = Interpreter::new(&environment);
+        let shadowed = Expression::new_logical_and(
+            &Expression::new_identifier("a"),
+            &Expression::new_identifier("b"),
+        );
+        let only_overlay = Expression::new_identifier("c");
+
+        assert_eq!(
+            interpreter.eval_with_overlay(&shadowed, &overlay),
+            Ok(Evaluation::Bool(true))
+        );
+        assert_eq!(
+            interpreter.eval_with_overlay(&only_overlay, &overlay),
+            Ok(Evaluation::Bool(true))
+        );
+        assert_eq!(
+            interpreter.eval_expression(&shadowed),
+            Ok(Evaluation::Bool(false))
+        );
+        assert!(interpreter.eval_expression(&only_overlay).is_err());
+        assert_eq!(interpreter.get_environment(), environment);
+    }
+
+    #[test]
+    fn interpreter_eval_deeply_parenthesized() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_true());
+        let mut interpreter = Interpreter::new(&environment);
+        let mut expression = Expression::new_identifier("a");
+        for _ in 0..100 {
+            expression = Expression::new_parenthesized(&expression);
+        }
+
+        let result = interpreter.eval_expression(&expression);
+
+        assert_eq!(result, Ok(Evaluation::Bool(true)));
+    }
+
+    #[test]
+    fn interpreter_eval_parenthesized_binaries() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_true());
+        environment.insert("b", &Value::new_false());
+        let mut interpreter = Interpreter::new(&environment);
+        let a = Expression::new_identifier("a");
+        let b = Expression::new_identifier("b");
+        // ((a || b)) && !((a && b))
+        let expression = Expression::new_logical_and(
+            &Expression::new_parenthesized(&Expression::new_parenthesized(
+                &Expression::new_logical_or(&a, &b),
+            )),
+            &Expression::new_logical_not(&Expression::new_parenthesized(
+                &Expression::new_parenthesized(&Expression::new_logical_and(&a, &b)),
+            )),
+        );
+        let nested_operands = Expression::new_logical_implication(
+            &Expression::new_parenthesized(&Expression::new_logical_and(
+                &Expression::new_parenthesized(&a),
+                &Expression::new_parenthesized(&b),
+            )),
+            &Expression::new_parenthesized(&b),
+        );
+
+        assert_eq!(
+            interpreter.eval_expression(&expression),
+            Ok(Evaluation::Bool(true))
+        );
+        assert_eq!(
+            interpreter.eval_expression(&nested_operands),
+            Ok(Evaluation::Bool(true))
+        );
+    }
+
+    #[test]
+    fn interpreter_eval_deep_right_leaning_chain() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_true());
+        let mut interpreter = Interpreter::new(&environment);
+        let a = Expression::new_identifier("a");
+        let mut chain = a.clone();
+        for _ in 0..10_000 {
+            chain = Expression::new_logical_and(&a, &Expression::new_parenthesized(&chain));
+        }
+
+        let result = interpreter.eval_expression(&chain);
+        drop(chain);
+
+        assert_eq!(result, Ok(Evaluation::Bool(true)));
+    }
+
+    #[test]
+    fn interpreter_equality_ignores_work_performed() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_true());
+        let idle = Interpreter::new(&environment);
+        let mut busy = Interpreter::with_fuel(&environment, 10);
+
+        busy.eval_expression(&Expression::new_identifier("a"))
+            .unwrap();
+
+        assert_ne!(busy.stats(), idle.stats());
+        assert!(busy == idle);
+    }
 }
@@ -1,8 +1,9 @@
 use std::fmt::Display;
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Hash)]
 pub enum LangType {
     Logical,
+    Clock,
     Void,
 }
 
@@ -10,6 +11,7 @@ impl Display for LangType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             LangType::Logical => f.write_str("logical"),
+            LangType::Clock => f.write_str("clock"),
             LangType::Void => f.write_str("void"),
         }
     }
@@ -2,8 +2,10 @@ pub mod environment;
 pub mod error;
 pub mod evaluation;
 pub mod expression;
+pub mod identifier;
 pub mod interpreter;
 pub mod lang_type;
+pub mod parser;
 pub mod statement;
 pub mod type_checker;
 pub mod value;
@@ -0,0 +1,558 @@
+use super::{
+    error::Error,
+    expression::{BinaryOperator, Expression},
+    statement::Statement,
+    value::Value,
+};
+
+/// Words which cannot be used as identifiers as they delimit expressions and
+/// statements.
+const KEYWORDS: [&str; 5] = ["if", "then", "else", "when", "do"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token {
+    Identifier(String),
+    Bool(bool),
+    Number(u32),
+    And,
+    Or,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Implication,
+    BiImplication,
+    Bang,
+    Assign,
+    Semicolon,
+    Colon,
+    Question,
+    Minus,
+    Arrow,
+    LeftParenthesis,
+    RightParenthesis,
+    LeftBrace,
+    RightBrace,
+}
+
+/// Splits the input into tokens along with their byte offsets.
+pub(crate) fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, Error> {
+    let symbols = [
+        ("<-->", Token::BiImplication),
+        ("<=", Token::LessEqual),
+        (">=", Token::GreaterEqual),
+        ("<", Token::Less),
+        (">", Token::Greater),
+        ("-->", Token::Implication),
+        ("->", Token::Arrow),
+        ("&&", Token::And),
+        ("||", Token::Or),
+        ("==", Token::Equal),
+        ("!=", Token::NotEqual),
+        ("!", Token::Bang),
+        ("=", Token::Assign),
+        (";", Token::Semicolon),
+        (":", Token::Colon),
+        ("?", Token::Question),
+        ("-", Token::Minus),
+        ("(", Token::LeftParenthesis),
+        (")", Token::RightParenthesis),
+        ("{", Token::LeftBrace),
+        ("}", Token::RightBrace),
+    ];
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    while let Some(c) = input[position..].chars().next() {
+        if c.is_whitespace() {
+            position += c.len_utf8();
+        } else if is_identifier(c) {
+            let length = input[position..]
+                .find(|c: char| !is_identifier(c))
+                .unwrap_or(input.len() - position);
+            let word = &input[position..position + length];
+            let token = match word {
+                "true" => Token::Bool(true),
+                "false" => Token::Bool(false),
+                _ if word.chars().all(|c| c.is_ascii_digit()) => match word.parse() {
+                    Ok(number) => Token::Number(number),
+                    Err(_) => {
+                        return Err(Error::ParseError {
+                            position,
+                            message: format!("number '{}' is too large", word),
+                        })
+                    }
+                },
+                _ => Token::Identifier(String::from(word)),
+            };
+            tokens.push((position, token));
+            position += length;
+        } else if let Some((symbol, token)) = symbols
+            .iter()
+            .find(|(symbol, _)| input[position..].starts_with(symbol))
+        {
+            tokens.push((position, token.clone()));
+            position += symbol.len();
+        } else {
+            return Err(Error::ParseError {
+                position,
+                message: format!("unexpected character '{}'", c),
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser over tokens. Binary operators from loosest to
+/// tightest binding are `<-->`, `-->`, `||`, `&&`, `==`/`!=` and the clock
+/// comparisons `<`/`<=`/`>`/`>=`, where the implication is right associative
+/// and the rest left associative.
+pub(crate) struct Parser<'a> {
+    tokens: &'a [(usize, Token)],
+    next: usize,
+    end: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Creates the parser where `end` is the offset reported for errors at the
+    /// end of the input.
+    pub(crate) fn new(tokens: &'a [(usize, Token)], end: usize) -> Self {
+        Parser {
+            tokens,
+            next: 0,
+            end,
+        }
+    }
+
+    pub(crate) fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    /// The offset of the next token, or of the end if all are consumed.
+    pub(crate) fn position(&self) -> usize {
+        match self.tokens.get(self.next) {
+            Some((position, _)) => *position,
+            None => self.end,
+        }
+    }
+
+    pub(crate) fn is_at_end(&self) -> bool {
+        self.next == self.tokens.len()
+    }
+
+    /// Consumes the next token if it is the given token.
+    pub(crate) fn accept(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.next += 1;
+            return true;
+        }
+        false
+    }
+
+    /// Consumes the next token if it is the given keyword.
+    pub(crate) fn accept_keyword(&mut self, keyword: &str) -> bool {
+        self.accept(&Token::Identifier(String::from(keyword)))
+    }
+
+    pub(crate) fn expect(&mut self, token: &Token, description: &str) -> Result<(), Error> {
+        if self.accept(token) {
+            return Ok(());
+        }
+        Err(self.error(&format!("expected {}", description)))
+    }
+
+    pub(crate) fn expect_end(&self) -> Result<(), Error> {
+        if self.is_at_end() {
+            return Ok(());
+        }
+        Err(self.error("expected the end of the input"))
+    }
+
+    /// Consumes an identifier which is not a keyword.
+    pub(crate) fn identifier(&mut self) -> Result<String, Error> {
+        match self.peek() {
+            Some(Token::Identifier(name)) if !KEYWORDS.contains(&name.as_str()) => {
+                let name = name.clone();
+                self.next += 1;
+                Ok(name)
+            }
+            _ => Err(self.error("expected an identifier")),
+        }
+    }
+
+    pub(crate) fn error(&self, message: &str) -> Error {
+        let found = match self.peek() {
+            Some(token) => format!("{:?}", token),
+            None => String::from("the end of the input"),
+        };
+        Error::ParseError {
+            position: self.position(),
+            message: format!("{} but found {}", message, found),
+        }
+    }
+
+    pub(crate) fn expression(&mut self) -> Result<Expression, Error> {
+        let lhs = self.implication()?;
+        if self.accept(&Token::BiImplication) {
+            let rhs = self.expression()?;
+            return Ok(Expression::new_logical_bi_implication(&lhs, &rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn implication(&mut self) -> Result<Expression, Error> {
+        let lhs = self.disjunction()?;
+        if self.accept(&Token::Implication) {
+            let rhs = self.implication()?;
+            return Ok(Expression::new_logical_implication(&lhs, &rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn disjunction(&mut self) -> Result<Expression, Error> {
+        let mut lhs = self.conjunction()?;
+        while self.accept(&Token::Or) {
+            let rhs = self.conjunction()?;
+            lhs = Expression::new_logical_or(&lhs, &rhs);
+        }
+        Ok(lhs)
+    }
+
+    fn conjunction(&mut self) -> Result<Expression, Error> {
+        let mut lhs = self.equality()?;
+        while self.accept(&Token::And) {
+            let rhs = self.equality()?;
+            lhs = Expression::new_logical_and(&lhs, &rhs);
+        }
+        Ok(lhs)
+    }
+
+    fn equality(&mut self) -> Result<Expression, Error> {
+        let mut lhs = self.comparison()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Equal) => BinaryOperator::Equal,
+                Some(Token::NotEqual) => BinaryOperator::NotEqual,
+                _ => return Ok(lhs),
+            };
+            self.next += 1;
+            let rhs = self.comparison()?;
+            lhs = Expression::new_binary_expression(&lhs, &operator, &rhs);
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expression, Error> {
+        let mut lhs = self.unary()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Less) => BinaryOperator::Less,
+                Some(Token::LessEqual) => BinaryOperator::LessEqual,
+                Some(Token::Greater) => BinaryOperator::Greater,
+                Some(Token::GreaterEqual) => BinaryOperator::GreaterEqual,
+                _ => return Ok(lhs),
+            };
+            self.next += 1;
+            let rhs = self.unary()?;
+            lhs = Expression::new_binary_expression(&lhs, &operator, &rhs);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expression, Error> {
+        if self.accept(&Token::Bang) {
+            let operand = self.unary()?;
+            return Ok(Expression::new_logical_not(&operand));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expression, Error> {
+        if let Some(Token::Bool(value)) = self.peek() {
+            let value = *value;
+            self.next += 1;
+            return Ok(Expression::new_boolean(&value));
+        }
+        if let Some(Token::Number(time)) = self.peek() {
+            let time = *time;
+            self.next += 1;
+            return Ok(Expression::new_clock(time));
+        }
+        if self.accept(&Token::LeftParenthesis) {
+            let inner = self.expression()?;
+            self.expect(&Token::RightParenthesis, "')'")?;
+            return Ok(Expression::new_parenthesized(&inner));
+        }
+        match self.identifier() {
+            Ok(name) => Ok(Expression::new_identifier(&name)),
+            Err(_) => Err(self.error("expected an expression")),
+        }
+    }
+
+    /// Parses statements separated by `;`, where a single statement is not
+    /// wrapped in a sequence.
+    pub(crate) fn statement(&mut self) -> Result<Statement, Error> {
+        let mut statements = vec![self.simple_statement()?];
+        while self.accept(&Token::Semicolon) {
+            statements.push(self.simple_statement()?);
+        }
+        if statements.len() == 1 {
+            return Ok(statements.remove(0));
+        }
+        Ok(Statement::new_sequence(&statements))
+    }
+
+    fn simple_statement(&mut self) -> Result<Statement, Error> {
+        if self.accept_keyword("if") {
+            let condition = self.expression()?;
+            if !self.accept_keyword("then") {
+                return Err(self.error("expected 'then'"));
+            }
+            let then = self.branch()?;
+            let otherwise = match self.accept_keyword("else") {
+                true => Some(self.branch()?),
+                false => None,
+            };
+            return Ok(Statement::new_if(&condition, &then, otherwise.as_ref()));
+        }
+        let identifier = self.identifier()?;
+        if self.accept(&Token::Colon) {
+            self.expect(&Token::Assign, "'='")?;
+            if !self.accept(&Token::Number(0)) {
+                return Err(self.error("a clock can only be reset to 0"));
+            }
+            return Ok(Statement::new_clock_reset(&identifier));
+        }
+        self.expect(&Token::Assign, "'='")?;
+        let value = self.expression()?;
+        Ok(Statement::new_assignment(
+            &Expression::new_identifier(&identifier),
+            &value,
+        ))
+    }
+
+    /// Parses the branch of an if, which is either a single statement or
+    /// statements separated by `;` enclosed in braces.
+    fn branch(&mut self) -> Result<Statement, Error> {
+        if self.accept(&Token::LeftBrace) {
+            let statement = self.statement()?;
+            self.expect(&Token::RightBrace, "'}'")?;
+            return Ok(statement);
+        }
+        self.simple_statement()
+    }
+
+    /// Parses a value, that is a boolean, a clock time or an identifier.
+    pub(crate) fn value(&mut self) -> Result<Value, Error> {
+        if let Some(Token::Bool(value)) = self.peek() {
+            let value = *value;
+            self.next += 1;
+            return Ok(Value::new_boolean(value));
+        }
+        if let Some(Token::Number(time)) = self.peek() {
+            let time = *time;
+            self.next += 1;
+            return Ok(Value::new_clock(time));
+        }
+        match self.identifier() {
+            Ok(name) => Ok(Value::new_identifier(&name)),
+            Err(_) => Err(self.error("expected a value")),
+        }
+    }
+}
+
+/// Parses the whole input as an expression, such that the printed form of a
+/// parsed expression parses to the same expression.
+pub fn parse_expression(input: &str) -> Result<Expression, Error> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser::new(&tokens, input.len());
+    let expression = parser.expression()?;
+    parser.expect_end()?;
+    Ok(expression)
+}
+
+/// Parses the whole input as a statement, such as `x = true; if x then { y = x }`.
+pub fn parse_statement(input: &str) -> Result<Statement, Error> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser::new(&tokens, input.len());
+    let statement = parser.statement()?;
+    parser.expect_end()?;
+    Ok(statement)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::language::{
+        error::Error,
+        expression::{BinaryOperator, Expression},
+        statement::Statement,
+        value::Value,
+    };
+
+    use super::{parse_expression, parse_statement};
+
+    #[test]
+    fn parse_expression_precedence() {
+        let a = Expression::new_identifier("a");
+        let b = Expression::new_identifier("b");
+        let c = Expression::new_identifier("c");
+
+        let expression = parse_expression("!a && b || c --> a == b");
+
+        assert_eq!(
+            expression,
+            Ok(Expression::new_logical_implication(
+                &Expression::new_logical_or(
+                    &Expression::new_logical_and(&Expression::new_logical_not(&a), &b),
+                    &c
+                ),
+                &Expression::new_logical_equal(&a, &b)
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_expression_keeps_parentheses() {
+        let a = Expression::new_identifier("a");
+        let b = Expression::new_identifier("b");
+
+        let expression = parse_expression("a && (b || true)");
+
+        assert_eq!(
+            expression,
+            Ok(Expression::new_logical_and(
+                &a,
+                &Expression::new_parenthesized(&Expression::new_logical_or(
+                    &b,
+                    &Expression::new_boolean(&true)
+                ))
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_expression_round_trips_through_to_string() {
+        let input = "a <--> !(b != c) --> d && (e || false)";
+
+        let expression = parse_expression(input).unwrap();
+
+        assert_eq!(expression.to_string(), input);
+        assert_eq!(parse_expression(&expression.to_string()), Ok(expression));
+    }
+
+    #[test]
+    fn parse_expression_round_trips_without_parenthesized_nodes() {
+        let (x, y, z) = (
+            Expression::new_identifier("x"),
+            Expression::new_identifier("y"),
+            Expression::new_identifier("z"),
+        );
+        let expressions = [
+            Expression::new_logical_and(&Expression::new_logical_or(&x, &y), &z),
+            Expression::new_logical_or(&x, &Expression::new_logical_or(&y, &z)),
+            Expression::new_logical_implication(&Expression::new_logical_implication(&x, &y), &z),
+            Expression::new_logical_not(&Expression::new_logical_equal(&x, &y)),
+        ];
+
+        let printed: Vec<String> = expressions.iter().map(|e| e.to_string()).collect();
+
+        assert_eq!(
+            printed,
+            vec![
+                "(x || y) && z",
+                "x || (y || z)",
+                "(x --> y) --> z",
+                "!(x == y)"
+            ]
+        );
+        for (expression, printed) in expressions.iter().zip(printed) {
+            let parsed = parse_expression(&printed).unwrap();
+            assert_eq!(parsed.without_parentheses(), *expression);
+        }
+    }
+
+    #[test]
+    fn parse_statement_braced_branches_round_trip() {
+        let statement = Statement::new_if(
+            &Expression::new_identifier("x"),
+            &Statement::new_sequence(&[
+                Statement::new_simple_assignment("y", &Value::new_true()),
+                Statement::new_simple_assignment("z", &Value::new_true()),
+            ]),
+            Some(&Statement::new_simple_assignment("y", &Value::new_false())),
+        );
+
+        let printed = statement.to_string();
+
+        assert_eq!(
+            printed,
+            "if x then { y = true; z = true } else { y = false }"
+        );
+        assert_eq!(parse_statement(&printed), Ok(statement));
+        assert!(parse_statement("if x then { y = true").is_err());
+    }
+
+    #[test]
+    fn parse_expression_reports_position() {
+        let expression = parse_expression("a && (b || c");
+
+        assert!(matches!(
+            expression,
+            Err(Error::ParseError { position: 12, .. })
+        ));
+        assert!(matches!(
+            parse_expression("a # b"),
+            Err(Error::ParseError { position: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn parse_statement_sequence_and_if() {
+        let statement = parse_statement("x = true; if x then y = x else y = false");
+
+        assert_eq!(
+            statement,
+            Ok(Statement::new_sequence(&[
+                Statement::new_simple_assignment("x", &Value::new_true()),
+                Statement::new_if(
+                    &Expression::new_identifier("x"),
+                    &Statement::new_assignment(
+                        &Expression::new_identifier("y"),
+                        &Expression::new_identifier("x")
+                    ),
+                    Some(&Statement::new_simple_assignment("y", &Value::new_false())),
+                ),
+            ]))
+        );
+    }
+
+    #[test]
+    fn parse_expression_clock_constraint() {
+        let expression = parse_expression("ready && x <= 5").ok().unwrap();
+
+        assert_eq!(
+            expression,
+            Expression::new_logical_and(
+                &Expression::new_identifier("ready"),
+                &Expression::new_clock_constraint("x", &BinaryOperator::LessEqual, 5),
+            )
+        );
+        assert_eq!(expression.to_string(), "ready && x <= 5");
+    }
+
+    #[test]
+    fn parse_statement_clock_reset() {
+        let statement = parse_statement("x := 0; ready = true").ok().unwrap();
+
+        assert_eq!(
+            statement,
+            Statement::new_sequence(&[
+                Statement::new_clock_reset("x"),
+                Statement::new_simple_assignment("ready", &Value::new_true()),
+            ])
+        );
+        assert_eq!(statement.to_string(), "x := 0; ready = true");
+        assert!(parse_statement("x := 1").is_err());
+    }
+}
@@ -1,11 +1,21 @@
 use super::{expression::Expression, value::Value};
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub enum Statement {
     Assigment {
         identifier: Expression,
         value: Expression,
     },
+    Sequence(Vec<Statement>),
+    If {
+        condition: Expression,
+        then: Box<Statement>,
+        otherwise: Option<Box<Statement>>,
+    },
+    /// Sets the clock back to zero, written `x := 0`.
+    ClockReset {
+        clock: String,
+    },
 }
 
 impl Statement {
@@ -23,6 +33,57 @@ impl Statement {
         }
     }
 
+    pub fn new_clock_reset(clock: &str) -> Statement {
+        Statement::ClockReset {
+            clock: String::from(clock),
+        }
+    }
+
+    pub fn new_sequence(statements: &[Statement]) -> Statement {
+        Statement::Sequence(statements.to_vec())
+    }
+
+    pub fn new_if(
+        condition: &Expression,
+        then: &Statement,
+        otherwise: Option<&Statement>,
+    ) -> Statement {
+        Statement::If {
+            condition: condition.clone(),
+            then: Box::new(then.clone()),
+            otherwise: otherwise.map(|statement| Box::new(statement.clone())),
+        }
+    }
+
+    /// The statement with the parentheses removed from its expressions.
+    pub fn without_parentheses(&self) -> Statement {
+        match self {
+            Statement::Assigment { identifier, value } => Statement::new_assignment(
+                &identifier.without_parentheses(),
+                &value.without_parentheses(),
+            ),
+            Statement::Sequence(statements) => Statement::Sequence(
+                statements
+                    .iter()
+                    .map(Statement::without_parentheses)
+                    .collect(),
+            ),
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+            } => Statement::new_if(
+                &condition.without_parentheses(),
+                &then.without_parentheses(),
+                otherwise
+                    .as_ref()
+                    .map(|otherwise| otherwise.without_parentheses())
+                    .as_ref(),
+            ),
+            Statement::ClockReset { .. } => self.clone(),
+        }
+    }
+
     pub fn identifiers(&self) -> Vec<String> {
         let mut identifiers: Vec<String> = Vec::new();
 
@@ -31,10 +92,118 @@ impl Statement {
                 identifiers.extend(identifier.identifiers());
                 identifiers.extend(value.identifiers());
             }
+            Statement::Sequence(statements) => {
+                for statement in statements {
+                    identifiers.extend(statement.identifiers());
+                }
+            }
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                identifiers.extend(condition.identifiers());
+                identifiers.extend(then.identifiers());
+                if let Some(otherwise) = otherwise {
+                    identifiers.extend(otherwise.identifiers());
+                }
+            }
+            Statement::ClockReset { clock } => identifiers.push(clock.clone()),
         }
 
         identifiers
     }
+
+    /// The identifiers whose values the statement uses, that is every identifier
+    /// except those only occurring as assignment targets.
+    pub fn read_identifiers(&self) -> Vec<String> {
+        match self {
+            Statement::Assigment { value, .. } => value.identifiers(),
+            Statement::Sequence(statements) => statements
+                .iter()
+                .flat_map(|statement| statement.read_identifiers())
+                .collect(),
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let mut identifiers = condition.identifiers();
+                identifiers.extend(then.read_identifiers());
+                if let Some(otherwise) = otherwise {
+                    identifiers.extend(otherwise.read_identifiers());
+                }
+                identifiers
+            }
+            Statement::ClockReset { .. } => Vec::new(),
+        }
+    }
+
+    /// The identifiers assigned to by the statement, in any branch.
+    pub fn assigned_identifiers(&self) -> Vec<String> {
+        match self {
+            Statement::Assigment { identifier, .. } => identifier.identifiers(),
+            Statement::Sequence(statements) => statements
+                .iter()
+                .flat_map(|statement| statement.assigned_identifiers())
+                .collect(),
+            Statement::If {
+                then, otherwise, ..
+            } => {
+                let mut identifiers = then.assigned_identifiers();
+                if let Some(otherwise) = otherwise {
+                    identifiers.extend(otherwise.assigned_identifiers());
+                }
+                identifiers
+            }
+            Statement::ClockReset { clock } => vec![clock.clone()],
+        }
+    }
+
+    /// The weakest condition before the statement which guarantees that the
+    /// postcondition holds after it, obtained by substituting assigned values.
+    pub fn weakest_precondition(&self, postcondition: &Expression) -> Expression {
+        match self {
+            Statement::Assigment { identifier, value } => identifier
+                .identifiers()
+                .iter()
+                .fold(postcondition.clone(), |condition, name| {
+                    condition.substitute(name, value)
+                }),
+            Statement::ClockReset { clock } => {
+                postcondition.substitute(clock, &Expression::new_clock(0))
+            }
+            Statement::Sequence(statements) => statements
+                .iter()
+                .rev()
+                .fold(postcondition.clone(), |condition, statement| {
+                    statement.weakest_precondition(&condition)
+                }),
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let then = then.weakest_precondition(postcondition);
+                let otherwise = match otherwise {
+                    Some(otherwise) => otherwise.weakest_precondition(postcondition),
+                    None => postcondition.clone(),
+                };
+                if then == otherwise {
+                    return then;
+                }
+                Expression::new_logical_or(
+                    &Expression::new_conjunction(&[condition.clone(), then])
+                        .parenthesized_if_binary(),
+                    &Expression::new_conjunction(&[
+                        Expression::new_logical_not(&condition.parenthesized_if_binary()),
+                        otherwise,
+                    ])
+                    .parenthesized_if_binary(),
+                )
+            }
+        }
+    }
 }
 
 impl ToString for Statement {
@@ -43,13 +212,35 @@ impl ToString for Statement {
             Statement::Assigment { identifier, value } => {
                 identifier.to_string() + " = " + &value.to_string()
             }
+            Statement::Sequence(statements) => statements
+                .iter()
+                .map(|statement| statement.to_string())
+                .collect::<Vec<String>>()
+                .join("; "),
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                // Branches are braced, such that a sequence is not cut short by the parser
+                let mut result = "if ".to_owned()
+                    + &condition.to_string()
+                    + " then { "
+                    + &then.to_string()
+                    + " }";
+                if let Some(otherwise) = otherwise {
+                    result = result + " else { " + &otherwise.to_string() + " }";
+                }
+                result
+            }
+            Statement::ClockReset { clock } => clock.to_owned() + " := 0",
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::language::expression::Expression;
+    use crate::language::{expression::Expression, value::Value};
 
     use super::Statement;
 
@@ -122,4 +313,54 @@ mod tests {
             vec![String::from(ident), String::from(rhs_ident)]
         )
     }
+
+    #[test]
+    fn node_new_sequence_to_string() {
+        let node = Statement::new_sequence(&[
+            Statement::new_simple_assignment("a", &Value::new_identifier("x")),
+            Statement::new_simple_assignment("b", &Value::new_identifier("y")),
+        ]);
+        assert_eq!(node.to_string(), "a = x; b = y");
+        assert_eq!(node.identifiers(), vec!["a", "x", "b", "y"]);
+    }
+
+    #[test]
+    fn node_new_if_to_string() {
+        let then = Statement::new_simple_assignment("a", &Value::new_true());
+        let otherwise = Statement::new_simple_assignment("a", &Value::new_false());
+        let condition = Expression::new_identifier("ready");
+        let with_else = Statement::new_if(&condition, &then, Some(&otherwise));
+        let without_else = Statement::new_if(&condition, &then, None);
+        assert_eq!(
+            with_else.to_string(),
+            "if ready then { a = true } else { a = false }"
+        );
+        assert_eq!(without_else.to_string(), "if ready then { a = true }");
+        assert_eq!(with_else.identifiers(), vec!["ready", "a", "a"]);
+    }
+
+    #[test]
+    fn statement_weakest_precondition() {
+        let x = Expression::new_identifier("x");
+        let y = Expression::new_identifier("y");
+        let sequence = Statement::new_sequence(&[
+            Statement::new_assignment(&x, &y),
+            Statement::new_simple_assignment("y", &Value::new_false()),
+        ]);
+        let branch = Statement::new_if(
+            &x,
+            &Statement::new_simple_assignment("y", &Value::new_true()),
+            None,
+        );
+        let postcondition = Expression::new_logical_and(&x, &y);
+
+        assert_eq!(
+            sequence.weakest_precondition(&postcondition).to_string(),
+            "y && false"
+        );
+        assert_eq!(
+            branch.weakest_precondition(&postcondition).to_string(),
+            "x || (!x && (x && y))"
+        );
+    }
 }
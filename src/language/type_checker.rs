@@ -28,8 +28,8 @@ impl TypeChecker {
             Expression::Literal(literal) => self.check_value(literal),
             Expression::Parenthesized(expr) => self.check_expression(expr),
             Expression::Binary(lhs, op, rhs) => {
-                let lhs_type = self.check_expression(&lhs).ok().unwrap();
-                let rhs_type = self.check_expression(&rhs).ok().unwrap();
+                let lhs_type = self.check_expression(lhs)?;
+                let rhs_type = self.check_expression(rhs)?;
 
                 match op {
                     BinaryOperator::LogicalAnd
@@ -46,6 +46,18 @@ impl TypeChecker {
                             })
                         }
                     }
+                    BinaryOperator::Less
+                    | BinaryOperator::LessEqual
+                    | BinaryOperator::Greater
+                    | BinaryOperator::GreaterEqual => {
+                        if lhs_type == LangType::Clock && rhs_type == LangType::Clock {
+                            Ok(LangType::Logical)
+                        } else {
+                            Err(Error::TypeCheckingError {
+                                message: String::from("Can only compare clocks"),
+                            })
+                        }
+                    }
                     BinaryOperator::Equal | BinaryOperator::NotEqual => {
                         if lhs_type == rhs_type {
                             Ok(LangType::Logical)
@@ -60,7 +72,7 @@ impl TypeChecker {
                 }
             }
             Expression::Unary(op, expr) => {
-                let expr_type = self.check_expression(&expr).ok().unwrap();
+                let expr_type = self.check_expression(expr)?;
                 match op {
                     super::expression::UnaryOperator::Negation => {
                         if expr_type != LangType::Logical {
@@ -80,19 +92,64 @@ impl TypeChecker {
 
     pub fn check_statement(&self, statement: &Statement) -> Result<LangType, Error> {
         match statement {
-            Statement::Assigment { .. } => Ok(LangType::Void),
+            Statement::Assigment { identifier, value } => {
+                let expected = self.check_expression(identifier)?;
+                let actual = self.check_expression(value)?;
+                if expected != actual {
+                    return Err(Error::AssignmentTypeMismatch {
+                        identifier: identifier.to_string(),
+                        expected,
+                        actual,
+                    });
+                }
+                Ok(LangType::Void)
+            }
+            Statement::ClockReset { clock } => {
+                let actual = self.check_value(&Value::new_identifier(clock))?;
+                if actual != LangType::Clock {
+                    return Err(Error::AssignmentTypeMismatch {
+                        identifier: clock.clone(),
+                        expected: actual,
+                        actual: LangType::Clock,
+                    });
+                }
+                Ok(LangType::Void)
+            }
+            Statement::Sequence(statements) => {
+                for statement in statements {
+                    self.check_statement(statement)?;
+                }
+                Ok(LangType::Void)
+            }
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                if self.check_expression(condition)? != LangType::Logical {
+                    return Err(Error::TypeCheckingError {
+                        message: String::from("The condition of an if statement must be logical"),
+                    });
+                }
+                self.check_statement(then)?;
+                if let Some(otherwise) = otherwise {
+                    self.check_statement(otherwise)?;
+                }
+                Ok(LangType::Void)
+            }
         }
     }
 
     pub fn check_value(&self, value: &Value) -> Result<LangType, Error> {
         match value {
             Value::Bool(_) => Ok(LangType::Logical),
+            Value::Clock(_) => Ok(LangType::Clock),
             Value::Identifier(identifier) => {
                 if let Some(value) = self.environment.get_value(identifier) {
                     return self.check_value(value);
                 }
                 Err(Error::TypeCheckingError {
-                    message: String::from(format!("unknown identifier: {identifier}")),
+                    message: format!("unknown identifier: {identifier}"),
                 })
             }
         }
@@ -102,8 +159,12 @@ impl TypeChecker {
 #[cfg(test)]
 mod tests {
     use crate::language::{
-        environment::Environment, expression::Expression, lang_type::LangType,
-        statement::Statement, value::Value,
+        environment::Environment,
+        error::Error,
+        expression::{BinaryOperator, Expression},
+        lang_type::LangType,
+        statement::Statement,
+        value::Value,
     };
 
     use super::TypeChecker;
@@ -337,6 +398,21 @@ mod tests {
                 Statement::new_simple_assignment("a", &Value::new_identifier("a")),
                 LangType::Void,
             ),
+            (
+                Statement::new_if(
+                    &Expression::new_identifier("a"),
+                    &Statement::new_simple_assignment("a", &Value::new_true()),
+                    Some(&Statement::new_simple_assignment("a", &Value::new_false())),
+                ),
+                LangType::Void,
+            ),
+            (
+                Statement::new_sequence(&[
+                    Statement::new_simple_assignment("a", &Value::new_true()),
+                    Statement::new_simple_assignment("a", &Value::new_false()),
+                ]),
+                LangType::Void,
+            ),
         ];
 
         for (node, node_type) in inputs {
@@ -347,6 +423,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn type_checker_check_statement_if_unknown_condition() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_false());
+        let type_checker = TypeChecker::new(&environment);
+        let statement = Statement::new_if(
+            &Expression::new_identifier("unknown"),
+            &Statement::new_simple_assignment("a", &Value::new_true()),
+            None,
+        );
+
+        let result = type_checker.check_statement(&statement);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn type_checker_check_statement_unknown_target() {
+        let type_checker = TypeChecker::new(&Environment::new_empty());
+        let assignment = Statement::new_simple_assignment("unknown", &Value::new_true());
+
+        let result = type_checker.check_statement(&assignment);
+
+        assert!(matches!(result, Err(Error::TypeCheckingError { .. })));
+    }
+
+    #[test]
+    fn type_checker_check_statement_ill_typed_value() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_false());
+        let type_checker = TypeChecker::new(&environment);
+        let assignment = Statement::new_assignment(
+            &Expression::new_identifier("a"),
+            &Expression::new_logical_and(
+                &Expression::new_boolean(&true),
+                &Expression::new_identifier("unknown"),
+            ),
+        );
+
+        let result = type_checker.check_statement(&assignment);
+
+        assert!(result.is_err());
+    }
+
     #[test]
     fn type_checker_check_value() {
         let mut environment = Environment::new_empty();
@@ -365,4 +485,80 @@ mod tests {
             assert_eq!(result.unwrap(), node_type);
         }
     }
+
+    #[test]
+    fn type_checker_check_clock_constraint() {
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_clock(0));
+        let type_checker = TypeChecker::new(&environment);
+        let constraint = Expression::new_clock_constraint("x", &BinaryOperator::LessEqual, 5);
+
+        let result = type_checker.check_expression(&constraint);
+
+        assert_eq!(result, Ok(LangType::Logical));
+    }
+
+    #[test]
+    fn type_checker_check_clock_reset() {
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_clock(3));
+        let type_checker = TypeChecker::new(&environment);
+        let reset = Statement::new_simple_assignment("x", &Value::new_clock(0));
+
+        let result = type_checker.check_statement(&reset);
+
+        assert_eq!(result, Ok(LangType::Void));
+    }
+
+    #[test]
+    fn type_checker_rejects_logical_operators_on_clocks() {
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_clock(0));
+        let type_checker = TypeChecker::new(&environment);
+        let conjunction = Expression::new_logical_and(
+            &Expression::new_identifier("x"),
+            &Expression::new_boolean(&true),
+        );
+        let negation = Expression::new_logical_not(&Expression::new_identifier("x"));
+
+        assert!(type_checker.check_expression(&conjunction).is_err());
+        assert!(type_checker.check_expression(&negation).is_err());
+    }
+
+    #[test]
+    fn type_checker_rejects_comparing_booleans() {
+        let mut environment = Environment::new_empty();
+        environment.insert("a", &Value::new_false());
+        let type_checker = TypeChecker::new(&environment);
+        let comparison = Expression::new_binary_expression(
+            &Expression::new_identifier("a"),
+            &BinaryOperator::Less,
+            &Expression::new_clock(5),
+        );
+
+        let result = type_checker.check_expression(&comparison);
+
+        assert!(matches!(result, Err(Error::TypeCheckingError { .. })));
+    }
+
+    #[test]
+    fn type_checker_check_clock_reset_requires_clock() {
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_clock(3));
+        environment.insert("a", &Value::new_false());
+        let type_checker = TypeChecker::new(&environment);
+
+        let clock = type_checker.check_statement(&Statement::new_clock_reset("x"));
+        let boolean = type_checker.check_statement(&Statement::new_clock_reset("a"));
+
+        assert_eq!(clock, Ok(LangType::Void));
+        assert_eq!(
+            boolean,
+            Err(Error::AssignmentTypeMismatch {
+                identifier: String::from("a"),
+                expected: LangType::Logical,
+                actual: LangType::Clock,
+            })
+        );
+    }
 }
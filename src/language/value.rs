@@ -1,9 +1,16 @@
 use std::fmt::Display;
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+use super::identifier::Identifier;
+
+/// Booleans are ordered before identifiers and identifiers before clocks, false
+/// before true, identifiers by their text and clocks by their value.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub enum Value {
     Bool(bool),
-    Identifier(String),
+    Identifier(Identifier),
+    /// A point in time, which is both the value of a clock variable and the
+    /// bound it is compared against in a clock constraint.
+    Clock(u32),
 }
 
 impl Value {
@@ -16,19 +23,24 @@ impl Value {
     }
 
     pub fn new_identifier(ident: &str) -> Self {
-        Value::Identifier(String::from(ident))
+        Value::Identifier(Identifier::new(ident))
     }
 
     pub fn new_boolean(boolean: bool) -> Self {
         Value::Bool(boolean)
     }
+
+    pub fn new_clock(time: u32) -> Self {
+        Value::Clock(time)
+    }
 }
 
 impl Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Value::Bool(value) => return f.write_str(&value.to_string()),
-            Value::Identifier(identifier) => return f.write_str(identifier),
+            Value::Identifier(identifier) => return f.write_str(identifier.as_str()),
+            Value::Clock(time) => return f.write_str(&time.to_string()),
         }
     }
 }
@@ -55,4 +67,14 @@ mod tests {
         let value = Value::new_identifier(&ident.to_owned());
         assert!(matches!(value, Value::Identifier(value_ident) if value_ident == ident))
     }
+
+    #[test]
+    fn value_new_identifier_shares_storage() {
+        let a = Value::new_identifier("ident");
+        let b = Value::new_identifier("ident");
+        assert_eq!(a, b);
+        assert!(
+            matches!((&a, &b), (Value::Identifier(a), Value::Identifier(b)) if a.shares_storage_with(b))
+        );
+    }
 }
@@ -1,4 +1,5 @@
 pub mod automatom;
+pub mod diagnostics;
 pub mod language;
 pub mod transition_system;
 
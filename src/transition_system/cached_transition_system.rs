@@ -0,0 +1,216 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+};
+
+use crate::{automatom::channel::Channel, language::error::Error as LangError};
+
+use super::{state::State, transition::Transition, transition_system::TransitionSystem};
+
+type CacheKey = (State, Vec<String>);
+
+/// Wraps a transition system and memoizes its successors and transitions for
+/// each state and set of actions for as long as the wrapper lives.
+#[derive(Debug)]
+pub struct CachedTransitionSystem<TS: TransitionSystem> {
+    inner: TS,
+    successors: RefCell<HashMap<CacheKey, Vec<State>>>,
+    transitions: RefCell<HashMap<CacheKey, Vec<Transition>>>,
+    hits: Cell<usize>,
+    misses: Cell<usize>,
+}
+
+impl<TS: TransitionSystem> CachedTransitionSystem<TS> {
+    pub fn new(inner: TS) -> Self {
+        CachedTransitionSystem {
+            inner,
+            successors: RefCell::new(HashMap::new()),
+            transitions: RefCell::new(HashMap::new()),
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+        }
+    }
+
+    pub fn get_inner(&self) -> &TS {
+        &self.inner
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses.get()
+    }
+
+    /// The fraction of lookups answered from the cache, zero before any lookup.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits() + self.misses();
+        if lookups == 0 {
+            return 0.0;
+        }
+        self.hits() as f64 / lookups as f64
+    }
+
+    fn key(state: &State, actions: &HashSet<Channel>) -> CacheKey {
+        let mut names: Vec<String> = actions
+            .iter()
+            .map(|action| action.name().to_string())
+            .collect();
+        names.sort();
+        (state.clone(), names)
+    }
+
+    fn memoize<T: Clone>(
+        &self,
+        cache: &RefCell<HashMap<CacheKey, Vec<T>>>,
+        key: CacheKey,
+        compute: impl FnOnce() -> Vec<T>,
+    ) -> Vec<T> {
+        if let Some(cached) = cache.borrow().get(&key) {
+            self.hits.set(self.hits.get() + 1);
+            return cached.clone();
+        }
+
+        self.misses.set(self.misses.get() + 1);
+        let computed = compute();
+        cache.borrow_mut().insert(key, computed.clone());
+        computed
+    }
+}
+
+impl<TS: TransitionSystem> TransitionSystem for CachedTransitionSystem<TS> {
+    fn predecessors_via(
+        &self,
+        state: &State,
+        last_actions: &HashSet<Channel>,
+        reachability_actions: &HashSet<Channel>,
+    ) -> Vec<State> {
+        self.inner
+            .predecessors_via(state, last_actions, reachability_actions)
+    }
+
+    fn successors(&self, state: &State, actions: &HashSet<Channel>) -> Vec<State> {
+        self.memoize(&self.successors, Self::key(state, actions), || {
+            self.inner.successors(state, actions)
+        })
+    }
+
+    fn try_successors(
+        &self,
+        state: &State,
+        actions: &HashSet<Channel>,
+    ) -> Result<Vec<State>, LangError> {
+        self.inner.try_successors(state, actions)
+    }
+
+    fn transitions(&self, state: &State, actions: &HashSet<Channel>) -> Vec<Transition> {
+        self.memoize(&self.transitions, Self::key(state, actions), || {
+            self.inner.transitions(state, actions)
+        })
+    }
+
+    fn delay_successors(&self, state: &State) -> Vec<State> {
+        self.inner.delay_successors(state)
+    }
+
+    fn get_initial_state(&self) -> State {
+        self.inner.get_initial_state()
+    }
+
+    fn get_actions(&self) -> &HashSet<Channel> {
+        self.inner.get_actions()
+    }
+
+    fn get_inputs(&self) -> &HashSet<Channel> {
+        self.inner.get_inputs()
+    }
+
+    fn get_outputs(&self) -> &HashSet<Channel> {
+        self.inner.get_outputs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        language::{environment::Environment, statement::Statement, value::Value},
+        transition_system::{
+            state::State, transition_system::TransitionSystem,
+            transition_system_breadth_first_search::TransitionSystemBreadthFirstSearch,
+            transition_system_depth_first_search::TransitionSystemDepthFirstSearch,
+        },
+    };
+
+    use super::CachedTransitionSystem;
+
+    fn new_automaton() -> Automaton {
+        let invariant = &Invariant::new_true();
+        let channel_in = Channel::new_input("in");
+        let channel_out = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_false());
+        let update = Update::new(&Statement::new_simple_assignment("x", &Value::new_true()));
+        let edges = HashSet::from([
+            Edge::new(&a, &channel_in, &Guard::new_true(), &update, &b),
+            Edge::new(&a, &channel_out, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&b, &channel_out, &Guard::new_true(), &Update::empty(), &c),
+            Edge::new(&c, &channel_in, &Guard::new_true(), &Update::empty(), &a),
+        ]);
+        Automaton::new("automaton", &edges, Some(&environment))
+            .ok()
+            .unwrap()
+    }
+
+    #[test]
+    fn cached_transition_system_explores_identically() {
+        let automaton = new_automaton();
+        let cached = CachedTransitionSystem::new(automaton.clone());
+        let actions = automaton.get_actions().clone();
+
+        let expected: Vec<State> =
+            TransitionSystemBreadthFirstSearch::new(&actions, &automaton).collect();
+        let breadth_first: Vec<State> =
+            TransitionSystemBreadthFirstSearch::new(&actions, &cached).collect();
+        let depth_first: Vec<State> =
+            TransitionSystemDepthFirstSearch::new(&actions, &cached).collect();
+
+        assert_eq!(breadth_first.len(), expected.len());
+        assert_eq!(depth_first.len(), expected.len());
+        for state in expected.iter() {
+            assert!(breadth_first.contains(state));
+            assert!(depth_first.contains(state));
+        }
+    }
+
+    #[test]
+    fn cached_transition_system_hit_rate() {
+        let automaton = new_automaton();
+        let cached = CachedTransitionSystem::new(automaton.clone());
+        let initial = cached.get_initial_state();
+        assert_eq!(cached.hit_rate(), 0.0);
+
+        let first = cached.successors(&initial, automaton.get_actions());
+        let second = cached.successors(&initial, automaton.get_actions());
+
+        assert_eq!(first, second);
+        assert_eq!(
+            first.len(),
+            automaton
+                .successors(&initial, automaton.get_actions())
+                .len()
+        );
+        assert_eq!(cached.hits(), 1);
+        assert_eq!(cached.misses(), 1);
+        assert_eq!(cached.hit_rate(), 0.5);
+    }
+}
@@ -0,0 +1,379 @@
+use std::collections::HashSet;
+
+use crate::{
+    automatom::{
+        automaton::Automaton, channel::Channel, edge::Edge, error::Error, invariant::Invariant,
+        location::Location,
+    },
+    diagnostics::trace_span,
+    language::expression::Expression,
+};
+
+use super::{
+    state::State, transition::Transition, transition_system::TransitionSystem,
+    transition_system_breadth_first_search::TransitionSystemBreadthFirstSearch,
+};
+
+/// The parallel composition of two automata over a shared environment. Actions
+/// in both alphabets synchronize, in which case the right automaton takes its
+/// step in the environment produced by the step of the left, such that an
+/// observer composed on the right sees the effect of the step it observes.
+/// All other actions interleave.
+#[derive(Debug, Clone)]
+pub struct Composition<'a> {
+    left: &'a Automaton,
+    right: &'a Automaton,
+    actions: HashSet<Channel>,
+    inputs: HashSet<Channel>,
+    outputs: HashSet<Channel>,
+}
+
+impl<'a> Composition<'a> {
+    pub fn new(left: &'a Automaton, right: &'a Automaton) -> Result<Self, Error> {
+        trace_span!("composition::new");
+        left.is_composable_with(right)?;
+
+        let outputs: HashSet<Channel> = left
+            .get_outputs()
+            .union(right.get_outputs())
+            .cloned()
+            .collect();
+        // An input of one automaton matched by an output of the other is an output
+        let inputs: HashSet<Channel> = left
+            .get_inputs()
+            .union(right.get_inputs())
+            .filter(|input| !outputs.contains(input))
+            .cloned()
+            .collect();
+        let actions: HashSet<Channel> = inputs.union(&outputs).cloned().collect();
+
+        Ok(Composition {
+            left,
+            right,
+            actions,
+            inputs,
+            outputs,
+        })
+    }
+
+    /// The location of the composition when the left automaton is in the left
+    /// location and the right automaton in the right location.
+    pub fn compose_locations(left: &Location, right: &Location) -> Location {
+        let mut invariants: Vec<Expression> = Vec::new();
+        for location in [left, right] {
+            match location {
+                Location::Normal { invariant, .. }
+                | Location::Initial { invariant, .. }
+                | Location::Conjunction { invariant, .. } => {
+                    invariants.push(invariant.node.clone())
+                }
+                Location::Inconsistent { .. } => {
+                    return Location::new_inconsistent(&format!(
+                        "{} || {}",
+                        left.get_name(),
+                        right.get_name()
+                    ))
+                }
+                Location::Universal { .. } => (),
+            }
+        }
+
+        let invariant = match invariants.as_slice() {
+            [] => Invariant::new_true(),
+            [only] => Invariant::new(only),
+            [lhs, rhs] => Invariant::new(&Expression::new_logical_and(lhs, rhs)),
+            _ => unreachable!(),
+        };
+        Location::Conjunction {
+            locations: vec![left.clone(), right.clone()],
+            invariant,
+        }
+    }
+
+    fn split(location: &Location) -> Option<(&Location, &Location)> {
+        match location {
+            Location::Conjunction { locations, .. } if locations.len() == 2 => {
+                Some((&locations[0], &locations[1]))
+            }
+            _ => None,
+        }
+    }
+
+    /// The transitions of the automaton stepping alone by the action, where
+    /// the edge ids are prefixed with the name of the automaton.
+    fn step_one(
+        automaton: &Automaton,
+        location: &Location,
+        state: &State,
+        action: &Channel,
+    ) -> Vec<Transition> {
+        let local = State::new(location, &state.environment);
+        let single = HashSet::from([action.clone()]);
+        automaton
+            .transitions(&local, &single)
+            .into_iter()
+            .map(|transition| Transition {
+                edge: transition
+                    .edge
+                    .map(|edge| edge.with_prefixed_id(&automaton.name)),
+                ..transition
+            })
+            .collect()
+    }
+
+    /// The edge labelling a synchronized step, which is the edge of the left
+    /// automaton identified by the ids of both edges.
+    fn synchronized_edge(left: Option<&Edge>, right: Option<&Edge>) -> Option<Edge> {
+        let ids: Vec<&str> = [left, right]
+            .into_iter()
+            .filter_map(|edge| edge?.id())
+            .collect();
+        let edge = left.or(right)?;
+        match ids.is_empty() {
+            true => Some(edge.clone()),
+            false => Some(edge.with_id(&ids.join(" | "))),
+        }
+    }
+}
+
+impl TransitionSystem for Composition<'_> {
+    fn predecessors_via(
+        &self,
+        state: &State,
+        last_actions: &HashSet<Channel>,
+        reachability_actions: &HashSet<Channel>,
+    ) -> Vec<State> {
+        TransitionSystemBreadthFirstSearch::new(reachability_actions, self)
+            .filter(|current| self.successors(current, last_actions).contains(state))
+            .collect()
+    }
+
+    fn successors(&self, state: &State, actions: &HashSet<Channel>) -> Vec<State> {
+        let mut result: Vec<State> = Vec::new();
+        for transition in self.transitions(state, actions) {
+            if !result.contains(&transition.target) {
+                result.push(transition.target);
+            }
+        }
+        result
+    }
+
+    fn transitions(&self, state: &State, actions: &HashSet<Channel>) -> Vec<Transition> {
+        let mut result: Vec<Transition> = Vec::new();
+        let Some((left, right)) = Self::split(&state.location) else {
+            return result;
+        };
+
+        for action in actions
+            .iter()
+            .filter(|action| self.actions.contains(action))
+        {
+            let in_left = self.left.get_actions().contains(action);
+            let in_right = self.right.get_actions().contains(action);
+
+            if in_left && in_right {
+                for first in Self::step_one(self.left, left, state, action) {
+                    for second in Self::step_one(self.right, right, &first.target, action) {
+                        let location = Self::compose_locations(
+                            &first.target.location,
+                            &second.target.location,
+                        );
+                        result.push(Transition {
+                            source: state.clone(),
+                            action: action.clone(),
+                            edge: Self::synchronized_edge(
+                                first.edge.as_ref(),
+                                second.edge.as_ref(),
+                            ),
+                            target: State::new(&location, &second.target.environment),
+                        });
+                    }
+                }
+            } else if in_left {
+                for step in Self::step_one(self.left, left, state, action) {
+                    let location = Self::compose_locations(&step.target.location, right);
+                    result.push(Transition {
+                        source: state.clone(),
+                        target: State::new(&location, &step.target.environment),
+                        ..step
+                    });
+                }
+            } else if in_right {
+                for step in Self::step_one(self.right, right, state, action) {
+                    let location = Self::compose_locations(left, &step.target.location);
+                    result.push(Transition {
+                        source: state.clone(),
+                        target: State::new(&location, &step.target.environment),
+                        ..step
+                    });
+                }
+            }
+        }
+        result
+    }
+
+    /// Time passes in both automata at once, such that the invariants of both
+    /// must hold after the delay.
+    fn delay_successors(&self, state: &State) -> Vec<State> {
+        let ceiling = self.left.clock_ceiling().max(self.right.clock_ceiling());
+        state
+            .delayed(ceiling)
+            .filter(|delayed| {
+                self.left
+                    .is_location_consistent(&delayed.location, &delayed.environment)
+                    .unwrap_or(false)
+            })
+            .into_iter()
+            .collect()
+    }
+
+    fn get_initial_state(&self) -> State {
+        // Variables declared by both automata take the initial value of the left
+        let mut environment = self.left.get_initial_environment_cloned();
+        let right = self.right.get_initial_environment();
+        for (identifier, left_value, right_value) in environment.differences(right) {
+            if let (None, Some(value)) = (left_value, right_value) {
+                environment.insert(&identifier, &value);
+            }
+        }
+        let location = Self::compose_locations(
+            self.left.get_initial_location(),
+            self.right.get_initial_location(),
+        );
+        State::new(&location, &environment)
+    }
+
+    fn get_actions(&self) -> &HashSet<Channel> {
+        &self.actions
+    }
+
+    fn get_inputs(&self) -> &HashSet<Channel> {
+        &self.inputs
+    }
+
+    fn get_outputs(&self) -> &HashSet<Channel> {
+        &self.outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        language::{statement::Statement, value::Value},
+        transition_system::transition_system::TransitionSystem,
+    };
+
+    use super::Composition;
+
+    #[test]
+    fn composition_synchronizes_shared_actions() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let x = Location::new_initial("x", invariant);
+        let y = Location::new_normal("y", invariant);
+        let send = Channel::new_output("message");
+        let receive = Channel::new_input("message");
+        let local = Channel::new_output("local");
+        let producer = Automaton::new(
+            "producer",
+            &HashSet::from([
+                Edge::new(&a, &send, &Guard::new_true(), &Update::empty(), &b),
+                Edge::new_loop(&a, &local, &Guard::new_true(), &Update::empty()),
+            ]),
+            None,
+        )
+        .ok()
+        .unwrap();
+        let consumer = Automaton::new(
+            "consumer",
+            &HashSet::from([Edge::new(
+                &x,
+                &receive,
+                &Guard::new_true(),
+                &Update::new(&Statement::new_simple_assignment(
+                    "received",
+                    &Value::new_true(),
+                )),
+                &y,
+            )]),
+            None,
+        )
+        .ok()
+        .unwrap();
+
+        let composition = Composition::new(&producer, &consumer).ok().unwrap();
+        let initial = composition.get_initial_state();
+        let by_message = composition.step(&initial, &send);
+        let by_local = composition.step(&initial, &local);
+
+        assert!(composition.get_inputs().is_empty());
+        assert_eq!(composition.get_outputs().len(), 2);
+        assert_eq!(by_message.len(), 1);
+        assert_eq!(
+            by_message[0].location,
+            Composition::compose_locations(&b, &y)
+        );
+        assert_eq!(
+            by_message[0].environment.get_value("received"),
+            Some(&Value::new_true())
+        );
+        assert_eq!(by_local, vec![initial]);
+    }
+
+    #[test]
+    fn composition_transitions_prefix_edge_ids() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let x = Location::new_initial("x", invariant);
+        let send = Channel::new_output("message");
+        let receive = Channel::new_input("message");
+        let local = Channel::new_output("local");
+        let producer = Automaton::new(
+            "producer",
+            &HashSet::from([
+                Edge::new_loop(&a, &send, &Guard::new_true(), &Update::empty()).with_id("send"),
+                Edge::new_loop(&a, &local, &Guard::new_true(), &Update::empty()).with_id("tick"),
+            ]),
+            None,
+        )
+        .ok()
+        .unwrap();
+        let consumer = Automaton::new(
+            "consumer",
+            &HashSet::from([
+                Edge::new_loop(&x, &receive, &Guard::new_true(), &Update::empty())
+                    .with_id("receive"),
+            ]),
+            None,
+        )
+        .ok()
+        .unwrap();
+
+        let composition = Composition::new(&producer, &consumer).ok().unwrap();
+        let initial = composition.get_initial_state();
+        let ids = |action: &Channel| -> Vec<Option<String>> {
+            composition
+                .transitions(&initial, &HashSet::from([action.clone()]))
+                .into_iter()
+                .map(|transition| transition.edge.and_then(|edge| edge.id.clone()))
+                .collect()
+        };
+
+        assert_eq!(
+            ids(&send),
+            vec![Some(String::from("producer.send | consumer.receive"))]
+        );
+        assert_eq!(ids(&local), vec![Some(String::from("producer.tick"))]);
+        assert_eq!(
+            composition.successors(&initial, &HashSet::from([send])),
+            vec![initial]
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::automatom::{automaton::Automaton, edge::Edge, location::Location};
+
+use super::{trace::Trace, transition::Transition};
+
+/// The edges and locations of an automaton exercised by the recorded traces.
+#[derive(Debug, Clone)]
+pub struct Coverage<'a> {
+    automaton: &'a Automaton,
+    edges: HashSet<Edge>,
+    locations: HashSet<Location>,
+}
+
+impl<'a> Coverage<'a> {
+    pub fn new(automaton: &'a Automaton) -> Self {
+        Coverage {
+            automaton,
+            edges: HashSet::new(),
+            locations: HashSet::new(),
+        }
+    }
+
+    pub fn record(&mut self, trace: &Trace) {
+        self.record_location(&trace.get_initial_state().location);
+        for transition in trace.transitions() {
+            self.record_transition(transition);
+        }
+    }
+
+    /// Marks the edge of the transition and its locations as visited. Transitions
+    /// without an edge are attributed to the single edge of the automaton which
+    /// could have produced them. Returns false if no such edge was found.
+    pub fn record_transition(&mut self, transition: &Transition) -> bool {
+        self.record_location(&transition.source.location);
+        self.record_location(&transition.target.location);
+
+        let edge = match &transition.edge {
+            Some(edge) => self.automaton.get_edges().get(edge),
+            None => {
+                let mut candidates = self.automaton.get_edges().iter().filter(|edge| {
+                    edge.action.same_direction(&transition.action)
+                        && edge.enabled(&transition.source)
+                        && edge.execute(&transition.source).as_ref() == Ok(&transition.target)
+                });
+                match (candidates.next(), candidates.next()) {
+                    (Some(edge), None) => Some(edge),
+                    _ => None,
+                }
+            }
+        };
+        match edge {
+            Some(edge) => {
+                self.edges.insert(edge.clone());
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn record_location(&mut self, location: &Location) {
+        if self.automaton.get_locations().contains(location) {
+            self.locations.insert(location.clone());
+        }
+    }
+
+    pub fn visited_edges(&self) -> Vec<Edge> {
+        self.automaton
+            .edges_sorted()
+            .into_iter()
+            .filter(|edge| self.edges.contains(edge))
+            .collect()
+    }
+
+    pub fn unvisited_edges(&self) -> Vec<Edge> {
+        self.automaton
+            .edges_sorted()
+            .into_iter()
+            .filter(|edge| !self.edges.contains(edge))
+            .collect()
+    }
+
+    pub fn visited_locations(&self) -> &HashSet<Location> {
+        &self.locations
+    }
+
+    /// The fraction of edges visited, which is 1 for automata without edges.
+    pub fn edge_coverage(&self) -> f64 {
+        Self::fraction(self.edges.len(), self.automaton.get_edges().len())
+    }
+
+    /// The fraction of locations visited, which is 1 for automata without locations.
+    pub fn location_coverage(&self) -> f64 {
+        Self::fraction(self.locations.len(), self.automaton.get_locations().len())
+    }
+
+    fn fraction(visited: usize, total: usize) -> f64 {
+        if total == 0 {
+            1.0
+        } else {
+            visited as f64 / total as f64
+        }
+    }
+}
+
+impl Display for Coverage<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Locations: {}/{} ({:.1}%)\nEdges: {}/{} ({:.1}%)",
+            self.locations.len(),
+            self.automaton.get_locations().len(),
+            self.location_coverage() * 100.0,
+            self.edges.len(),
+            self.automaton.get_edges().len(),
+            self.edge_coverage() * 100.0
+        ))?;
+        let unvisited = self.unvisited_edges();
+        if !unvisited.is_empty() {
+            f.write_str("\nUnvisited:")?;
+            for edge in unvisited {
+                f.write_fmt(format_args!("\n  {}", edge))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        transition_system::{reachability::shortest_trace, state::State, transition::Transition},
+    };
+
+    use super::Coverage;
+
+    /// a -go!-> b -go!-> c, a -skip!-> c, c -back?-> a
+    fn new_fixture() -> (Automaton, [Edge; 4], [Location; 3]) {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let go = Channel::new_output("go");
+        let edge = |source: &Location, action: &Channel, target: &Location, id: &str| {
+            Edge::new(source, action, &Guard::new_true(), &Update::empty(), target).with_id(id)
+        };
+        let edges = [
+            edge(&a, &go, &b, "e0"),
+            edge(&b, &go, &c, "e1"),
+            edge(&a, &Channel::new_output("skip"), &c, "e2"),
+            edge(&c, &Channel::new_input("back"), &a, "e3"),
+        ];
+        let automaton = Automaton::new("fixture", &HashSet::from(edges.clone()), None)
+            .ok()
+            .unwrap();
+        (automaton, edges, [a, b, c])
+    }
+
+    #[test]
+    fn coverage_of_recorded_traces() {
+        let (automaton, [e0, e1, e2, e3], [_, b, c]) = new_fixture();
+        let actions = automaton.get_actions();
+        let to_b = shortest_trace(&automaton, actions, |state| state.location == b).unwrap();
+        let to_c = shortest_trace(&automaton, actions, |state| state.location == c).unwrap();
+
+        let mut coverage = Coverage::new(&automaton);
+        coverage.record(&to_b);
+        let after_first = coverage.location_coverage();
+        coverage.record(&to_c);
+
+        assert_eq!(after_first, 2.0 / 3.0);
+        assert_eq!(coverage.visited_edges(), vec![e0, e2]);
+        assert_eq!(coverage.unvisited_edges(), vec![e1, e3]);
+        assert_eq!(coverage.location_coverage(), 1.0);
+        assert_eq!(coverage.edge_coverage(), 0.5);
+        assert_eq!(
+            coverage.to_string(),
+            format!(
+                "Locations: 3/3 (100.0%)\nEdges: 2/4 (50.0%)\nUnvisited:\n  {}\n  {}",
+                coverage.unvisited_edges()[0],
+                coverage.unvisited_edges()[1]
+            )
+        );
+    }
+
+    #[test]
+    fn coverage_attributes_transitions_without_edges() {
+        let (automaton, [_, _, _, e3], [a, _, c]) = new_fixture();
+        let environment = automaton.get_initial_environment();
+        let back = Transition::new(
+            &State::new(&c, environment),
+            &Channel::new_input("back"),
+            &State::new(&a, environment),
+        );
+        let impossible = Transition::new(
+            &State::new(&a, environment),
+            &Channel::new_input("back"),
+            &State::new(&c, environment),
+        );
+
+        let mut coverage = Coverage::new(&automaton);
+        let attributed = coverage.record_transition(&back);
+        let unattributed = coverage.record_transition(&impossible);
+
+        assert!(attributed);
+        assert!(!unattributed);
+        assert_eq!(coverage.visited_edges(), vec![e3]);
+    }
+}
@@ -0,0 +1,39 @@
+use thiserror::Error;
+
+use super::{state::State, trace::Trace, transition::Transition};
+
+/// The states and transitions are boxed, such that results carrying the error
+/// stay small.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    #[error("The trace starts in {trace:} but the initial state is {actual:}")]
+    InitialStateMismatch {
+        trace: Box<State>,
+        actual: Box<State>,
+    },
+    #[error("Step {step:} of the trace is not enabled: {transition:}")]
+    TransitionNotEnabled {
+        step: usize,
+        transition: Box<Transition>,
+    },
+    #[error("Step {step:} of the trace does not leave the state the trace ends in: {transition:}")]
+    DisconnectedTransition {
+        step: usize,
+        transition: Box<Transition>,
+    },
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TestGenError {
+    #[error("The generated trace does not replay: {error:}\n{trace:}")]
+    TraceNotReplayable {
+        trace: Box<Trace>,
+        error: ReplayError,
+    },
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TraceFormatError {
+    #[error("Invalid trace at byte {position:}: {message:}")]
+    InvalidTrace { position: usize, message: String },
+}
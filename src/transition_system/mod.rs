@@ -1,3 +1,20 @@
+pub mod cached_transition_system;
+pub mod composition;
+pub mod coverage;
+pub mod error;
+pub mod model_checking;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod reachability;
+pub mod reachability_index;
+pub mod search_observer;
+pub mod simulation;
 pub mod state;
+pub mod testgen;
+pub mod trace;
+pub mod trace_json;
+pub mod transition;
+pub mod transition_iterator;
 pub mod transition_system;
 pub mod transition_system_breadth_first_search;
+pub mod transition_system_depth_first_search;
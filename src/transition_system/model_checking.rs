@@ -0,0 +1,360 @@
+use std::collections::HashSet;
+
+use crate::{
+    automatom::{channel::Channel, location::Location},
+    language::{expression::Expression, interpreter::Interpreter},
+};
+
+use super::{
+    reachability::shortest_trace, state::State, trace::Trace, transition::Transition,
+    transition_system::TransitionSystem,
+};
+
+/// A boolean combination of predicates over a single state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatePredicate {
+    /// Holds if the expression evaluates to true in the environment of the state.
+    /// States where it cannot be evaluated does not satisfy it.
+    Expression(Expression),
+    AtLocation(Location),
+    Not(Box<StatePredicate>),
+    And(Box<StatePredicate>, Box<StatePredicate>),
+    Or(Box<StatePredicate>, Box<StatePredicate>),
+}
+
+impl StatePredicate {
+    pub fn new_expression(expression: &Expression) -> Self {
+        StatePredicate::Expression(expression.clone())
+    }
+
+    pub fn new_at_location(location: &Location) -> Self {
+        StatePredicate::AtLocation(location.clone())
+    }
+
+    pub fn new_not(operand: &StatePredicate) -> Self {
+        StatePredicate::Not(Box::new(operand.clone()))
+    }
+
+    pub fn new_and(lhs: &StatePredicate, rhs: &StatePredicate) -> Self {
+        StatePredicate::And(Box::new(lhs.clone()), Box::new(rhs.clone()))
+    }
+
+    pub fn new_or(lhs: &StatePredicate, rhs: &StatePredicate) -> Self {
+        StatePredicate::Or(Box::new(lhs.clone()), Box::new(rhs.clone()))
+    }
+
+    pub fn holds(&self, state: &State) -> bool {
+        match self {
+            StatePredicate::Expression(expression) => {
+                let mut interpreter = Interpreter::new(&state.environment);
+                match interpreter.eval_expression(expression) {
+                    Ok(evaluation) => evaluation.is_true(),
+                    Err(_) => false,
+                }
+            }
+            StatePredicate::AtLocation(location) => state.location == *location,
+            StatePredicate::Not(operand) => !operand.holds(state),
+            StatePredicate::And(lhs, rhs) => lhs.holds(state) && rhs.holds(state),
+            StatePredicate::Or(lhs, rhs) => lhs.holds(state) || rhs.holds(state),
+        }
+    }
+}
+
+/// A state predicate under a single temporal operator. Paths are the maximal
+/// paths from the initial state, which may end in a deadlocked state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CtlFormula {
+    /// Some reachable state satisfies the predicate.
+    EF(StatePredicate),
+    /// Every reachable state satisfies the predicate.
+    AG(StatePredicate),
+    /// Some path satisfies the predicate in all of its states.
+    EG(StatePredicate),
+    /// Every path satisfies the predicate in some of its states.
+    AF(StatePredicate),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckResult {
+    /// The formula holds. Existential formulas carry the path witnessing it.
+    Satisfied { witness: Option<Trace> },
+    /// The formula does not hold. Universal formulas carry the path refuting it.
+    Violated { counterexample: Option<Trace> },
+}
+
+impl CheckResult {
+    pub fn holds(&self) -> bool {
+        matches!(self, CheckResult::Satisfied { .. })
+    }
+}
+
+pub fn check<TS: TransitionSystem>(
+    ts: &TS,
+    actions: &HashSet<Channel>,
+    formula: &CtlFormula,
+) -> CheckResult {
+    match formula {
+        CtlFormula::EF(predicate) => {
+            match shortest_trace(ts, actions, |state| predicate.holds(state)) {
+                Some(witness) => CheckResult::Satisfied {
+                    witness: Some(witness),
+                },
+                None => CheckResult::Violated {
+                    counterexample: None,
+                },
+            }
+        }
+        CtlFormula::AG(predicate) => {
+            match shortest_trace(ts, actions, |state| !predicate.holds(state)) {
+                Some(counterexample) => CheckResult::Violated {
+                    counterexample: Some(counterexample),
+                },
+                None => CheckResult::Satisfied { witness: None },
+            }
+        }
+        CtlFormula::EG(predicate) => match globally_path(ts, actions, predicate) {
+            Some(witness) => CheckResult::Satisfied {
+                witness: Some(witness),
+            },
+            None => CheckResult::Violated {
+                counterexample: None,
+            },
+        },
+        CtlFormula::AF(predicate) => {
+            match globally_path(ts, actions, &StatePredicate::new_not(predicate)) {
+                Some(counterexample) => CheckResult::Violated {
+                    counterexample: Some(counterexample),
+                },
+                None => CheckResult::Satisfied { witness: None },
+            }
+        }
+    }
+}
+
+/// Finds a maximal path on which the predicate always holds, that is a path
+/// through satisfying states which either closes a cycle or ends in a deadlock.
+fn globally_path<TS: TransitionSystem>(
+    ts: &TS,
+    actions: &HashSet<Channel>,
+    predicate: &StatePredicate,
+) -> Option<Trace> {
+    let initial = ts.get_initial_state();
+    if !predicate.holds(&initial) {
+        return None;
+    }
+
+    let mut visited: Vec<State> = vec![initial.clone()];
+    let mut path: Vec<Transition> = Vec::new();
+    // Each frame holds the satisfying transitions of a state on the path not yet taken
+    let mut stack: Vec<Vec<Transition>> = Vec::new();
+    let mut current = initial.clone();
+
+    loop {
+        let transitions = ts.timed_transitions(&current, actions);
+        if transitions.is_empty() {
+            return Some(new_trace(&initial, &path));
+        }
+
+        let mut pending: Vec<Transition> = transitions
+            .into_iter()
+            .filter(|transition| predicate.holds(&transition.target))
+            .collect();
+        pending.reverse();
+        stack.push(pending);
+
+        // Take the next untried transition, backtracking when a state has none left
+        loop {
+            let frame = stack.last_mut()?;
+            match frame.pop() {
+                Some(transition) => {
+                    let on_path = transition.target == initial
+                        || path.iter().any(|taken| taken.target == transition.target);
+                    if on_path {
+                        path.push(transition);
+                        return Some(new_trace(&initial, &path));
+                    }
+                    if !visited.contains(&transition.target) {
+                        visited.push(transition.target.clone());
+                        current = transition.target.clone();
+                        path.push(transition);
+                        break;
+                    }
+                }
+                None => {
+                    stack.pop();
+                    path.pop();
+                }
+            }
+        }
+    }
+}
+
+fn new_trace(initial: &State, transitions: &[Transition]) -> Trace {
+    let mut trace = Trace::new(initial);
+    for transition in transitions {
+        trace
+            .push(transition)
+            .expect("every transition of the path leaves the target of the one before it");
+    }
+    trace
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        language::{
+            environment::Environment, expression::Expression, statement::Statement, value::Value,
+        },
+    };
+
+    use super::{check, CheckResult, CtlFormula, StatePredicate};
+
+    /// a -in?-> b [x = true] -out!-> c -out!-> c, a -out!-> d
+    fn new_fixture() -> (Automaton, [Location; 4]) {
+        let invariant = &Invariant::new_true();
+        let channel_in = Channel::new_input("in");
+        let channel_out = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let d = Location::new_normal("d", invariant);
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_false());
+        let set_x = Update::new(&Statement::new_simple_assignment("x", &Value::new_true()));
+        let guard = Guard::new_true();
+        let edges = HashSet::from([
+            Edge::new(&a, &channel_in, &guard, &set_x, &b),
+            Edge::new(&b, &channel_out, &guard, &Update::empty(), &c),
+            Edge::new_loop(&c, &channel_out, &guard, &Update::empty()),
+            Edge::new(&a, &channel_out, &guard, &Update::empty(), &d),
+        ]);
+        let automaton = Automaton::new("fixture", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+        (automaton, [a, b, c, d])
+    }
+
+    fn x() -> StatePredicate {
+        StatePredicate::new_expression(&Expression::new_identifier("x"))
+    }
+
+    #[test]
+    fn model_checking_exists_finally() {
+        let (automaton, [_, b, _, _]) = new_fixture();
+        let actions = automaton.get_actions();
+
+        let result = check(&automaton, actions, &CtlFormula::EF(x()));
+
+        match result {
+            CheckResult::Satisfied {
+                witness: Some(witness),
+            } => assert_eq!(witness.last_state().location, b),
+            _ => panic!("expected a witness, got {:?}", result),
+        }
+        let unreachable = Location::new_normal("unreachable", &Invariant::new_true());
+        let at_unreachable = StatePredicate::new_at_location(&unreachable);
+        assert!(!check(&automaton, actions, &CtlFormula::EF(at_unreachable)).holds());
+    }
+
+    #[test]
+    fn model_checking_always_globally_counterexample() {
+        let (automaton, [a, b, _, _]) = new_fixture();
+        let actions = automaton.get_actions();
+        let not_x = StatePredicate::new_not(&x());
+
+        let result = check(&automaton, actions, &CtlFormula::AG(not_x));
+
+        match result {
+            CheckResult::Violated {
+                counterexample: Some(counterexample),
+            } => {
+                assert_eq!(counterexample.len(), 1);
+                assert_eq!(counterexample.get_initial_state().location, a);
+                assert_eq!(counterexample.last_state().location, b);
+                assert!(counterexample.replay(&automaton).is_ok());
+            }
+            _ => panic!("expected a counterexample, got {:?}", result),
+        }
+        let x_or_not_x = StatePredicate::new_or(&x(), &StatePredicate::new_not(&x()));
+        assert!(check(&automaton, actions, &CtlFormula::AG(x_or_not_x)).holds());
+    }
+
+    #[test]
+    fn model_checking_exists_globally() {
+        let (automaton, [a, b, c, d]) = new_fixture();
+        let actions = automaton.get_actions();
+        // Either stay without x by deadlocking in d, or loop in c after setting x
+        let not_b = StatePredicate::new_not(&StatePredicate::new_at_location(&b));
+        let not_d = StatePredicate::new_not(&StatePredicate::new_at_location(&d));
+        let not_a = StatePredicate::new_not(&StatePredicate::new_at_location(&a));
+
+        let deadlock = check(&automaton, actions, &CtlFormula::EG(not_b));
+        let cycle = check(&automaton, actions, &CtlFormula::EG(not_d.clone()));
+        let never = check(&automaton, actions, &CtlFormula::EG(not_a));
+
+        match deadlock {
+            CheckResult::Satisfied {
+                witness: Some(witness),
+            } => assert_eq!(witness.last_state().location, d),
+            _ => panic!("expected a witness, got {:?}", deadlock),
+        }
+        match cycle {
+            CheckResult::Satisfied {
+                witness: Some(witness),
+            } => {
+                assert_eq!(witness.last_state().location, c);
+                assert_eq!(witness.len(), 3);
+            }
+            _ => panic!("expected a witness, got {:?}", cycle),
+        }
+        assert!(!never.holds());
+    }
+
+    #[test]
+    fn model_checking_always_finally() {
+        let (automaton, [_, b, c, d]) = new_fixture();
+        let actions = automaton.get_actions();
+        let at_c_or_d = StatePredicate::new_or(
+            &StatePredicate::new_at_location(&c),
+            &StatePredicate::new_at_location(&d),
+        );
+        let at_b = StatePredicate::new_at_location(&b);
+
+        let holds = check(&automaton, actions, &CtlFormula::AF(at_c_or_d));
+        let fails = check(&automaton, actions, &CtlFormula::AF(at_b));
+
+        assert_eq!(holds, CheckResult::Satisfied { witness: None });
+        match fails {
+            CheckResult::Violated {
+                counterexample: Some(counterexample),
+            } => assert_eq!(counterexample.last_state().location, d),
+            _ => panic!("expected a counterexample, got {:?}", fails),
+        }
+    }
+
+    #[test]
+    fn model_checking_always_finally_lets_time_pass() {
+        let automaton = Automaton::parse(
+            "var x = 0\nlocation a init invariant x <= 3\nlocation b\na -go! when x >= 2-> b",
+        )
+        .ok()
+        .unwrap();
+        let b = automaton
+            .get_locations()
+            .iter()
+            .find(|location| location.get_name() == "b")
+            .unwrap()
+            .clone();
+        let at_b = StatePredicate::new_at_location(&b);
+
+        let eventually = check(&automaton, automaton.get_actions(), &CtlFormula::AF(at_b));
+
+        assert_eq!(eventually, CheckResult::Satisfied { witness: None });
+    }
+}
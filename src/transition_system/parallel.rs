@@ -0,0 +1,113 @@
+use std::{
+    collections::HashSet,
+    sync::Mutex,
+    thread::{available_parallelism, scope},
+};
+
+use crate::automatom::channel::Channel;
+
+use super::{state::State, transition_system::TransitionSystem};
+
+/// Collects the states reachable by the actions by expanding each breadth-first
+/// layer on all available threads, deduplicating through a shared set.
+pub fn reachable_states<TS: TransitionSystem + Sync>(
+    ts: &TS,
+    actions: &HashSet<Channel>,
+) -> HashSet<State> {
+    let threads = available_parallelism().map_or(1, |count| count.get());
+    let initial = ts.get_initial_state();
+    let visited = Mutex::new(HashSet::from([initial.clone()]));
+    let mut frontier = vec![initial];
+
+    while !frontier.is_empty() {
+        let chunk_size = frontier.len().div_ceil(threads);
+        frontier = scope(|scope| {
+            let workers: Vec<_> = frontier
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let visited = &visited;
+                    scope.spawn(move || {
+                        let mut discovered = Vec::new();
+                        for state in chunk {
+                            let mut successors = ts.successors(state, actions);
+                            successors.extend(ts.delay_successors(state));
+                            for successor in successors {
+                                if visited.lock().unwrap().insert(successor.clone()) {
+                                    discovered.push(successor);
+                                }
+                            }
+                        }
+                        discovered
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        });
+    }
+
+    visited.into_inner().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        language::{environment::Environment, statement::Statement, value::Value},
+        transition_system::{state::State, transition_system::TransitionSystem},
+    };
+
+    use super::reachable_states;
+
+    #[test]
+    fn parallel_reachable_states_equal_sequential() {
+        let invariant = &Invariant::new_true();
+        let channel_in = Channel::new_input("in");
+        let channel_out = Channel::new_output("out");
+        let locations: Vec<Location> = (0..8)
+            .map(|index| match index {
+                0 => Location::new_initial("l0", invariant),
+                _ => Location::new_normal(&format!("l{}", index), invariant),
+            })
+            .collect();
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_false());
+        let update = Update::new(&Statement::new_simple_assignment("x", &Value::new_true()));
+        let mut edges = HashSet::new();
+        for pair in locations.windows(2) {
+            edges.insert(Edge::new(
+                &pair[0],
+                &channel_in,
+                &Guard::new_true(),
+                &Update::empty(),
+                &pair[1],
+            ));
+            edges.insert(Edge::new(
+                &pair[0],
+                &channel_out,
+                &Guard::new_true(),
+                &update,
+                &pair[1],
+            ));
+        }
+        let automaton = Automaton::new("chain", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+
+        let parallel = reachable_states(&automaton, automaton.get_actions());
+
+        let sequential: HashSet<State> = automaton
+            .reachable_states(automaton.get_actions())
+            .into_iter()
+            .collect();
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel.len(), 15);
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::{
+    automatom::{channel::Channel, location::Location},
+    language::{environment::Environment, expression::Expression, interpreter::Interpreter},
+};
+
+use super::{
+    state::State, trace::Trace, transition_system::TransitionSystem,
+    transition_system_breadth_first_search::TransitionSystemBreadthFirstSearch,
+};
+
+/// Finds a shortest trace from the initial state to a state satisfying the goal.
+pub fn shortest_trace<TS: TransitionSystem>(
+    ts: &TS,
+    actions: &HashSet<Channel>,
+    goal: impl Fn(&State) -> bool,
+) -> Option<Trace> {
+    let mut search = TransitionSystemBreadthFirstSearch::new(actions, ts);
+    // States are visited in order of their depth so the first match is the closest
+    let state = search.by_ref().find(|state| goal(state))?;
+    search.path_to(&state)
+}
+
+/// Every state reachable by the actions of the system which satisfies the
+/// predicate, in the order they are found by a breadth first search.
+pub fn filter_reachable<TS: TransitionSystem, F: Fn(&State) -> bool>(
+    ts: &TS,
+    pred: F,
+) -> Vec<State> {
+    TransitionSystemBreadthFirstSearch::new(ts.get_actions(), ts)
+        .filter(|state| pred(state))
+        .collect()
+}
+
+pub fn trace_to_location<TS: TransitionSystem>(
+    ts: &TS,
+    actions: &HashSet<Channel>,
+    location: &Location,
+) -> Option<Trace> {
+    shortest_trace(ts, actions, |state| state.location == *location)
+}
+
+/// Finds a shortest trace to a state whose environment satisfies the expression.
+/// States where the expression cannot be evaluated does not satisfy it.
+pub fn trace_where_expression_holds<TS: TransitionSystem>(
+    ts: &TS,
+    actions: &HashSet<Channel>,
+    expression: &Expression,
+) -> Option<Trace> {
+    shortest_trace(ts, actions, |state| {
+        let mut interpreter = Interpreter::new(&state.environment);
+        match interpreter.eval_expression(expression) {
+            Ok(evaluation) => evaluation.is_true(),
+            Err(_) => false,
+        }
+    })
+}
+
+/// The states reachable in `a` but not in `b`, and those reachable in `b` but
+/// not in `a`, each by the actions of its own system.
+pub fn state_space_diff(
+    a: &impl TransitionSystem,
+    b: &impl TransitionSystem,
+) -> (Vec<State>, Vec<State>) {
+    let a_states = a.reachable_states(a.get_actions());
+    let b_states = b.reachable_states(b.get_actions());
+    let a_set: HashSet<&State> = a_states.iter().collect();
+    let b_set: HashSet<&State> = b_states.iter().collect();
+    // Filtering the ordered states rather than the sets keeps the order of discovery
+    let only_in = |states: &Vec<State>, others: &HashSet<&State>| -> Vec<State> {
+        states
+            .iter()
+            .filter(|state| !others.contains(state))
+            .cloned()
+            .collect()
+    };
+    (only_in(&a_states, &b_set), only_in(&b_states, &a_set))
+}
+
+/// Writes the reachable states as CSV with a location column followed by a
+/// column for every variable bound in any of the states, sorted by name. Values
+/// are left blank in states where the variable is unbound.
+pub fn states_to_csv<TS: TransitionSystem>(ts: &TS) -> String {
+    let empty = Environment::new_empty();
+    let rows: Vec<(String, Vec<(String, String)>)> = ts
+        .reachable_states(ts.get_actions())
+        .iter()
+        .map(|state| {
+            let values = state
+                .environment
+                .differences(&empty)
+                .into_iter()
+                .filter_map(|(identifier, value, _)| Some((identifier, value?.to_string())))
+                .collect();
+            (state.location.get_name(), values)
+        })
+        .collect();
+    let variables: BTreeSet<&String> = rows
+        .iter()
+        .flat_map(|(_, values)| values.iter().map(|(identifier, _)| identifier))
+        .collect();
+
+    let escape = |field: &str| -> String {
+        if field.contains([',', '"', '\n']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            String::from(field)
+        }
+    };
+    let mut csv = std::iter::once("location")
+        .chain(variables.iter().map(|variable| variable.as_str()))
+        .map(escape)
+        .collect::<Vec<String>>()
+        .join(",");
+    for (location, values) in rows.iter() {
+        let mut fields = vec![escape(location)];
+        for variable in variables.iter() {
+            let value = values
+                .iter()
+                .find(|(identifier, _)| identifier == *variable)
+                .map_or("", |(_, value)| value.as_str());
+            fields.push(escape(value));
+        }
+        csv.push('\n');
+        csv.push_str(&fields.join(","));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, fixtures::DIRECTED_DIAMOND,
+            guard::Guard, invariant::Invariant, location::Location, update::Update,
+        },
+        language::{
+            environment::Environment, expression::Expression, statement::Statement, value::Value,
+        },
+        transition_system::{state::State, transition_system::TransitionSystem},
+    };
+
+    use super::{
+        filter_reachable, shortest_trace, state_space_diff, states_to_csv, trace_to_location,
+        trace_where_expression_holds,
+    };
+
+    #[test]
+    fn reachability_trace_to_location_full_system() {
+        let automaton = Automaton::parse(DIRECTED_DIAMOND).ok().unwrap();
+        let f = Location::new_normal("f", &Invariant::new_true());
+
+        let trace = trace_to_location(&automaton, automaton.get_actions(), &f).unwrap();
+
+        assert_eq!(trace.len(), 2);
+        assert_eq!(trace.last_state().location, f);
+        assert_eq!(trace.replay(&automaton), Ok(()));
+    }
+
+    #[test]
+    fn reachability_filter_reachable_counts_flagged_states() {
+        let automaton = Automaton::parse(
+            "var flag = false\nvar other = false\nlocation a init\nlocation b\nlocation c\n\
+             a -left! do flag = true-> b\na -right! do other = true-> c\n\
+             b -swap? do other = flag; flag = false-> c\nc -raise? do flag = true-> a",
+        )
+        .ok()
+        .unwrap();
+        let flagged =
+            |state: &State| state.environment.get_value("flag") == Some(&Value::new_true());
+
+        let states = filter_reachable(&automaton, flagged);
+
+        let all = automaton.reachable_states(automaton.get_actions());
+        assert_eq!(states.len(), 4);
+        assert_eq!(
+            states.len(),
+            all.iter().filter(|state| flagged(state)).count()
+        );
+        assert!(states.iter().all(flagged));
+    }
+
+    #[test]
+    fn reachability_trace_where_expression_holds() {
+        let invariant = &Invariant::new_true();
+        let channel = Channel::new_output("out");
+        let channels = HashSet::from([channel.clone()]);
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_false());
+        let set = Update::new(&Statement::new_simple_assignment("x", &Value::new_true()));
+        let edges = HashSet::from([
+            Edge::new(&a, &channel, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new_loop(&b, &channel, &Guard::new_true(), &set),
+        ]);
+        let automaton = Automaton::new("automaton", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+
+        let trace =
+            trace_where_expression_holds(&automaton, &channels, &Expression::new_identifier("x"))
+                .unwrap();
+
+        assert_eq!(trace.len(), 2);
+        assert_eq!(
+            trace.last_state().environment.get_value("x"),
+            Some(&Value::new_true())
+        );
+        assert_eq!(trace.replay(&automaton), Ok(()));
+    }
+
+    #[test]
+    fn reachability_shortest_trace_unreachable_goal() {
+        let location = Location::new_initial("a", &Invariant::new_true());
+        let channel = Channel::new_output("out");
+        let edges = HashSet::from([Edge::new_loop(
+            &location,
+            &channel,
+            &Guard::new_true(),
+            &Update::empty(),
+        )]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+
+        let trace = shortest_trace(&automaton, &HashSet::from([channel]), |_| false);
+
+        assert_eq!(trace, None);
+    }
+
+    #[test]
+    fn reachability_state_space_diff_one_new_state() {
+        let invariant = &Invariant::new_true();
+        let channel = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let a_b = Edge::new(&a, &channel, &Guard::new_true(), &Update::empty(), &b);
+        let b_c = Edge::new(&b, &channel, &Guard::new_true(), &Update::empty(), &c);
+        let before = Automaton::new("before", &HashSet::from([a_b.clone()]), None)
+            .ok()
+            .unwrap();
+        let after = Automaton::new("after", &HashSet::from([a_b, b_c]), None)
+            .ok()
+            .unwrap();
+
+        let (removed, added) = state_space_diff(&before, &after);
+
+        assert!(removed.is_empty());
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].location, c);
+    }
+
+    #[test]
+    fn reachability_states_to_csv() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let mut environment = Environment::new_empty();
+        environment.insert("y", &Value::new_false());
+        environment.insert("x", &Value::new_false());
+        let set_x = Update::new(&Statement::new_simple_assignment("x", &Value::new_true()));
+        let edges = HashSet::from([
+            Edge::new(
+                &a,
+                &Channel::new_output("set"),
+                &Guard::new_true(),
+                &set_x,
+                &b,
+            ),
+            Edge::new(
+                &a,
+                &Channel::new_output("skip"),
+                &Guard::new_true(),
+                &Update::empty(),
+                &b,
+            ),
+        ]);
+        let automaton = Automaton::new("csv", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+
+        let csv = states_to_csv(&automaton);
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "location,x,y");
+        assert_eq!(
+            lines.len() - 1,
+            automaton.reachable_states(automaton.get_actions()).len()
+        );
+        assert_eq!(lines.len() - 1, 3);
+        assert!(lines.contains(&"b,true,false"));
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::automatom::channel::Channel;
+
+use super::{
+    state::State, transition_iterator::TransitionIterator, transition_system::TransitionSystem,
+};
+
+/// The reachable state graph of a transition system explored once, such that
+/// successor and predecessor queries are lookups rather than searches.
+#[derive(Debug, Clone, Default)]
+pub struct ReachabilityIndex {
+    forward: HashMap<State, Vec<(Channel, State)>>,
+    reverse: HashMap<State, Vec<(Channel, State)>>,
+}
+
+impl ReachabilityIndex {
+    pub fn build<TS: TransitionSystem>(ts: &TS, actions: &HashSet<Channel>) -> Self {
+        let mut index = ReachabilityIndex::default();
+        let initial = ts.get_initial_state();
+        index.forward.entry(initial.clone()).or_default();
+        index.reverse.entry(initial).or_default();
+
+        for transition in TransitionIterator::new(actions, ts) {
+            index.forward.entry(transition.target.clone()).or_default();
+            index.reverse.entry(transition.source.clone()).or_default();
+            index
+                .forward
+                .entry(transition.source.clone())
+                .or_default()
+                .push((transition.action.clone(), transition.target.clone()));
+            index
+                .reverse
+                .entry(transition.target)
+                .or_default()
+                .push((transition.action, transition.source));
+        }
+        index
+    }
+
+    pub fn is_reachable(&self, state: &State) -> bool {
+        self.forward.contains_key(state)
+    }
+
+    pub fn states(&self) -> impl Iterator<Item = &State> {
+        self.forward.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.forward.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.forward.is_empty()
+    }
+
+    /// The outgoing steps of the state, empty if the state is not reachable.
+    pub fn outgoing(&self, state: &State) -> &[(Channel, State)] {
+        self.forward.get(state).map_or(&[], Vec::as_slice)
+    }
+
+    /// The ingoing steps of the state, empty if the state is not reachable.
+    pub fn ingoing(&self, state: &State) -> &[(Channel, State)] {
+        self.reverse.get(state).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn successors(&self, state: &State) -> Vec<State> {
+        Self::distinct(self.outgoing(state), |_| true)
+    }
+
+    pub fn predecessors(&self, state: &State) -> Vec<State> {
+        Self::distinct(self.ingoing(state), |_| true)
+    }
+
+    /// The predecessors which step to the state by one of the actions.
+    pub fn predecessors_via(&self, state: &State, actions: &HashSet<Channel>) -> Vec<State> {
+        Self::distinct(self.ingoing(state), |action| {
+            actions
+                .iter()
+                .any(|other| other == action && other.same_direction(action))
+        })
+    }
+
+    fn distinct(steps: &[(Channel, State)], keep: impl Fn(&Channel) -> bool) -> Vec<State> {
+        let mut result: Vec<State> = Vec::new();
+        for (action, state) in steps {
+            if keep(action) && !result.contains(state) {
+                result.push(state.clone());
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, collections::HashSet};
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        transition_system::{state::State, transition_system::TransitionSystem},
+    };
+
+    use super::ReachabilityIndex;
+
+    /// Delegates to an automaton while counting how many states are expanded.
+    struct Counting<'a> {
+        automaton: &'a Automaton,
+        expansions: Cell<usize>,
+    }
+
+    impl TransitionSystem for Counting<'_> {
+        fn predecessors_via(
+            &self,
+            state: &State,
+            last_actions: &HashSet<Channel>,
+            reachability_actions: &HashSet<Channel>,
+        ) -> Vec<State> {
+            self.automaton
+                .predecessors_via(state, last_actions, reachability_actions)
+        }
+
+        fn successors(&self, state: &State, actions: &HashSet<Channel>) -> Vec<State> {
+            self.expansions.set(self.expansions.get() + 1);
+            self.automaton.successors(state, actions)
+        }
+
+        fn get_initial_state(&self) -> State {
+            self.automaton.get_initial_state()
+        }
+
+        fn get_actions(&self) -> &HashSet<Channel> {
+            self.automaton.get_actions()
+        }
+
+        fn get_inputs(&self) -> &HashSet<Channel> {
+            self.automaton.get_inputs()
+        }
+
+        fn get_outputs(&self) -> &HashSet<Channel> {
+            self.automaton.get_outputs()
+        }
+    }
+
+    fn new_diamond() -> (Automaton, [Location; 4]) {
+        let invariant = &Invariant::new_true();
+        let channel_in = Channel::new_input("in");
+        let channel_out = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let d = Location::new_normal("d", invariant);
+        let guard = Guard::new_true();
+        let update = Update::empty();
+        let edges = HashSet::from([
+            Edge::new(&a, &channel_in, &guard, &update, &b),
+            Edge::new(&a, &channel_out, &guard, &update, &c),
+            Edge::new(&b, &channel_out, &guard, &update, &d),
+            Edge::new(&c, &channel_in, &guard, &update, &d),
+        ]);
+        let automaton = Automaton::new("diamond", &edges, None).ok().unwrap();
+        (automaton, [a, b, c, d])
+    }
+
+    #[test]
+    fn reachability_index_agrees_with_transition_system() {
+        let (automaton, [a, b, c, d]) = new_diamond();
+        let environment = automaton.get_initial_environment();
+        let state = |location: &Location| State::new(location, environment);
+
+        let index = ReachabilityIndex::build(&automaton, automaton.get_actions());
+
+        assert_eq!(index.len(), 4);
+        assert!(index.is_reachable(&state(&d)));
+        assert_eq!(index.successors(&state(&a)).len(), 2);
+        assert_eq!(index.predecessors(&state(&a)).len(), 0);
+        assert_eq!(index.predecessors(&state(&d)).len(), 2);
+        assert_eq!(
+            index.predecessors_via(&state(&d), automaton.get_inputs()),
+            vec![state(&c)]
+        );
+        assert_eq!(
+            index.predecessors_via(&state(&d), automaton.get_outputs()),
+            vec![state(&b)]
+        );
+        assert_eq!(
+            automaton.input_predecessors(&state(&d)),
+            automaton.input_predecessors_with(&state(&d), Some(&index))
+        );
+        assert!(!index.is_reachable(&State::new(
+            &Location::new_normal("unknown", &Invariant::new_true()),
+            environment
+        )));
+    }
+
+    #[test]
+    fn reachability_index_repeated_queries_do_not_explore() {
+        let (automaton, [_, _, _, d]) = new_diamond();
+        let counting = Counting {
+            automaton: &automaton,
+            expansions: Cell::new(0),
+        };
+        let target = State::new(&d, automaton.get_initial_environment());
+
+        let index = ReachabilityIndex::build(&counting, automaton.get_actions());
+        let expansions_to_build = counting.expansions.get();
+        for _ in 0..100 {
+            counting.input_predecessors_with(&target, Some(&index));
+            counting.output_predecessors_with(&target, Some(&index));
+        }
+
+        // Each state is expanded once per action while building and never again
+        assert_eq!(expansions_to_build, 4 * 2);
+        assert_eq!(counting.expansions.get(), expansions_to_build);
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use super::{state::State, transition::Transition};
+
+/// Callbacks invoked by the searches while they explore, e.g. to report progress.
+pub trait SearchObserver {
+    /// Called once for every state the search yields, along with its depth.
+    fn on_state_visited(&mut self, _state: &State, _depth: usize) {}
+    /// Called with the number of states waiting to be explored after each expansion.
+    fn on_frontier_size(&mut self, _size: usize) {}
+    /// Called for every transition the breadth-first search checks for an
+    /// undiscovered target.
+    fn on_transition_traversed(&mut self, _transition: &Transition) {}
+}
+
+/// The observer used when none is given, which ignores every callback.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoObserver;
+
+impl SearchObserver for NoObserver {}
+
+/// A flag shared between a search and whoever wants to stop it. Cancelling any
+/// clone of the token cancels them all.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Reported alike by the breadth- and depth-first searches when they stop
+/// because their cancellation token was cancelled, along with the number of
+/// states they had visited by then.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchInterrupted {
+    pub states: usize,
+}
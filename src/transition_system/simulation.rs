@@ -0,0 +1,452 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{automatom::channel::Channel, language::environment::Environment};
+
+use super::{
+    state::State, trace::Trace, transition::Transition, transition_system::TransitionSystem,
+};
+
+/// Pairs of concrete and abstract states where the abstract state can match
+/// every step of the concrete state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationRelation {
+    pairs: HashSet<(State, State)>,
+}
+
+impl SimulationRelation {
+    pub fn contains(&self, concrete: &State, abstract_state: &State) -> bool {
+        self.pairs
+            .contains(&(concrete.clone(), abstract_state.clone()))
+    }
+
+    /// The abstract states which simulate the concrete state.
+    pub fn covering(&self, concrete: &State) -> Vec<&State> {
+        self.pairs
+            .iter()
+            .filter(|(related, _)| related == concrete)
+            .map(|(_, abstract_state)| abstract_state)
+            .collect()
+    }
+
+    pub fn pairs(&self) -> &HashSet<(State, State)> {
+        &self.pairs
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+/// A concrete state with an action the abstraction cannot match, along with
+/// the concrete trace leading to it from the initial state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationFailure {
+    pub concrete_state: State,
+    pub action: Channel,
+    pub trace: Trace,
+}
+
+/// Computes the largest simulation relation between the reachable states of
+/// the concrete and abstract systems. Succeeds if it relates the initial states.
+pub fn simulates(
+    abstract_ts: &impl TransitionSystem,
+    concrete_ts: &impl TransitionSystem,
+) -> Result<SimulationRelation, Box<SimulationFailure>> {
+    let concrete_states = concrete_ts.reachable_states(concrete_ts.get_actions());
+    let abstract_states = abstract_ts.reachable_states(abstract_ts.get_actions());
+
+    let mut concrete_transitions: HashMap<State, Vec<Transition>> = HashMap::new();
+    for state in concrete_states.iter() {
+        let transitions = concrete_ts.timed_transitions(state, concrete_ts.get_actions());
+        concrete_transitions.insert(state.clone(), transitions);
+    }
+    let mut abstract_steps: HashMap<(State, Channel), Vec<State>> = HashMap::new();
+    let mut abstract_step = |state: &State, action: &Channel| -> Vec<State> {
+        abstract_steps
+            .entry((state.clone(), action.clone()))
+            .or_insert_with(|| abstract_ts.timed_step(state, action))
+            .clone()
+    };
+
+    let mut relation: HashSet<(State, State)> = HashSet::new();
+    for concrete in concrete_states.iter() {
+        for abstract_state in abstract_states.iter() {
+            relation.insert((concrete.clone(), abstract_state.clone()));
+        }
+    }
+
+    // Pairs are removed in rounds, remembering the concrete transition which
+    // could not be matched. Every match of it was removed in an earlier round.
+    let mut removals: HashMap<(State, State), (usize, Transition)> = HashMap::new();
+    for round in 0.. {
+        let mut removed: Vec<((State, State), Transition)> = Vec::new();
+        for (concrete, abstract_state) in relation.iter() {
+            let unmatched = concrete_transitions[concrete].iter().find(|transition| {
+                !abstract_step(abstract_state, &transition.action)
+                    .into_iter()
+                    .any(|target| relation.contains(&(transition.target.clone(), target)))
+            });
+            if let Some(transition) = unmatched {
+                removed.push((
+                    (concrete.clone(), abstract_state.clone()),
+                    transition.clone(),
+                ));
+            }
+        }
+        if removed.is_empty() {
+            break;
+        }
+        for (pair, transition) in removed {
+            relation.remove(&pair);
+            removals.insert(pair, (round, transition));
+        }
+    }
+
+    let concrete_initial = concrete_ts.get_initial_state();
+    let abstract_initial = abstract_ts.get_initial_state();
+    if relation.contains(&(concrete_initial.clone(), abstract_initial.clone())) {
+        return Ok(SimulationRelation { pairs: relation });
+    }
+
+    // Follow the unmatched transitions until the abstraction cannot take the action at all
+    let mut trace = Trace::new(&concrete_initial);
+    let mut pair = (concrete_initial, abstract_initial);
+    loop {
+        let (_, transition) = &removals[&pair];
+        let matches = abstract_step(&pair.1, &transition.action);
+        let next = matches
+            .into_iter()
+            .map(|target| (transition.target.clone(), target))
+            .min_by_key(|next| removals[next].0);
+        match next {
+            Some(next) => {
+                trace
+                    .push(transition)
+                    .expect("every unmatched transition leaves the concrete state of its pair");
+                pair = next;
+            }
+            None => {
+                return Err(Box::new(SimulationFailure {
+                    concrete_state: pair.0,
+                    action: transition.action.clone(),
+                    trace,
+                }))
+            }
+        }
+    }
+}
+
+/// Weighs the transitions a random walk chooses between.
+pub type WalkPolicy<'a> = Box<dyn Fn(&Transition) -> u32 + 'a>;
+
+/// A walk through the transition system from its initial state, taking a
+/// randomly chosen transition or delay in every step. The walk ends when it reaches a
+/// state without transitions or after the maximum number of steps. A fixed seed
+/// always yields the same walk.
+pub struct RandomWalk<'a, TS: TransitionSystem> {
+    ts: &'a TS,
+    actions: HashSet<Channel>,
+    state: State,
+    random: u64,
+    steps: usize,
+    max_steps: Option<usize>,
+    policy: Option<WalkPolicy<'a>>,
+}
+
+impl<'a, TS: TransitionSystem> RandomWalk<'a, TS> {
+    pub fn new(ts: &'a TS, actions: &HashSet<Channel>, seed: u64) -> Self {
+        RandomWalk {
+            ts,
+            actions: actions.clone(),
+            state: ts.get_initial_state(),
+            random: seed,
+            steps: 0,
+            max_steps: None,
+            policy: None,
+        }
+    }
+
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Weighs the transitions such that each is taken with a probability
+    /// proportional to its weight instead of uniformly, e.g. to prefer outputs
+    /// over inputs. Transitions weighing zero are never taken, unless every
+    /// transition of the state weighs zero, in which case one is chosen
+    /// uniformly rather than ending the walk in a state which has transitions.
+    pub fn with_policy(mut self, policy: impl Fn(&Transition) -> u32 + 'a) -> Self {
+        self.policy = Some(Box::new(policy));
+        self
+    }
+
+    pub fn get_state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn get_steps(&self) -> usize {
+        self.steps
+    }
+
+    /// The next number of the SplitMix64 generator.
+    fn next_random(&mut self) -> u64 {
+        self.random = self.random.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.random;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl<TS: TransitionSystem> Iterator for RandomWalk<'_, TS> {
+    type Item = Transition;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self
+            .max_steps
+            .is_some_and(|max_steps| self.steps >= max_steps)
+        {
+            return None;
+        }
+
+        // The transitions come in arbitrary order, so sort them for the seed to determine the walk
+        let mut transitions = self.ts.timed_transitions(&self.state, &self.actions);
+        let empty = Environment::new_empty();
+        transitions.sort_by_cached_key(|transition| {
+            (
+                transition.action.name().to_string(),
+                transition.action.is_output(),
+                transition.target.location.get_name(),
+                format!("{:?}", transition.target.environment.differences(&empty)),
+                transition.edge.as_ref().map(|edge| edge.to_string()),
+            )
+        });
+
+        if transitions.is_empty() {
+            return None;
+        }
+        // Summed as u128 such that no number of maximal weights can overflow
+        let mut weights: Vec<u128> = transitions
+            .iter()
+            .map(|transition| match &self.policy {
+                Some(policy) => u128::from(policy(transition)),
+                None => 1,
+            })
+            .collect();
+        let mut total: u128 = weights.iter().sum();
+        if total == 0 {
+            weights.fill(1);
+            total = weights.len() as u128;
+        }
+
+        let mut choice = u128::from(self.next_random()) % total;
+        let index = weights
+            .iter()
+            .position(|weight| {
+                if choice < *weight {
+                    true
+                } else {
+                    choice -= weight;
+                    false
+                }
+            })
+            .unwrap();
+        let transition = transitions.swap_remove(index);
+        self.state = transition.target.clone();
+        self.steps += 1;
+        Some(transition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, fixtures::new_edge, invariant::Invariant,
+            location::Location,
+        },
+        transition_system::state::State,
+    };
+
+    use super::{simulates, RandomWalk, Transition};
+
+    fn new_abstraction() -> (Automaton, [Location; 2]) {
+        let invariant = &Invariant::new_true();
+        let out = Channel::new_output("out");
+        let x = Location::new_initial("x", invariant);
+        let y = Location::new_normal("y", invariant);
+        let edges = HashSet::from([new_edge(&x, &out, &y), new_edge(&y, &out, &y)]);
+        let automaton = Automaton::new("abstraction", &edges, None).ok().unwrap();
+        (automaton, [x, y])
+    }
+
+    #[test]
+    fn simulation_chain_is_simulated_by_abstraction() {
+        let invariant = &Invariant::new_true();
+        let out = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let edges = HashSet::from([new_edge(&a, &out, &b), new_edge(&b, &out, &c)]);
+        let concrete = Automaton::new("chain", &edges, None).ok().unwrap();
+        let (abstraction, [x, y]) = new_abstraction();
+        let environment = concrete.get_initial_environment();
+        let state = |location: &Location| State::new(location, environment);
+
+        let relation = simulates(&abstraction, &concrete).ok().unwrap();
+
+        assert!(relation.contains(&state(&a), &state(&x)));
+        assert!(relation.contains(&state(&b), &state(&y)));
+        assert!(relation.contains(&state(&c), &state(&y)));
+        assert_eq!(relation.covering(&state(&c)).len(), 2);
+        assert!(simulates(&concrete, &abstraction).is_err());
+    }
+
+    #[test]
+    fn simulation_extra_output_is_not_simulated() {
+        let invariant = &Invariant::new_true();
+        let out = Channel::new_output("out");
+        let extra = Channel::new_output("extra");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let edges = HashSet::from([
+            new_edge(&a, &out, &b),
+            new_edge(&b, &out, &c),
+            new_edge(&b, &extra, &c),
+        ]);
+        let concrete = Automaton::new("chain", &edges, None).ok().unwrap();
+        let (abstraction, _) = new_abstraction();
+
+        let failure = simulates(&abstraction, &concrete).err().unwrap();
+
+        assert_eq!(failure.concrete_state.location, b);
+        assert_eq!(failure.action, extra);
+        assert_eq!(failure.trace.len(), 1);
+        assert_eq!(failure.trace.last_state(), &failure.concrete_state);
+        assert_eq!(failure.trace.actions(), vec![out]);
+        assert!(failure.trace.replay(&concrete).is_ok());
+    }
+
+    /// a -tick!-> a, a -tock!-> a, a -stop?-> b
+    fn new_walk_fixture() -> (Automaton, [Location; 2]) {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let edges = HashSet::from([
+            new_edge(&a, &Channel::new_output("tick"), &a),
+            new_edge(&a, &Channel::new_output("tock"), &a),
+            new_edge(&a, &Channel::new_input("stop"), &b),
+        ]);
+        let automaton = Automaton::new("walk", &edges, None).ok().unwrap();
+        (automaton, [a, b])
+    }
+
+    #[test]
+    fn random_walk_is_determined_by_seed() {
+        let (automaton, [_, b]) = new_walk_fixture();
+        let actions = automaton.get_actions();
+
+        let walk: Vec<Channel> = RandomWalk::new(&automaton, actions, 5)
+            .map(|transition| transition.action)
+            .collect();
+        let again: Vec<Channel> = RandomWalk::new(&automaton, actions, 5)
+            .map(|transition| transition.action)
+            .collect();
+
+        let tick = Channel::new_output("tick");
+        let tock = Channel::new_output("tock");
+        let stop = Channel::new_input("stop");
+        assert_eq!(walk, again);
+        assert_eq!(
+            walk,
+            vec![
+                tock.clone(),
+                tick.clone(),
+                tock.clone(),
+                tock.clone(),
+                tick.clone(),
+                tick,
+                stop
+            ]
+        );
+        let mut finished = RandomWalk::new(&automaton, actions, 5);
+        finished.by_ref().for_each(drop);
+        assert_eq!(finished.get_state().location, b);
+        assert_eq!(finished.next(), None);
+    }
+
+    #[test]
+    fn random_walk_stops_after_max_steps() {
+        let (automaton, [a, _]) = new_walk_fixture();
+        let actions = automaton.get_actions();
+
+        let mut walk = RandomWalk::new(&automaton, actions, 3)
+            .with_max_steps(20)
+            .with_policy(|transition| u32::from(transition.action.is_output()));
+        let transitions: Vec<_> = walk.by_ref().collect();
+
+        assert_eq!(transitions.len(), 20);
+        assert_eq!(walk.get_steps(), 20);
+        assert!(transitions
+            .iter()
+            .all(|transition| transition.action.is_output() && transition.target.location == a));
+    }
+
+    #[test]
+    fn random_walk_maximal_weights_do_not_overflow() {
+        let (automaton, [a, _]) = new_walk_fixture();
+        let actions = automaton.get_actions();
+
+        let mut walk = RandomWalk::new(&automaton, actions, 3)
+            .with_max_steps(20)
+            .with_policy(|transition| match transition.action.is_output() {
+                true => u32::MAX,
+                false => 0,
+            });
+        let transitions: Vec<_> = walk.by_ref().collect();
+
+        assert_eq!(transitions.len(), 20);
+        assert!(transitions
+            .iter()
+            .all(|transition| transition.action.is_output() && transition.target.location == a));
+    }
+
+    #[test]
+    fn random_walk_zero_weights_choose_uniformly() {
+        let (automaton, _) = new_walk_fixture();
+        let actions = automaton.get_actions();
+
+        let weighted: Vec<Transition> = RandomWalk::new(&automaton, actions, 5)
+            .with_policy(|_| 0)
+            .collect();
+        let uniform: Vec<Transition> = RandomWalk::new(&automaton, actions, 5).collect();
+
+        assert!(!weighted.is_empty());
+        assert_eq!(weighted, uniform);
+    }
+
+    #[test]
+    fn random_walk_lets_time_pass() {
+        let automaton = Automaton::parse(
+            "var x = 0\nlocation a init invariant x <= 3\nlocation b\na -go! when x >= 2-> b",
+        )
+        .ok()
+        .unwrap();
+
+        for seed in 0..8 {
+            let mut walk = RandomWalk::new(&automaton, automaton.get_actions(), seed);
+            let transitions: Vec<Transition> = walk.by_ref().collect();
+
+            assert!(transitions.iter().any(Transition::is_delay));
+            assert_eq!(walk.get_state().location.get_name(), "b");
+        }
+    }
+}
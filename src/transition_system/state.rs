@@ -1,11 +1,12 @@
-use std::fmt::Display;
+use std::{collections::HashSet, fmt::Display};
 
 use crate::{
     automatom::{edge::Edge, location::Location},
-    language::environment::Environment,
+    language::{environment::Environment, value::Value},
 };
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+/// States are ordered by location and then by environment.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub struct State {
     pub location: Location,
     pub environment: Environment,
@@ -19,6 +20,34 @@ impl State {
         }
     }
 
+    /// The state with only the variables in `keep` left in its environment.
+    pub fn project(&self, keep: &HashSet<String>) -> State {
+        State::new(&self.location, &self.environment.project(keep))
+    }
+
+    /// Displays the state with only the variables in `relevant`, such that
+    /// large models can be shown without their whole environment.
+    pub fn display_compact(&self, relevant: &HashSet<String>) -> String {
+        self.project(relevant).to_string()
+    }
+
+    /// The state after one unit of time has passed, where every clock advances
+    /// unless it is already beyond the ceiling, as no constraint up to the
+    /// ceiling can tell such times apart. None if no clock advances.
+    pub fn delayed(&self, ceiling: u32) -> Option<State> {
+        let mut environment = self.environment.clone();
+        let mut advanced = false;
+        for (identifier, value) in self.environment.sorted_entries() {
+            if let Value::Clock(time) = value {
+                if *time <= ceiling {
+                    environment.set(identifier.as_str(), &Value::new_clock(time + 1));
+                    advanced = true;
+                }
+            }
+        }
+        advanced.then(|| State::new(&self.location, &environment))
+    }
+
     pub fn enables_any(&self, edges: &Vec<Edge>) -> bool {
         for edge in edges {
             if edge.enabled(&self) {
@@ -29,8 +58,78 @@ impl State {
     }
 }
 
+/// Displays the location name followed by the variables sorted by name, e.g.
+/// `a { x:=true, y:=false }`.
 impl Display for State {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("({}, {})", self.location, self.environment))
+        let variables: Vec<String> = self
+            .environment
+            .sorted_entries()
+            .into_iter()
+            .map(|(identifier, value)| format!("{}:={}", identifier, value))
+            .collect();
+        if variables.is_empty() {
+            f.write_fmt(format_args!("{} {{}}", self.location.get_name()))
+        } else {
+            f.write_fmt(format_args!(
+                "{} {{ {} }}",
+                self.location.get_name(),
+                variables.join(", ")
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{invariant::Invariant, location::Location},
+        language::{environment::Environment, value::Value},
+    };
+
+    use super::State;
+
+    fn new_state() -> State {
+        let mut environment = Environment::new_empty();
+        environment.insert("b", &Value::new_false());
+        environment.insert("c", &Value::new_true());
+        environment.insert("a", &Value::new_true());
+        State::new(
+            &Location::new_normal("idle", &Invariant::new_true()),
+            &environment,
+        )
+    }
+
+    #[test]
+    fn state_display_sorts_variables() {
+        let state = new_state();
+
+        let display = state.to_string();
+
+        assert_eq!(display, "idle { a:=true, b:=false, c:=true }");
+    }
+
+    #[test]
+    fn state_display_without_variables() {
+        let state = State::new(
+            &Location::new_initial("start", &Invariant::new_true()),
+            &Environment::new_empty(),
+        );
+
+        let display = state.to_string();
+
+        assert_eq!(display, "start {}");
+    }
+
+    #[test]
+    fn state_display_compact_shows_relevant_variables() {
+        let state = new_state();
+        let relevant = HashSet::from([String::from("c"), String::from("a")]);
+
+        let display = state.display_compact(&relevant);
+
+        assert_eq!(display, "idle { a:=true, c:=true }");
     }
 }
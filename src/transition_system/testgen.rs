@@ -0,0 +1,155 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::automatom::{automaton::Automaton, edge::Edge};
+
+use super::{
+    error::TestGenError, state::State, trace::Trace, transition::Transition,
+    transition_system::TransitionSystem,
+};
+
+/// The edges which are not enabled in any reachable state, sorted as by
+/// `Automaton::edges_sorted`.
+pub fn dead_edges(automaton: &Automaton) -> Vec<Edge> {
+    let fired = fireable_edges(automaton);
+    automaton
+        .edges_sorted()
+        .into_iter()
+        .filter(|edge| !fired.contains(edge))
+        .collect()
+}
+
+/// Generates traces from the initial state which together fire every edge that
+/// is not dead. A trace is extended towards the nearest edge not yet fired until
+/// none can be reached from its last state, after which a new trace is started.
+pub fn cover_all_edges(automaton: &Automaton) -> Result<Vec<Trace>, TestGenError> {
+    let mut uncovered = fireable_edges(automaton);
+    let initial = automaton.get_initial_state();
+    let mut traces: Vec<Trace> = Vec::new();
+
+    while !uncovered.is_empty() {
+        let mut trace = Trace::new(&initial);
+        while let Some(path) = path_to_uncovered(automaton, trace.last_state(), &uncovered) {
+            for transition in path.iter() {
+                if let Some(edge) = &transition.edge {
+                    uncovered.remove(edge);
+                }
+                if let Err(error) = trace.push(transition) {
+                    return Err(TestGenError::TraceNotReplayable {
+                        trace: Box::new(trace),
+                        error,
+                    });
+                }
+            }
+        }
+        if trace.is_empty() {
+            // Cannot happen as every fireable edge is reachable from the initial state
+            break;
+        }
+
+        if let Err(error) = trace.replay(automaton) {
+            return Err(TestGenError::TraceNotReplayable {
+                trace: Box::new(trace),
+                error,
+            });
+        }
+        traces.push(trace);
+    }
+
+    Ok(traces)
+}
+
+fn fireable_edges(automaton: &Automaton) -> HashSet<Edge> {
+    automaton
+        .reachable_states(automaton.get_actions())
+        .iter()
+        .flat_map(|state| automaton.transitions(state, automaton.get_actions()))
+        .filter_map(|transition| transition.edge)
+        .collect()
+}
+
+/// A shortest path from the state ending with a transition by an uncovered edge.
+fn path_to_uncovered(
+    automaton: &Automaton,
+    from: &State,
+    uncovered: &HashSet<Edge>,
+) -> Option<Vec<Transition>> {
+    let mut parents: HashMap<State, Transition> = HashMap::new();
+    let mut discovered: HashSet<State> = HashSet::from([from.clone()]);
+    let mut frontier: VecDeque<State> = VecDeque::from([from.clone()]);
+
+    while let Some(state) = frontier.pop_front() {
+        for transition in automaton.timed_transitions(&state, automaton.get_actions()) {
+            let fires_uncovered = transition
+                .edge
+                .as_ref()
+                .is_some_and(|edge| uncovered.contains(edge));
+            if fires_uncovered {
+                let mut path = vec![transition];
+                let mut current = &path[0].source;
+                while let Some(parent) = parents.get(current) {
+                    path.push(parent.clone());
+                    current = &parent.source;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if discovered.insert(transition.target.clone()) {
+                parents.insert(transition.target.clone(), transition.clone());
+                frontier.push_back(transition.target);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, fixtures::DIRECTED_DIAMOND,
+            guard::Guard, invariant::Invariant, location::Location, update::Update,
+        },
+        transition_system::coverage::Coverage,
+    };
+
+    use super::{cover_all_edges, dead_edges};
+
+    #[test]
+    fn testgen_cover_all_edges_full_system() {
+        let automaton = Automaton::parse(DIRECTED_DIAMOND).ok().unwrap();
+
+        let traces = cover_all_edges(&automaton).unwrap();
+
+        let mut coverage = Coverage::new(&automaton);
+        for trace in traces.iter() {
+            assert_eq!(trace.replay(&automaton), Ok(()));
+            coverage.record(trace);
+        }
+        assert_eq!(traces.len(), 4);
+        assert_eq!(coverage.visited_edges().len(), 8);
+        assert_eq!(coverage.edge_coverage(), 1.0);
+        assert!(dead_edges(&automaton).is_empty());
+    }
+
+    #[test]
+    fn testgen_skips_dead_edges() {
+        let invariant = &Invariant::new_true();
+        let out = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let live = Edge::new(&a, &out, &Guard::new_true(), &Update::empty(), &b);
+        let dead = Edge::new(&b, &out, &Guard::new_false(), &Update::empty(), &a);
+        let automaton = Automaton::new("dead", &HashSet::from([live.clone(), dead.clone()]), None)
+            .ok()
+            .unwrap();
+
+        let traces = cover_all_edges(&automaton).unwrap();
+
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].transitions()[0].edge, Some(live));
+        assert_eq!(dead_edges(&automaton), vec![dead]);
+    }
+}
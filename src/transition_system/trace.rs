@@ -0,0 +1,435 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::automatom::channel::Channel;
+
+use super::{
+    error::ReplayError, state::State, transition::Transition, transition_system::TransitionSystem,
+};
+
+/// Replays the actions from the initial state of the transition system, where
+/// the delay action lets time pass. If an action has several enabled transitions
+/// the first one is taken. On success the final state is returned, otherwise the
+/// index of the action which could not be taken and the state the replay got
+/// stuck in.
+pub fn replay<TS: TransitionSystem>(ts: &TS, trace: &[Channel]) -> Result<State, (usize, State)> {
+    let mut state = ts.get_initial_state();
+    for (index, action) in trace.iter().enumerate() {
+        match ts.timed_step(&state, action).into_iter().next() {
+            Some(next) => state = next,
+            None => return Err((index, state)),
+        }
+    }
+    Ok(state)
+}
+
+/// A sequence of transitions starting at an initial state, where the source of
+/// every transition is the target of the one before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    initial: State,
+    transitions: Vec<Transition>,
+}
+
+impl Trace {
+    pub fn new(initial: &State) -> Self {
+        Trace {
+            initial: initial.clone(),
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Extends the trace by the transition, which must leave the state the
+    /// trace ends in.
+    pub fn push(&mut self, transition: &Transition) -> Result<(), ReplayError> {
+        if transition.source != *self.last_state() {
+            return Err(ReplayError::DisconnectedTransition {
+                step: self.transitions.len(),
+                transition: Box::new(transition.clone()),
+            });
+        }
+        self.transitions.push(transition.clone());
+        Ok(())
+    }
+
+    pub fn get_initial_state(&self) -> &State {
+        &self.initial
+    }
+
+    pub fn last_state(&self) -> &State {
+        match self.transitions.last() {
+            Some(transition) => &transition.target,
+            None => &self.initial,
+        }
+    }
+
+    pub fn transitions(&self) -> &Vec<Transition> {
+        &self.transitions
+    }
+
+    pub fn states(&self) -> Vec<&State> {
+        let mut states = vec![&self.initial];
+        states.extend(self.transitions.iter().map(|transition| &transition.target));
+        states
+    }
+
+    /// The number of transitions in the trace.
+    pub fn len(&self) -> usize {
+        self.transitions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transitions.is_empty()
+    }
+
+    /// The word of actions taken along the trace.
+    pub fn actions(&self) -> Vec<Channel> {
+        self.transitions
+            .iter()
+            .map(|transition| transition.action.clone())
+            .collect()
+    }
+
+    /// The trace with only the variables in `keep` left in the environments of its states.
+    pub fn project_variables(&self, keep: &HashSet<String>) -> Trace {
+        Trace {
+            initial: self.initial.project(keep),
+            transitions: self
+                .transitions
+                .iter()
+                .map(|transition| Transition {
+                    source: transition.source.project(keep),
+                    target: transition.target.project(keep),
+                    ..transition.clone()
+                })
+                .collect(),
+        }
+    }
+
+    /// Verifies that the trace starts in the initial state of the transition
+    /// system and that every step is enabled in it. Delay steps are enabled if
+    /// letting time pass in the source reaches the target, such that the
+    /// invariant of the location still holds.
+    pub fn replay(&self, ts: &impl TransitionSystem) -> Result<(), ReplayError> {
+        let initial = ts.get_initial_state();
+        if initial != self.initial {
+            return Err(ReplayError::InitialStateMismatch {
+                trace: Box::new(self.initial.clone()),
+                actual: Box::new(initial),
+            });
+        }
+
+        for (step, transition) in self.transitions.iter().enumerate() {
+            let enabled = if transition.is_delay() {
+                ts.delay_successors(&transition.source)
+                    .contains(&transition.target)
+            } else {
+                let actions = HashSet::from([transition.action.clone()]);
+                ts.transitions(&transition.source, &actions)
+                    .iter()
+                    .any(|candidate| {
+                        candidate.action.same_direction(&transition.action)
+                            && candidate.target == transition.target
+                    })
+            };
+            if !enabled {
+                return Err(ReplayError::TransitionNotEnabled {
+                    step,
+                    transition: Box::new(transition.clone()),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}", self.initial))?;
+        for (step, transition) in self.transitions.iter().enumerate() {
+            f.write_fmt(format_args!(
+                "\n{}: {} -({})-> {}",
+                step,
+                transition.source.location.get_name(),
+                transition.action,
+                transition.target.location.get_name()
+            ))?;
+            let differences = transition
+                .source
+                .environment
+                .differences(&transition.target.environment);
+            for (identifier, before, after) in differences {
+                let render = |value: Option<_>| match value {
+                    Some(value) => format!("{}", value),
+                    None => String::from("undeclared"),
+                };
+                f.write_fmt(format_args!(
+                    " [{}: {} -> {}]",
+                    identifier,
+                    render(before),
+                    render(after)
+                ))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An infinite run given by a finite trace and a loop repeated forever. The
+/// stem leads from the initial state to the state the loop starts and ends in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lasso {
+    pub stem: Trace,
+    pub cycle: Vec<Transition>,
+}
+
+impl Lasso {
+    /// The state where the loop starts and ends.
+    pub fn loop_state(&self) -> &State {
+        self.stem.last_state()
+    }
+
+    /// The stem followed by one pass through the loop. Fails if the loop does
+    /// not continue the stem.
+    pub fn unrolled(&self) -> Result<Trace, ReplayError> {
+        let mut trace = self.stem.clone();
+        for transition in self.cycle.iter() {
+            trace.push(transition)?;
+        }
+        Ok(trace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        language::{environment::Environment, statement::Statement, value::Value},
+        transition_system::{
+            error::ReplayError, state::State, transition::Transition,
+            transition_system_breadth_first_search::TransitionSystemBreadthFirstSearch,
+        },
+    };
+
+    use super::{replay, Trace};
+
+    fn new_toggle(guard: &Guard) -> (Automaton, Location, Location) {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_false());
+        let set = Update::new(&Statement::new_simple_assignment("x", &Value::new_true()));
+        let edges = HashSet::from([
+            Edge::new(&a, &Channel::new_input("go"), &Guard::new_true(), &set, &b),
+            Edge::new(
+                &b,
+                &Channel::new_output("back"),
+                guard,
+                &Update::empty(),
+                &a,
+            ),
+        ]);
+        let automaton = Automaton::new("toggle", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+        (automaton, a, b)
+    }
+
+    fn new_toggle_trace(a: &Location, b: &Location) -> Trace {
+        let mut before = Environment::new_empty();
+        before.insert("x", &Value::new_false());
+        let mut after = Environment::new_empty();
+        after.insert("x", &Value::new_true());
+        let first = State::new(a, &before);
+        let second = State::new(b, &after);
+        let third = State::new(a, &after);
+
+        let mut trace = Trace::new(&first);
+        trace
+            .push(&Transition::new(&first, &Channel::new_input("go"), &second))
+            .unwrap();
+        trace
+            .push(&Transition::new(
+                &second,
+                &Channel::new_output("back"),
+                &third,
+            ))
+            .unwrap();
+        trace
+    }
+
+    fn new_automaton() -> (Automaton, Vec<Location>) {
+        let invariant = &Invariant::new_true();
+        let input = Channel::new_input("in");
+        let output = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let edges = HashSet::from([
+            Edge::new(&a, &input, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&b, &output, &Guard::new_true(), &Update::empty(), &c),
+            Edge::new(&c, &input, &Guard::new_true(), &Update::empty(), &a),
+        ]);
+        let automaton = Automaton::new("automaton", &edges, None).ok().unwrap();
+        (automaton, vec![a, b, c])
+    }
+
+    #[test]
+    fn replay_valid_trace() {
+        let (automaton, locations) = new_automaton();
+        let trace = vec![
+            Channel::new_input("in"),
+            Channel::new_output("out"),
+            Channel::new_input("in"),
+            Channel::new_input("in"),
+        ];
+
+        let result = replay(&automaton, &trace);
+
+        assert_eq!(result.map(|state| state.location), Ok(locations[1].clone()));
+    }
+
+    #[test]
+    fn replay_stuck_trace() {
+        let (automaton, locations) = new_automaton();
+        let trace = vec![
+            Channel::new_input("in"),
+            Channel::new_output("out"),
+            Channel::new_output("out"),
+        ];
+
+        let result = replay(&automaton, &trace);
+
+        assert_eq!(
+            result.map_err(|(index, state)| (index, state.location)),
+            Err((2, locations[2].clone()))
+        );
+    }
+
+    #[test]
+    fn trace_actions_and_display() {
+        let (_, a, b) = new_toggle(&Guard::new_true());
+        let trace = new_toggle_trace(&a, &b);
+
+        assert_eq!(
+            trace.actions(),
+            vec![Channel::new_input("go"), Channel::new_output("back")]
+        );
+        assert_eq!(
+            trace.to_string(),
+            "a { x:=false }\n0: a -(go?)-> b [x: false -> true]\n1: b -(back!)-> a"
+        );
+    }
+
+    #[test]
+    fn trace_replay_matching_automaton() {
+        let (automaton, a, b) = new_toggle(&Guard::new_true());
+        let trace = new_toggle_trace(&a, &b);
+
+        assert_eq!(trace.replay(&automaton), Ok(()));
+    }
+
+    #[test]
+    fn trace_replay_mutated_automaton() {
+        let (automaton, a, b) = new_toggle(&Guard::new_false());
+        let trace = new_toggle_trace(&a, &b);
+
+        let result = trace.replay(&automaton);
+
+        assert!(matches!(
+            result,
+            Err(ReplayError::TransitionNotEnabled { step: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn trace_push_rejects_disconnected_transition() {
+        let (_, a, b) = new_toggle(&Guard::new_true());
+        let mut trace = new_toggle_trace(&a, &b);
+        let restart = trace.transitions()[0].clone();
+
+        let result = trace.push(&restart);
+
+        assert_eq!(
+            result,
+            Err(ReplayError::DisconnectedTransition {
+                step: 2,
+                transition: Box::new(restart),
+            })
+        );
+        assert_eq!(trace.len(), 2);
+    }
+
+    #[test]
+    fn trace_project_variables() {
+        let (_, a, b) = new_toggle(&Guard::new_true());
+        let trace = new_toggle_trace(&a, &b);
+        let with_y = |state: &State| {
+            let mut environment = state.environment.clone();
+            environment.insert("y", &Value::new_false());
+            State::new(&state.location, &environment)
+        };
+        let mut extended = Trace::new(&with_y(trace.get_initial_state()));
+        for transition in trace.transitions() {
+            extended
+                .push(&Transition::new(
+                    &with_y(&transition.source),
+                    &transition.action,
+                    &with_y(&transition.target),
+                ))
+                .unwrap();
+        }
+
+        let projected = extended.project_variables(&HashSet::from([String::from("x")]));
+
+        assert_eq!(projected, trace);
+        assert_eq!(projected.actions(), extended.actions());
+    }
+
+    #[test]
+    fn trace_replay_checks_delays_against_invariants() {
+        let automaton = Automaton::parse(
+            "
+            var x = 0
+            location a init invariant x <= 2
+            location b
+            a -go! when x >= 1-> b
+            ",
+        )
+        .ok()
+        .unwrap();
+        let mut search =
+            TransitionSystemBreadthFirstSearch::new(automaton.get_actions(), &automaton);
+        let in_b = search
+            .by_ref()
+            .find(|state| state.location.get_name() == "b")
+            .unwrap();
+        let trace = search.path_to(&in_b).unwrap();
+        let initial = trace.get_initial_state();
+        let at = |time: u32| {
+            let mut environment = initial.environment.clone();
+            environment.set("x", &Value::new_clock(time));
+            State::new(&initial.location, &environment)
+        };
+        let mut beyond_invariant = Trace::new(initial);
+        for time in 0..3 {
+            beyond_invariant
+                .push(&Transition::new_delay(&at(time), &at(time + 1)))
+                .unwrap();
+        }
+
+        assert!(trace.transitions()[0].is_delay());
+        assert_eq!(trace.replay(&automaton), Ok(()));
+        assert_eq!(replay(&automaton, &trace.actions()), Ok(in_b.clone()));
+        assert!(matches!(
+            beyond_invariant.replay(&automaton),
+            Err(ReplayError::TransitionNotEnabled { step: 2, .. })
+        ));
+    }
+}
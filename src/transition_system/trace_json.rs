@@ -0,0 +1,593 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    automatom::{channel::Channel, invariant::Invariant, location::Location},
+    language::{
+        environment::Environment,
+        expression::{BinaryOperator, Expression, UnaryOperator},
+        value::Value,
+    },
+};
+
+use super::{error::TraceFormatError, state::State, transition::Transition};
+
+/// Writes the transitions as a JSON array where each transition records the
+/// channel and, for the source and target states, the location and the
+/// environment. Locations record their name, kind and invariant, along with
+/// the operands of conjunctions. Edges are not recorded, so transitions are
+/// read back without them.
+pub fn trace_to_json(trace: &[Transition]) -> String {
+    let transitions: Vec<Json> = trace
+        .iter()
+        .map(|transition| {
+            let direction = if transition.action.is_input() {
+                "input"
+            } else {
+                "output"
+            };
+            Json::Object(BTreeMap::from([
+                (String::from("source"), state_to_json(&transition.source)),
+                (
+                    String::from("action"),
+                    Json::Object(BTreeMap::from([
+                        (
+                            String::from("name"),
+                            Json::String(transition.action.name().to_string()),
+                        ),
+                        (
+                            String::from("direction"),
+                            Json::String(String::from(direction)),
+                        ),
+                    ])),
+                ),
+                (String::from("target"), state_to_json(&transition.target)),
+            ]))
+        })
+        .collect();
+    Json::Array(transitions).to_string()
+}
+
+pub fn trace_from_json(json: &str) -> Result<Vec<Transition>, TraceFormatError> {
+    let mut parser = Parser { json, position: 0 };
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.position != json.len() {
+        return Err(parser.error("Expected the end of the trace"));
+    }
+
+    let invalid = |message: &str| TraceFormatError::InvalidTrace {
+        position: 0,
+        message: String::from(message),
+    };
+    let Json::Array(transitions) = value else {
+        return Err(invalid("The trace is not an array"));
+    };
+    transitions
+        .iter()
+        .map(|transition| {
+            let source = state_from_json(
+                transition
+                    .field("source")
+                    .ok_or_else(|| invalid("Missing source"))?,
+            )
+            .ok_or_else(|| invalid("Invalid source state"))?;
+            let target = state_from_json(
+                transition
+                    .field("target")
+                    .ok_or_else(|| invalid("Missing target"))?,
+            )
+            .ok_or_else(|| invalid("Invalid target state"))?;
+            let action = transition
+                .field("action")
+                .ok_or_else(|| invalid("Missing action"))?;
+            let name = action
+                .field("name")
+                .and_then(Json::as_str)
+                .ok_or_else(|| invalid("Invalid action name"))?;
+            let action = match action.field("direction").and_then(Json::as_str) {
+                Some("input") => Channel::new_input(name),
+                Some("output") => Channel::new_output(name),
+                _ => return Err(invalid("Invalid action direction")),
+            };
+            Ok(Transition::new(&source, &action, &target))
+        })
+        .collect()
+}
+
+fn state_to_json(state: &State) -> Json {
+    let environment = state
+        .environment
+        .differences(&Environment::new_empty())
+        .into_iter()
+        .filter_map(|(identifier, value, _)| Some((identifier, value_to_json(&value?))))
+        .collect();
+    let mut fields = location_fields(&state.location);
+    fields.insert(String::from("environment"), Json::Object(environment));
+    Json::Object(fields)
+}
+
+fn state_from_json(json: &Json) -> Option<State> {
+    let location = location_from_json(json)?;
+    let Json::Object(bindings) = json.field("environment")? else {
+        return None;
+    };
+    let mut environment = Environment::new_empty();
+    for (identifier, value) in bindings {
+        environment.insert(identifier, &value_from_json(value)?);
+    }
+    Some(State::new(&location, &environment))
+}
+
+/// The name, kind and invariant of the location, where conjunctions also
+/// record their operands such that they can be rebuilt exactly.
+fn location_fields(location: &Location) -> BTreeMap<String, Json> {
+    let (kind, invariant) = match location {
+        Location::Normal { invariant, .. } => ("normal", Some(invariant)),
+        Location::Initial { invariant, .. } => ("initial", Some(invariant)),
+        Location::Conjunction { invariant, .. } => ("conjunction", Some(invariant)),
+        Location::Inconsistent { .. } => ("inconsistent", None),
+        Location::Universal { .. } => ("universal", None),
+    };
+    let mut fields = BTreeMap::from([
+        (String::from("location"), Json::String(location.get_name())),
+        (String::from("kind"), Json::String(String::from(kind))),
+    ]);
+    if let Some(invariant) = invariant {
+        fields.insert(
+            String::from("invariant"),
+            expression_to_json(&invariant.node),
+        );
+    }
+    if let Location::Conjunction { locations, .. } = location {
+        let operands = locations
+            .iter()
+            .map(|location| Json::Object(location_fields(location)))
+            .collect();
+        fields.insert(String::from("locations"), Json::Array(operands));
+    }
+    fields
+}
+
+fn location_from_json(json: &Json) -> Option<Location> {
+    let name = json.field("location")?.as_str()?;
+    let invariant = || -> Option<Invariant> {
+        Some(Invariant::new(&expression_from_json(
+            json.field("invariant")?,
+        )?))
+    };
+    let location = match json.field("kind")?.as_str()? {
+        "normal" => Location::new_normal(name, &invariant()?),
+        "initial" => Location::new_initial(name, &invariant()?),
+        "conjunction" => {
+            let Json::Array(operands) = json.field("locations")? else {
+                return None;
+            };
+            Location::Conjunction {
+                locations: operands
+                    .iter()
+                    .map(location_from_json)
+                    .collect::<Option<Vec<Location>>>()?,
+                invariant: invariant()?,
+            }
+        }
+        "inconsistent" => Location::new_inconsistent(name),
+        "universal" => Location::new_universal(name),
+        _ => return None,
+    };
+    Some(location)
+}
+
+fn value_to_json(value: &Value) -> Json {
+    match value {
+        Value::Bool(value) => Json::Bool(*value),
+        Value::Identifier(identifier) => Json::String(identifier.as_str().to_string()),
+        Value::Clock(time) => Json::Number(*time),
+    }
+}
+
+fn value_from_json(json: &Json) -> Option<Value> {
+    match json {
+        Json::Bool(value) => Some(Value::new_boolean(*value)),
+        Json::String(identifier) => Some(Value::new_identifier(identifier)),
+        Json::Number(time) => Some(Value::new_clock(*time)),
+        _ => None,
+    }
+}
+
+const BINARY_OPERATORS: [BinaryOperator; 10] = [
+    BinaryOperator::LogicalAnd,
+    BinaryOperator::LogicalOr,
+    BinaryOperator::Equal,
+    BinaryOperator::NotEqual,
+    BinaryOperator::Implication,
+    BinaryOperator::BiImplication,
+    BinaryOperator::Less,
+    BinaryOperator::LessEqual,
+    BinaryOperator::Greater,
+    BinaryOperator::GreaterEqual,
+];
+
+/// Writes the expression as a tree rather than as text, such that it is read
+/// back with exactly the same structure, including its parentheses.
+fn expression_to_json(expression: &Expression) -> Json {
+    match expression {
+        Expression::Literal(value) => value_to_json(value),
+        Expression::Parenthesized(inner) => Json::Object(BTreeMap::from([(
+            String::from("parenthesized"),
+            expression_to_json(inner),
+        )])),
+        Expression::Binary(lhs, operator, rhs) => Json::Object(BTreeMap::from([
+            (String::from("lhs"), expression_to_json(lhs)),
+            (String::from("operator"), Json::String(operator.to_string())),
+            (String::from("rhs"), expression_to_json(rhs)),
+        ])),
+        Expression::Unary(operator, operand) => Json::Object(BTreeMap::from([
+            (String::from("operator"), Json::String(operator.to_string())),
+            (String::from("operand"), expression_to_json(operand)),
+        ])),
+    }
+}
+
+fn expression_from_json(json: &Json) -> Option<Expression> {
+    if let Some(inner) = json.field("parenthesized") {
+        return Some(Expression::new_parenthesized(&expression_from_json(inner)?));
+    }
+    if let Some(operand) = json.field("operand") {
+        let operator = json.field("operator")?.as_str()?;
+        if operator != UnaryOperator::Negation.to_string() {
+            return None;
+        }
+        return Some(Expression::new_logical_not(&expression_from_json(operand)?));
+    }
+    if let (Some(lhs), Some(rhs)) = (json.field("lhs"), json.field("rhs")) {
+        let operator = json.field("operator")?.as_str()?;
+        let operator = BINARY_OPERATORS
+            .into_iter()
+            .find(|candidate| candidate.to_string() == operator)?;
+        return Some(Expression::new_binary_expression(
+            &expression_from_json(lhs)?,
+            &operator,
+            &expression_from_json(rhs)?,
+        ));
+    }
+    Some(Expression::new_literal(&value_from_json(json)?))
+}
+
+/// The string as a quoted JSON string, escaping quotes, backslashes and
+/// control characters.
+pub(crate) fn json_string(string: &str) -> String {
+    let mut quoted = String::from("\"");
+    for character in string.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            character if character.is_control() => {
+                quoted.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The subset of JSON needed to represent traces.
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Bool(bool),
+    Number(u32),
+    String(String),
+    Array(Vec<Json>),
+    Object(BTreeMap<String, Json>),
+}
+
+impl Json {
+    fn field(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.get(key),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(string) => Some(string),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Bool(value) => f.write_fmt(format_args!("{}", value)),
+            Json::Number(value) => f.write_fmt(format_args!("{}", value)),
+            Json::String(string) => f.write_str(&json_string(string)),
+            Json::Array(elements) => {
+                f.write_str("[")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    element.fmt(f)?;
+                }
+                f.write_str("]")
+            }
+            Json::Object(fields) => {
+                f.write_str("{")?;
+                for (index, (key, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    Json::String(key.clone()).fmt(f)?;
+                    f.write_str(":")?;
+                    value.fmt(f)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    json: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> TraceFormatError {
+        TraceFormatError::InvalidTrace {
+            position: self.position,
+            message: String::from(message),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.json[self.position..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(character) = self.peek().filter(|character| character.is_whitespace()) {
+            self.position += character.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), TraceFormatError> {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{}'", expected)))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Json, TraceFormatError> {
+        self.skip_whitespace();
+        let rest = &self.json[self.position..];
+        match self.peek() {
+            Some('"') => Ok(Json::String(self.parse_string()?)),
+            Some('[') => {
+                self.position += 1;
+                let mut elements = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(']') {
+                    self.position += 1;
+                    return Ok(Json::Array(elements));
+                }
+                loop {
+                    elements.push(self.parse_value()?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(',') => self.position += 1,
+                        _ => break,
+                    }
+                }
+                self.expect(']')?;
+                Ok(Json::Array(elements))
+            }
+            Some('{') => {
+                self.position += 1;
+                let mut fields = BTreeMap::new();
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.position += 1;
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.parse_string()?;
+                    self.expect(':')?;
+                    fields.insert(key, self.parse_value()?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(',') => self.position += 1,
+                        _ => break,
+                    }
+                }
+                self.expect('}')?;
+                Ok(Json::Object(fields))
+            }
+            _ if rest.starts_with("true") => {
+                self.position += 4;
+                Ok(Json::Bool(true))
+            }
+            _ if rest.starts_with("false") => {
+                self.position += 5;
+                Ok(Json::Bool(false))
+            }
+            Some(character) if character.is_ascii_digit() => {
+                let length = rest
+                    .find(|character: char| !character.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                let number = rest[..length]
+                    .parse()
+                    .map_err(|_| self.error("The number is too large"))?;
+                self.position += length;
+                Ok(Json::Number(number))
+            }
+            _ => Err(self.error("Expected a string, array, object, boolean or number")),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, TraceFormatError> {
+        if self.peek() != Some('"') {
+            return Err(self.error("Expected a string"));
+        }
+        self.position += 1;
+
+        let mut string = String::new();
+        let mut characters = self.json[self.position..].char_indices();
+        while let Some((offset, character)) = characters.next() {
+            match character {
+                '"' => {
+                    self.position += offset + 1;
+                    return Ok(string);
+                }
+                '\\' => {
+                    let escaped = match characters.next() {
+                        Some((_, '"')) => '"',
+                        Some((_, '\\')) => '\\',
+                        Some((_, '/')) => '/',
+                        Some((_, 'n')) => '\n',
+                        Some((_, 't')) => '\t',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 'u')) => {
+                            let digits: String = characters
+                                .by_ref()
+                                .take(4)
+                                .map(|(_, digit)| digit)
+                                .collect();
+                            u32::from_str_radix(&digits, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("Invalid unicode escape"))?
+                        }
+                        _ => return Err(self.error("Invalid escape")),
+                    };
+                    string.push(escaped);
+                }
+                character => string.push(character),
+            }
+        }
+        Err(self.error("Unterminated string"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        language::{
+            environment::Environment, expression::Expression, statement::Statement, value::Value,
+        },
+        transition_system::{
+            error::TraceFormatError, state::State, trace::Trace, transition::Transition,
+            transition_system_breadth_first_search::TransitionSystemBreadthFirstSearch,
+        },
+    };
+
+    use super::{trace_from_json, trace_to_json};
+
+    #[test]
+    fn trace_json_round_trip() {
+        let invariant = &Invariant::new_true();
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b \"quoted\"", invariant);
+        let environment = |x: bool| {
+            let mut environment = Environment::new_empty();
+            environment.insert("x", &Value::new_boolean(x));
+            environment.insert("y", &Value::new_identifier("x"));
+            environment
+        };
+        let first = State::new(&a, &environment(false));
+        let second = State::new(&b, &environment(true));
+        let third = State::new(&a, &environment(true));
+        let trace = vec![
+            Transition::new(&first, &Channel::new_input("go"), &second),
+            Transition::new(&second, &Channel::new_output("back"), &third),
+            Transition::new(&third, &Channel::new_input("go"), &second),
+        ];
+
+        let json = trace_to_json(&trace);
+        let parsed = trace_from_json(&json);
+
+        assert_eq!(parsed, Ok(trace));
+    }
+
+    #[test]
+    fn trace_json_round_trip_replays() {
+        let x = Expression::new_identifier("x");
+        let y = Expression::new_identifier("y");
+        // !x || (y --> x)
+        let invariant = Invariant::new(&Expression::new_logical_or(
+            &Expression::new_logical_not(&x),
+            &Expression::new_parenthesized(&Expression::new_logical_implication(&y, &x)),
+        ));
+        let a = Location::new_initial("a", &invariant);
+        let b = Location::new_normal("b", &Invariant::new(&x));
+        let set = Update::new(&Statement::new_simple_assignment("x", &Value::new_true()));
+        let edges = HashSet::from([
+            Edge::new(&a, &Channel::new_input("go"), &Guard::new_true(), &set, &b),
+            Edge::new(
+                &b,
+                &Channel::new_output("back"),
+                &Guard::new(&Expression::new_logical_not(&y)),
+                &Update::empty(),
+                &a,
+            ),
+        ]);
+        let mut environment = Environment::new_empty();
+        environment.insert("x", &Value::new_false());
+        environment.insert("y", &Value::new_false());
+        let automaton = Automaton::new("toggle", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+        let mut search =
+            TransitionSystemBreadthFirstSearch::new(automaton.get_actions(), &automaton);
+        let last = search.by_ref().last().unwrap();
+        let trace = search.path_to(&last).unwrap();
+        let conjunction = Location::new_conjunction(&vec![
+            trace.get_initial_state().location.clone(),
+            Location::new_normal("c", &Invariant::new_false()),
+        ]);
+        let conjoined = State::new(&conjunction, &Environment::new_empty());
+        let loop_on_conjunction = [Transition::new(
+            &conjoined,
+            &Channel::new_output("out"),
+            &conjoined,
+        )];
+
+        let parsed = trace_from_json(&trace_to_json(trace.transitions())).unwrap();
+        let mut replayed = Trace::new(trace.get_initial_state());
+        for transition in parsed.iter() {
+            replayed.push(transition).unwrap();
+        }
+
+        assert_eq!(trace.len(), 2);
+        assert_eq!(replayed.replay(&automaton), Ok(()));
+        assert_eq!(
+            trace_from_json(&trace_to_json(&loop_on_conjunction)),
+            Ok(loop_on_conjunction.to_vec())
+        );
+    }
+
+    #[test]
+    fn trace_json_rejects_malformed_input() {
+        let truncated = trace_from_json("[{\"source\": ");
+        let not_a_trace = trace_from_json("{}");
+
+        assert!(matches!(
+            truncated,
+            Err(TraceFormatError::InvalidTrace { position: 12, .. })
+        ));
+        assert!(not_a_trace.is_err());
+        assert_eq!(trace_from_json(" [ ] "), Ok(vec![]));
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt::Display;
+
+use crate::automatom::{channel::Channel, edge::Edge};
+
+use super::state::State;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Transition {
+    pub source: State,
+    pub action: Channel,
+    pub edge: Option<Edge>,
+    pub target: State,
+}
+
+/// The name of the action labelling delay transitions, which cannot be the
+/// name of a channel in the DSL.
+pub const DELAY_ACTION: &str = "δ";
+
+impl Transition {
+    pub fn new(source: &State, action: &Channel, target: &State) -> Self {
+        Transition {
+            source: source.clone(),
+            action: action.clone(),
+            edge: None,
+            target: target.clone(),
+        }
+    }
+
+    /// A transition where time passes from the source to the target.
+    pub fn new_delay(source: &State, target: &State) -> Self {
+        Transition::new(source, &Channel::new_output(DELAY_ACTION), target)
+    }
+
+    pub fn is_delay(&self) -> bool {
+        self.edge.is_none() && self.action.name() == DELAY_ACTION
+    }
+
+    /// A transition generated by executing the edge in the source state.
+    pub fn new_from_edge(source: &State, edge: &Edge, target: &State) -> Self {
+        Transition {
+            source: source.clone(),
+            action: edge.action.clone(),
+            edge: Some(edge.clone()),
+            target: target.clone(),
+        }
+    }
+}
+
+impl Display for Transition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.edge.as_ref().and_then(|edge| edge.id()) {
+            Some(id) => f.write_fmt(format_args!(
+                "{} -({}: {})-> {}",
+                self.source, id, self.action, self.target
+            )),
+            None => f.write_fmt(format_args!(
+                "{} -({})-> {}",
+                self.source, self.action, self.target
+            )),
+        }
+    }
+}
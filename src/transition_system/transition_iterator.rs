@@ -0,0 +1,99 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::automatom::channel::Channel;
+
+use super::{state::State, transition::Transition, transition_system::TransitionSystem};
+
+/// Walks the reachable state graph breadth-first and yields every transition
+/// between reachable states exactly once.
+#[derive(Clone)]
+pub struct TransitionIterator<'a, TS: TransitionSystem> {
+    transition_system: &'a TS,
+    actions: HashSet<Channel>,
+    visited: Vec<State>,
+    frontier: VecDeque<State>,
+    pending: VecDeque<Transition>,
+    initialized: bool,
+}
+
+impl<'a, TS: TransitionSystem> TransitionIterator<'a, TS> {
+    pub fn new(actions: &HashSet<Channel>, transition_system: &'a TS) -> Self {
+        TransitionIterator {
+            transition_system,
+            actions: actions.clone(),
+            visited: Vec::new(),
+            frontier: VecDeque::new(),
+            pending: VecDeque::new(),
+            initialized: false,
+        }
+    }
+}
+
+impl<'a, TS: TransitionSystem> Iterator for TransitionIterator<'a, TS> {
+    type Item = Transition;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.initialized {
+            self.initialized = true;
+            let initial = self.transition_system.get_initial_state();
+            self.visited.push(initial.clone());
+            self.frontier.push_back(initial);
+        }
+
+        // Expand states until some transitions are pending or all states are expanded
+        while self.pending.is_empty() {
+            let state = self.frontier.pop_front()?;
+            for transition in self.transition_system.transitions(&state, &self.actions) {
+                if !self.visited.contains(&transition.target) {
+                    self.visited.push(transition.target.clone());
+                    self.frontier.push_back(transition.target.clone());
+                }
+                self.pending.push_back(transition);
+            }
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        transition_system::transition::Transition,
+    };
+
+    use super::TransitionIterator;
+
+    #[test]
+    fn transition_iterator_yields_every_transition_once() {
+        let invariant = &Invariant::new_true();
+        let channel = Channel::new_output("out");
+        let channels = HashSet::from([channel.clone()]);
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let a_b = Edge::new(&a, &channel, &Guard::new_true(), &Update::empty(), &b).with_id("ab");
+        let b_a = Edge::new(&b, &channel, &Guard::new_true(), &Update::empty(), &a).with_id("ba");
+        let b_b = Edge::new_loop(&b, &channel, &Guard::new_true(), &Update::empty()).with_id("bb");
+        let edges = HashSet::from([a_b.clone(), b_a.clone(), b_b.clone()]);
+        let automaton = Automaton::new("cycle", &edges, None).ok().unwrap();
+
+        let transitions: Vec<Transition> = TransitionIterator::new(&channels, &automaton).collect();
+
+        assert_eq!(transitions.len(), 3);
+        for edge in [a_b, b_a, b_b] {
+            let generated = transitions
+                .iter()
+                .filter(|transition| transition.edge.as_ref() == Some(&edge))
+                .count();
+            assert_eq!(generated, 1);
+        }
+        assert_eq!(transitions[0].source.location, a);
+        assert!(transitions[0].to_string().contains("(ab: out!)"));
+    }
+}
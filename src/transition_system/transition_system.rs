@@ -1,68 +1,315 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use crate::automatom::{automaton::Automaton, channel::Channel};
+use crate::{
+    automatom::{automaton::Automaton, channel::Channel, edge::Edge, location::Location},
+    language::error::Error as LangError,
+};
 
 use super::{
-    state::State, transition_system_breadth_first_search::TransitionSystemBreadthFirstSearch,
+    reachability_index::ReachabilityIndex, state::State, transition::Transition,
+    transition_system_breadth_first_search::TransitionSystemBreadthFirstSearch,
 };
 
+/// The outcome of running a word of actions from the initial state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunResult {
+    /// The whole word was performed, ending in any of the states.
+    Feasible { states: Vec<State> },
+    /// The action at the index could not be taken from any of the states.
+    Infeasible { index: usize, states: Vec<State> },
+}
+
+impl RunResult {
+    pub fn is_feasible(&self) -> bool {
+        matches!(self, RunResult::Feasible { .. })
+    }
+}
+
 pub trait TransitionSystem {
-    fn predecessors(&self, state: &State, actions: &HashSet<Channel>) -> Vec<State>;
-    fn input_predecessors(&self, state: &State) -> Vec<State>;
-    fn output_predecessors(&self, state: &State) -> Vec<State>;
+    /// The reachable states, using only the reachability actions, from which
+    /// the state can be reached by a single step over one of the last actions.
+    fn predecessors_via(
+        &self,
+        state: &State,
+        last_actions: &HashSet<Channel>,
+        reachability_actions: &HashSet<Channel>,
+    ) -> Vec<State>;
+    /// The predecessors where both the path to the preceding state and the
+    /// last step are restricted to the actions. A state only reachable by
+    /// mixing actions outside the set has no predecessors.
+    fn predecessors(&self, state: &State, actions: &HashSet<Channel>) -> Vec<State> {
+        self.predecessors_via(state, actions, actions)
+    }
+    /// The predecessors reachable by any action that step to the state by an input.
+    fn input_predecessors(&self, state: &State) -> Vec<State> {
+        self.predecessors_via(state, self.get_inputs(), self.get_actions())
+    }
+    /// The predecessors reachable by any action that step to the state by an output.
+    fn output_predecessors(&self, state: &State) -> Vec<State> {
+        self.predecessors_via(state, self.get_outputs(), self.get_actions())
+    }
+    /// As `input_predecessors` but answered from the index when one is given.
+    /// The index should be built over all actions to agree with the search.
+    fn input_predecessors_with(
+        &self,
+        state: &State,
+        index: Option<&ReachabilityIndex>,
+    ) -> Vec<State> {
+        match index {
+            Some(index) => index.predecessors_via(state, self.get_inputs()),
+            None => self.input_predecessors(state),
+        }
+    }
+    /// As `output_predecessors` but answered from the index when one is given.
+    fn output_predecessors_with(
+        &self,
+        state: &State,
+        index: Option<&ReachabilityIndex>,
+    ) -> Vec<State> {
+        match index {
+            Some(index) => index.predecessors_via(state, self.get_outputs()),
+            None => self.output_predecessors(state),
+        }
+    }
     fn successors(&self, state: &State, actions: &HashSet<Channel>) -> Vec<State>;
+    /// As `successors`, but reports the error of a guard or update failing to
+    /// evaluate in the state rather than assuming it cannot.
+    fn try_successors(
+        &self,
+        state: &State,
+        actions: &HashSet<Channel>,
+    ) -> Result<Vec<State>, LangError> {
+        Ok(self.successors(state, actions))
+    }
+    fn transitions(&self, state: &State, actions: &HashSet<Channel>) -> Vec<Transition> {
+        let mut result = Vec::new();
+        for action in actions {
+            let single = HashSet::from([action.clone()]);
+            for target in self.successors(state, &single) {
+                result.push(Transition::new(state, action, &target));
+            }
+        }
+        result
+    }
+    /// The states reached by letting time pass in the state without taking an
+    /// action. Untimed systems never delay.
+    fn delay_successors(&self, _state: &State) -> Vec<State> {
+        Vec::new()
+    }
+    /// The transitions by the actions followed by a delay transition for every
+    /// delay successor, such that walking the state space by them lets time
+    /// pass as the searches do.
+    fn timed_transitions(&self, state: &State, actions: &HashSet<Channel>) -> Vec<Transition> {
+        let mut result = self.transitions(state, actions);
+        for target in self.delay_successors(state) {
+            result.push(Transition::new_delay(state, &target));
+        }
+        result
+    }
+    /// As `timed_transitions`, but without the transitions staying in the
+    /// state, such as idle self-loops, which a search may skip as they never
+    /// reach a new state. By default they are only dropped once taken.
+    fn progressing_transitions(
+        &self,
+        state: &State,
+        actions: &HashSet<Channel>,
+    ) -> Vec<Transition> {
+        self.timed_transitions(state, actions)
+            .into_iter()
+            .filter(|transition| transition.target != *state)
+            .collect()
+    }
+    /// As `step`, but the delay action lets time pass, such that a word of
+    /// actions may include the delay steps of a trace.
+    fn timed_step(&self, state: &State, action: &Channel) -> Vec<State> {
+        self.timed_transitions(state, &HashSet::from([action.clone()]))
+            .into_iter()
+            .filter(|transition| transition.action == *action)
+            .map(|transition| transition.target)
+            .collect()
+    }
     fn get_initial_state(&self) -> State;
     fn get_actions(&self) -> &HashSet<Channel>;
     fn get_inputs(&self) -> &HashSet<Channel>;
     fn get_outputs(&self) -> &HashSet<Channel>;
+
+    /// The successors of the state grouped by the action leading to them, where
+    /// actions without successors are left out.
+    fn successors_by_action(&self, state: &State) -> HashMap<Channel, Vec<State>> {
+        let mut result = HashMap::new();
+        for action in self.get_actions() {
+            let successors = self.step(state, action);
+            if !successors.is_empty() {
+                result.insert(action.clone(), successors);
+            }
+        }
+        result
+    }
+
+    /// The successors of the state by exactly the action.
+    fn step(&self, state: &State, action: &Channel) -> Vec<State> {
+        self.successors(state, &HashSet::from([action.clone()]))
+    }
+
+    /// Performs the word from the initial state while tracking every state the
+    /// system could be in, such that nondeterministic choices are not lost.
+    fn run(&self, word: &[Channel]) -> RunResult {
+        let mut states = vec![self.get_initial_state()];
+        for (index, action) in word.iter().enumerate() {
+            let mut next: Vec<State> = Vec::new();
+            for state in states.iter() {
+                for successor in self.step(state, action) {
+                    if !next.contains(&successor) {
+                        next.push(successor);
+                    }
+                }
+            }
+            if next.is_empty() {
+                return RunResult::Infeasible { index, states };
+            }
+            states = next;
+        }
+        RunResult::Feasible { states }
+    }
+
+    fn reachable_states(&self, actions: &HashSet<Channel>) -> Vec<State>
+    where
+        Self: Sized,
+    {
+        TransitionSystemBreadthFirstSearch::new(actions, self).collect()
+    }
+
+    /// The states reachable using only the inputs.
+    fn reachable_states_inputs(&self) -> Vec<State>
+    where
+        Self: Sized,
+    {
+        self.reachable_states(self.get_inputs())
+    }
+
+    /// The states reachable using only the outputs.
+    fn reachable_states_outputs(&self) -> Vec<State>
+    where
+        Self: Sized,
+    {
+        self.reachable_states(self.get_outputs())
+    }
+
+    fn reachable_locations(&self, actions: &HashSet<Channel>) -> HashSet<Location>
+    where
+        Self: Sized,
+    {
+        TransitionSystemBreadthFirstSearch::new(actions, self)
+            .map(|state| state.location)
+            .collect()
+    }
+
+    fn is_location_reachable(&self, location: &Location, actions: &HashSet<Channel>) -> bool
+    where
+        Self: Sized,
+    {
+        TransitionSystemBreadthFirstSearch::new(actions, self)
+            .any(|state| state.location == *location)
+    }
 }
 
 impl TransitionSystem for Automaton {
     fn get_initial_state(&self) -> State {
-        State::new(
-            &self.get_initial_location(),
-            &self.get_initial_environment().clone(),
-        )
+        State::new(self.get_initial_location(), self.get_initial_environment())
     }
 
-    fn predecessors(&self, state: &State, actions: &HashSet<Channel>) -> Vec<State> {
+    fn predecessors_via(
+        &self,
+        state: &State,
+        last_actions: &HashSet<Channel>,
+        reachability_actions: &HashSet<Channel>,
+    ) -> Vec<State> {
         let mut result = Vec::new();
-        let precedeeing_locations = self.precedeeing_locations(&state.location, &actions);
+        let precedeeing_locations = self.precedeeing_locations(&state.location, last_actions);
         let mut states_in_preceding_locations = Vec::new();
 
         // For all reachable states store the ones in the preceding locations
-        for current in TransitionSystemBreadthFirstSearch::new(&actions, self.clone()) {
+        for current in TransitionSystemBreadthFirstSearch::new(reachability_actions, self) {
             if precedeeing_locations.contains(&current.location) {
                 states_in_preceding_locations.push(current)
             }
         }
 
-        // For all the states in the preceding locations the result is the ones which can execute the conencting edge
-        let preceding_edges = self.ingoing_edges(&state.location, &actions);
+        // For all the states in the preceding locations the result is the ones which can execute the conencting edge into the state
+        let preceding_edges: Vec<&Edge> =
+            self.ingoing_edges(&state.location, last_actions).collect();
         for preceding_state in states_in_preceding_locations {
-            if preceding_state.enables_any(&preceding_edges) {
+            if preceding_edges.iter().any(|edge| {
+                edge.source == preceding_state.location
+                    && self.is_edge_enabled(edge, &preceding_state)
+                    && edge.execute(&preceding_state) == Ok(state.clone())
+            }) {
                 result.push(preceding_state);
             }
         }
-        return result;
-    }
-
-    fn input_predecessors(&self, state: &State) -> Vec<State> {
-        self.predecessors(state, self.get_inputs())
+        result
     }
 
-    fn output_predecessors(&self, state: &State) -> Vec<State> {
-        self.predecessors(state, self.get_outputs())
+    /// Panics if a guard or update fails to evaluate in the state, see `try_successors`.
+    fn successors(&self, state: &State, actions: &HashSet<Channel>) -> Vec<State> {
+        evaluated(self.try_successors(state, actions), state)
     }
 
-    fn successors(&self, state: &State, actions: &HashSet<Channel>) -> Vec<State> {
+    fn try_successors(
+        &self,
+        state: &State,
+        actions: &HashSet<Channel>,
+    ) -> Result<Vec<State>, LangError> {
         let mut result = Vec::new();
         for edge in self.outgoing_edges(&state.location, actions) {
-            if edge.enabled(&state) {
-                result.push(edge.execute(&state));
+            if self.try_is_edge_enabled(edge, state)? {
+                result.push(edge.execute(state)?);
             }
         }
-        return result;
+        Ok(result)
+    }
+
+    fn successors_by_action(&self, state: &State) -> HashMap<Channel, Vec<State>> {
+        let mut result: HashMap<Channel, Vec<State>> = HashMap::new();
+        for (edge, target) in evaluated(self.enabled_edges(state), state) {
+            result.entry(edge.action).or_default().push(target);
+        }
+        result
+    }
+
+    fn transitions(&self, state: &State, actions: &HashSet<Channel>) -> Vec<Transition> {
+        evaluated(edge_transitions(self, state, actions, false), state)
+    }
+
+    /// Self-loops without an update are left out before their guards are
+    /// evaluated, and the remaining transitions staying in the state once taken.
+    fn progressing_transitions(
+        &self,
+        state: &State,
+        actions: &HashSet<Channel>,
+    ) -> Vec<Transition> {
+        let mut result: Vec<Transition> =
+            evaluated(edge_transitions(self, state, actions, true), state)
+                .into_iter()
+                .filter(|transition| transition.target != *state)
+                .collect();
+        for target in self.delay_successors(state) {
+            result.push(Transition::new_delay(state, &target));
+        }
+        result
+    }
+
+    /// Advances all clocks by one unit of time as long as the invariant of the
+    /// location still holds afterwards.
+    fn delay_successors(&self, state: &State) -> Vec<State> {
+        state
+            .delayed(self.clock_ceiling())
+            .filter(|delayed| {
+                self.is_location_consistent(&delayed.location, &delayed.environment)
+                    .unwrap_or(false)
+            })
+            .into_iter()
+            .collect()
     }
 
     fn get_actions(&self) -> &HashSet<Channel> {
@@ -78,23 +325,58 @@ impl TransitionSystem for Automaton {
     }
 }
 
+/// The transitions by the enabled edges of the automaton, optionally leaving
+/// out the self-loops without an update, which cannot change the state.
+fn edge_transitions(
+    automaton: &Automaton,
+    state: &State,
+    actions: &HashSet<Channel>,
+    skip_idle_loops: bool,
+) -> Result<Vec<Transition>, LangError> {
+    let mut result = Vec::new();
+    for edge in automaton.outgoing_edges(&state.location, actions) {
+        if skip_idle_loops && edge.source == edge.target && edge.update.node.is_none() {
+            continue;
+        }
+        if automaton.try_is_edge_enabled(edge, state)? {
+            let target = edge.execute(state)?;
+            result.push(Transition::new_from_edge(state, edge, &target));
+        }
+    }
+    Ok(result)
+}
+
+/// The edges of the automaton evaluated in the state. `Automaton::new` rejects
+/// the guards and updates which do not type check, such that they only fail to
+/// evaluate in states the automaton never reaches, e.g. ones missing a variable.
+fn evaluated<T>(result: Result<T, LangError>, state: &State) -> T {
+    result.unwrap_or_else(|error| {
+        panic!(
+            "The edges leaving {} could not be evaluated: {}",
+            state, error
+        )
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::collections::{HashMap, HashSet};
 
     use crate::{
         automatom::{
-            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
-            location::Location, update::Update,
+            automaton::Automaton, channel::Channel, edge::Edge, fixtures::DIRECTED_DIAMOND,
+            guard::Guard, invariant::Invariant, location::Location, update::Update,
+        },
+        language::{
+            environment::Environment, error::Error as LangError, statement::Statement, value::Value,
         },
-        language::{environment::Environment, statement::Statement, value::Value},
         transition_system::{
-            state::State,
+            composition::Composition, reachability_index::ReachabilityIndex, state::State,
             transition_system_breadth_first_search::TransitionSystemBreadthFirstSearch,
         },
     };
 
-    use super::TransitionSystem;
+    use super::{RunResult, TransitionSystem};
 
     #[test]
     fn transition_system_get_initial_state() {
@@ -108,9 +390,9 @@ mod tests {
 
         let initial_state = automaton.get_initial_state();
 
-        assert_eq!(initial_state.location, automaton.get_initial_location());
+        assert_eq!(&initial_state.location, automaton.get_initial_location());
         assert_eq!(
-            initial_state.environment,
+            &initial_state.environment,
             automaton.get_initial_environment()
         );
     }
@@ -144,15 +426,15 @@ mod tests {
             .ok()
             .unwrap();
 
-        let all_bfs = TransitionSystemBreadthFirstSearch::new(&channels, automaton.clone());
+        let all_bfs = TransitionSystemBreadthFirstSearch::new(&channels, &automaton);
         let all_reachable_states: Vec<State> = all_bfs.collect();
         assert_eq!(all_reachable_states.len(), 3);
 
-        let in_bfs = TransitionSystemBreadthFirstSearch::new(&in_channels, automaton.clone());
+        let in_bfs = TransitionSystemBreadthFirstSearch::new(&in_channels, &automaton);
         let in_reachable_states: Vec<State> = in_bfs.collect();
         assert_eq!(in_reachable_states.len(), 2);
 
-        let out_bfs = TransitionSystemBreadthFirstSearch::new(&out_channels, automaton.clone());
+        let out_bfs = TransitionSystemBreadthFirstSearch::new(&out_channels, &automaton);
         let out_reachable_states: Vec<State> = out_bfs.collect();
         assert_eq!(out_reachable_states.len(), 2);
     }
@@ -206,13 +488,13 @@ mod tests {
             .unwrap();
 
         let initial_state = automaton.get_initial_state();
-        assert_eq!(initial_state.location, automaton.get_initial_location());
+        assert_eq!(&initial_state.location, automaton.get_initial_location());
         assert_eq!(
-            initial_state.environment,
+            &initial_state.environment,
             automaton.get_initial_environment()
         );
 
-        let bfs = TransitionSystemBreadthFirstSearch::new(&channels, automaton.clone());
+        let bfs = TransitionSystemBreadthFirstSearch::new(&channels, &automaton);
         let all_states: Vec<State> = bfs.collect();
         assert_eq!(all_states.len(), 6);
 
@@ -238,7 +520,7 @@ mod tests {
         assert_eq!(a_out_predecessors.len(), 0);
         assert_eq!(a_in_predecessors.len(), 0);
 
-        let b_state = &State::new(&b, &automaton.get_initial_environment());
+        let b_state = &State::new(&b, automaton.get_initial_environment());
         let b_all_successors = automaton.successors(b_state, &channels);
         let b_in_successors = automaton.successors(b_state, &in_channels);
         let b_out_successors = automaton.successors(b_state, &out_channels);
@@ -256,7 +538,7 @@ mod tests {
         assert_eq!(b_in_predecessors.len(), 1);
         assert_eq!(b_out_predecessors.len(), 0);
 
-        let c_state = &State::new(&c, &automaton.get_initial_environment());
+        let c_state = &State::new(&c, automaton.get_initial_environment());
         let c_all_successors = automaton.successors(c_state, &channels);
         let c_in_successors = automaton.successors(c_state, &in_channels);
         let c_out_successors = automaton.successors(c_state, &out_channels);
@@ -274,7 +556,7 @@ mod tests {
         assert_eq!(c_in_predecessors.len(), 1);
         assert_eq!(c_out_predecessors.len(), 0);
 
-        let d_state = &State::new(&d, &automaton.get_initial_environment());
+        let d_state = &State::new(&d, automaton.get_initial_environment());
         let d_all_successors = automaton.successors(d_state, &channels);
         let d_in_successors = automaton.successors(d_state, &in_channels);
         let d_out_successors = automaton.successors(d_state, &out_channels);
@@ -292,7 +574,7 @@ mod tests {
         assert!(contains_location(&d_out_predecessors, &a));
         assert_eq!(d_out_predecessors.len(), 1);
 
-        let e_state = &State::new(&e, &automaton.get_initial_environment());
+        let e_state = &State::new(&e, automaton.get_initial_environment());
         let e_all_successors = automaton.successors(e_state, &channels);
         let e_in_successors = automaton.successors(e_state, &in_channels);
         let e_out_successors = automaton.successors(e_state, &out_channels);
@@ -310,7 +592,7 @@ mod tests {
         assert!(contains_location(&e_out_predecessors, &a));
         assert_eq!(e_out_predecessors.len(), 1);
 
-        let f_state = &State::new(&f, &automaton.get_initial_environment());
+        let f_state = &State::new(&f, automaton.get_initial_environment());
         let f_all_successors = automaton.successors(f_state, &channels);
         let f_in_successors = automaton.successors(f_state, &in_channels);
         let f_out_successors = automaton.successors(f_state, &out_channels);
@@ -325,8 +607,486 @@ mod tests {
         assert!(contains_location(&f_all_predecessors, &d));
         assert!(contains_location(&f_all_predecessors, &e));
         assert_eq!(f_all_predecessors.len(), 4);
-        // We have zero predecessors because no path exists with the actions from a to f
+        // Restricted to a single action set no path exists from a to f
         assert_eq!(f_in_predecessors.len(), 0);
         assert_eq!(f_out_predecessors.len(), 0);
+
+        let f_input_predecessors = automaton.input_predecessors(f_state);
+        let f_output_predecessors = automaton.output_predecessors(f_state);
+        let f_via_predecessors = automaton.predecessors_via(f_state, &in_channels, &channels);
+        assert!(contains_location(&f_input_predecessors, &d));
+        assert!(contains_location(&f_input_predecessors, &e));
+        assert_eq!(f_input_predecessors.len(), 2);
+        assert!(contains_location(&f_output_predecessors, &b));
+        assert!(contains_location(&f_output_predecessors, &c));
+        assert_eq!(f_output_predecessors.len(), 2);
+        assert_eq!(f_via_predecessors.len(), 2);
+        assert_eq!(automaton.input_predecessors(b_state).len(), 1);
+        assert_eq!(automaton.output_predecessors(b_state).len(), 0);
+    }
+
+    #[test]
+    fn transition_system_reachability_queries() {
+        let invariant = &Invariant::new_true();
+        let channel_in = Channel::new_input("in");
+        let channel_out = Channel::new_output("out");
+        let in_channels: HashSet<Channel> = HashSet::from([channel_in.clone()]);
+        let channels: HashSet<Channel> = HashSet::from([channel_in.clone(), channel_out.clone()]);
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let unreachable = Location::new_normal("unreachable", invariant);
+        let guard = Guard::new_true();
+        let mut environment = Environment::new_empty();
+        environment.insert("ident", &Value::new_false());
+        let update_false = Update::new(&Statement::new_simple_assignment(
+            "ident",
+            &Value::new_false(),
+        ));
+        let update_true = Update::new(&Statement::new_simple_assignment(
+            "ident",
+            &Value::new_true(),
+        ));
+        let edges = &HashSet::from([
+            Edge::new(&a, &channel_in, &guard, &update_false, &b),
+            Edge::new(&a, &channel_out, &guard, &update_true, &b),
+            Edge::new(&unreachable, &channel_in, &guard, &update_true, &a),
+        ]);
+        let automaton = Automaton::new("automaton", edges, Some(&environment))
+            .ok()
+            .unwrap();
+
+        // Two different environments in b are reachable but b is only listed once
+        assert_eq!(automaton.reachable_states(&channels).len(), 3);
+        assert_eq!(
+            automaton.reachable_locations(&channels),
+            HashSet::from([a.clone(), b.clone()])
+        );
+        assert_eq!(automaton.reachable_states(&in_channels).len(), 2);
+        assert_eq!(
+            automaton.reachable_states_inputs(),
+            automaton.reachable_states(&in_channels)
+        );
+        assert!(automaton.is_location_reachable(&b, &channels));
+        assert!(automaton.is_location_reachable(&b, &in_channels));
+        assert!(!automaton.is_location_reachable(&unreachable, &channels));
+        assert!(!automaton
+            .reachable_locations(&channels)
+            .contains(&unreachable));
+    }
+
+    #[test]
+    fn transition_system_reachable_states_by_direction() {
+        let automaton = Automaton::parse(DIRECTED_DIAMOND).ok().unwrap();
+        let names = |states: Vec<State>| -> HashSet<String> {
+            states
+                .iter()
+                .map(|state| state.location.get_name())
+                .collect()
+        };
+
+        let inputs = automaton.reachable_states_inputs();
+        let outputs = automaton.reachable_states_outputs();
+
+        assert_eq!(automaton.reachable_states(automaton.get_actions()).len(), 6);
+        assert_eq!(inputs.len(), 3);
+        assert_eq!(outputs.len(), 3);
+        assert_eq!(
+            names(inputs),
+            HashSet::from([String::from("a"), String::from("b"), String::from("c")])
+        );
+        assert_eq!(
+            names(outputs),
+            HashSet::from([String::from("a"), String::from("d"), String::from("e")])
+        );
+    }
+
+    #[test]
+    fn transition_system_successors_by_action() {
+        let producer = Automaton::parse(
+            "
+            automaton producer
+            var ready = false
+            location a init
+            location b
+            location c
+            a -in? do ready = true-> b
+            a -in?-> c
+            a -out! when ready-> c
+            b -out!-> a
+            c -done!-> c
+            ",
+        )
+        .ok()
+        .unwrap();
+        let consumer = Automaton::parse("automaton consumer\nlocation x init\nx -out?-> x")
+            .ok()
+            .unwrap();
+        let composition = Composition::new(&producer, &consumer).ok().unwrap();
+        fn per_action<TS: TransitionSystem>(
+            ts: &TS,
+            state: &State,
+        ) -> HashMap<Channel, Vec<State>> {
+            ts.get_actions()
+                .iter()
+                .map(|action| (action.clone(), ts.step(state, action)))
+                .filter(|(_, successors)| !successors.is_empty())
+                .collect()
+        }
+
+        for state in producer.reachable_states(producer.get_actions()) {
+            assert_eq!(
+                producer.successors_by_action(&state),
+                per_action(&producer, &state)
+            );
+        }
+        for state in composition.reachable_states(composition.get_actions()) {
+            assert_eq!(
+                composition.successors_by_action(&state),
+                per_action(&composition, &state)
+            );
+        }
+        let initial = producer.successors_by_action(&producer.get_initial_state());
+        assert_eq!(initial.len(), 1);
+        assert_eq!(initial[&Channel::new_input("in")].len(), 2);
+    }
+
+    #[test]
+    fn transition_system_step_and_run() {
+        let invariant = &Invariant::new_true();
+        let channel_in = Channel::new_input("in");
+        let channel_out = Channel::new_output("out");
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let d = Location::new_normal("d", invariant);
+        let guard = Guard::new_true();
+        let update = Update::new_pure();
+        let edges = &HashSet::from([
+            Edge::new(&a, &channel_in, &guard, &update, &b),
+            Edge::new(&a, &channel_in, &guard, &update, &c),
+            Edge::new(&b, &channel_out, &guard, &update, &d),
+            Edge::new(&c, &channel_in, &guard, &update, &d),
+        ]);
+        let automaton = Automaton::new("automaton", edges, None).ok().unwrap();
+        let environment = automaton.get_initial_environment();
+        let initial = automaton.get_initial_state();
+
+        assert_eq!(automaton.step(&initial, &channel_in).len(), 2);
+        assert!(automaton.step(&initial, &channel_out).is_empty());
+
+        let result = automaton.run(&[channel_in.clone(), channel_out.clone()]);
+        assert_eq!(
+            result,
+            RunResult::Feasible {
+                states: vec![State::new(&d, environment)]
+            }
+        );
+        assert!(result.is_feasible());
+
+        let result = automaton.run(&[channel_in.clone(), channel_out.clone(), channel_in.clone()]);
+        assert_eq!(
+            result,
+            RunResult::Infeasible {
+                index: 2,
+                states: vec![State::new(&d, environment)]
+            }
+        );
+
+        let result = automaton.run(&[channel_in]);
+        match result {
+            RunResult::Feasible { states } => {
+                assert_eq!(states.len(), 2);
+                assert!(states.contains(&State::new(&b, environment)));
+                assert!(states.contains(&State::new(&c, environment)));
+            }
+            RunResult::Infeasible { .. } => panic!("Expected the word to be feasible"),
+        }
+    }
+
+    #[test]
+    fn transition_system_delay_enables_clock_guard() {
+        let automaton = Automaton::parse(
+            "
+            var x = 0
+            location a init invariant x <= 3
+            location b
+            a -go! when x >= 2-> b
+            ",
+        )
+        .ok()
+        .unwrap();
+        let go = Channel::new_output("go");
+        let at = |time: u32| {
+            let mut environment = Environment::new_empty();
+            environment.insert("x", &Value::new_clock(time));
+            environment
+        };
+        let initial = automaton.get_initial_state();
+
+        assert!(automaton.step(&initial, &go).is_empty());
+        let delayed = automaton.delay_successors(&initial);
+        assert_eq!(delayed, vec![State::new(&initial.location, &at(1))]);
+        let enabled = automaton.delay_successors(&delayed[0]);
+        assert_eq!(automaton.step(&enabled[0], &go).len(), 1);
+        // The invariant stops time from passing beyond 3
+        let latest = State::new(&initial.location, &at(3));
+        assert!(automaton.delay_successors(&latest).is_empty());
+
+        let reachable = automaton.reachable_states(automaton.get_actions());
+        let in_b: Vec<&State> = reachable
+            .iter()
+            .filter(|state| state.location.get_name() == "b")
+            .collect();
+        // Time passes in a from 0 to 3, and in b from 2 until beyond the ceiling of 3
+        assert_eq!(reachable.len(), 7);
+        assert!(in_b.contains(&&State::new(&in_b[0].location, &at(2))));
+        assert!(in_b.contains(&&State::new(&in_b[0].location, &at(4))));
+    }
+
+    #[test]
+    fn transition_system_clock_reset_sets_clock_to_zero() {
+        let automaton = Automaton::parse(
+            "
+            var x = 0
+            location a init
+            location b
+            a -go! when x >= 2 do x := 0-> b
+            ",
+        )
+        .ok()
+        .unwrap();
+        let go = Channel::new_output("go");
+        let mut state = automaton.get_initial_state();
+        for _ in 0..2 {
+            state = automaton.delay_successors(&state).remove(0);
+        }
+        assert_eq!(state.environment.get_value("x"), Some(&Value::new_clock(2)));
+
+        let successors = automaton.step(&state, &go);
+
+        assert_eq!(successors.len(), 1);
+        assert_eq!(successors[0].location.get_name(), "b");
+        assert_eq!(
+            successors[0].environment.get_value("x"),
+            Some(&Value::new_clock(0))
+        );
+    }
+
+    #[test]
+    fn transition_system_predecessors_do_not_clone_the_system() {
+        use std::{cell::Cell, rc::Rc};
+
+        // Counts its clones, such that a search owning a copy would be noticed
+        struct CloneCounting {
+            inner: Automaton,
+            clones: Rc<Cell<usize>>,
+        }
+
+        impl Clone for CloneCounting {
+            fn clone(&self) -> Self {
+                self.clones.set(self.clones.get() + 1);
+                CloneCounting {
+                    inner: self.inner.clone(),
+                    clones: self.clones.clone(),
+                }
+            }
+        }
+
+        impl TransitionSystem for CloneCounting {
+            fn predecessors_via(
+                &self,
+                state: &State,
+                last_actions: &HashSet<Channel>,
+                reachability_actions: &HashSet<Channel>,
+            ) -> Vec<State> {
+                self.inner
+                    .predecessors_via(state, last_actions, reachability_actions)
+            }
+
+            fn successors(&self, state: &State, actions: &HashSet<Channel>) -> Vec<State> {
+                self.inner.successors(state, actions)
+            }
+
+            fn get_initial_state(&self) -> State {
+                self.inner.get_initial_state()
+            }
+
+            fn get_actions(&self) -> &HashSet<Channel> {
+                self.inner.get_actions()
+            }
+
+            fn get_inputs(&self) -> &HashSet<Channel> {
+                self.inner.get_inputs()
+            }
+
+            fn get_outputs(&self) -> &HashSet<Channel> {
+                self.inner.get_outputs()
+            }
+        }
+
+        let automaton = Automaton::parse(
+            "
+            location a init
+            location b
+            location c
+            a -in?-> b
+            b -out!-> c
+            c -in?-> a
+            a -out!-> c
+            ",
+        )
+        .ok()
+        .unwrap();
+        let clones = Rc::new(Cell::new(0));
+        let counting = CloneCounting {
+            inner: automaton.clone(),
+            clones: clones.clone(),
+        };
+
+        // The states are found by the breadth first search over the wrapper
+        let states = counting.reachable_states(counting.get_actions());
+        let expected_states: HashSet<State> = automaton
+            .reachable_states(automaton.get_actions())
+            .into_iter()
+            .collect();
+        assert_eq!(
+            states.iter().cloned().collect::<HashSet<_>>(),
+            expected_states
+        );
+        for state in states {
+            let expected: HashSet<State> = automaton
+                .predecessors(&state, automaton.get_actions())
+                .into_iter()
+                .collect();
+            let inputs: HashSet<State> = automaton.input_predecessors(&state).into_iter().collect();
+
+            let actual: HashSet<State> = counting
+                .predecessors(&state, counting.get_actions())
+                .into_iter()
+                .collect();
+            let actual_inputs: HashSet<State> =
+                counting.input_predecessors(&state).into_iter().collect();
+
+            assert_eq!(actual, expected);
+            assert_eq!(actual_inputs, inputs);
+        }
+        // The searches behind the queries only borrowed the system
+        assert_eq!(clones.get(), 0);
+    }
+
+    #[test]
+    fn transition_system_try_successors_reports_evaluation_errors() {
+        let guarded =
+            Automaton::parse("var x = false\nlocation a init\nlocation b\na -go! when x-> b")
+                .ok()
+                .unwrap();
+        let updating =
+            Automaton::parse("var x = false\nlocation a init\nlocation b\na -go! do x = true-> b")
+                .ok()
+                .unwrap();
+
+        for automaton in [guarded, updating] {
+            let initial = automaton.get_initial_state();
+            // The state lacks the variable the edge reads or assigns
+            let foreign = State::new(&initial.location, &Environment::new_empty());
+
+            assert!(automaton
+                .try_successors(&initial, automaton.get_actions())
+                .is_ok());
+            assert!(matches!(
+                automaton.try_successors(&foreign, automaton.get_actions()),
+                Err(LangError::RuntimeError { .. })
+            ));
+            assert!(matches!(
+                automaton.enabled_edges(&foreign),
+                Err(LangError::RuntimeError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn transition_system_predecessors_execute_the_ingoing_edge() {
+        let automaton = Automaton::parse(
+            "
+            var x = false
+            location a init
+            location b
+            a -o! do x = true-> a
+            a -in?-> b
+            ",
+        )
+        .ok()
+        .unwrap();
+        let b = automaton
+            .get_locations()
+            .iter()
+            .find(|location| location.get_name() == "b")
+            .unwrap()
+            .clone();
+        let state = State::new(&b, automaton.get_initial_environment());
+
+        // Only the state with x = false steps into the state by the input
+        let predecessors = automaton.input_predecessors(&state);
+        assert_eq!(predecessors.len(), 1);
+        assert_eq!(predecessors[0].location.get_name(), "a");
+        assert_eq!(
+            predecessors[0].environment.get_value("x"),
+            Some(&Value::new_false())
+        );
+        let index = ReachabilityIndex::build(&automaton, automaton.get_actions());
+        assert_eq!(
+            automaton.input_predecessors_with(&state, Some(&index)),
+            predecessors
+        );
+    }
+
+    #[test]
+    #[ignore = "timing test, run with --ignored in release mode"]
+    fn transition_system_successors_do_not_clone_the_automaton() {
+        use std::time::Instant;
+
+        // A cycle of two hundred locations over a handful of variables
+        let mut model = String::from("var p = false\nvar q = true\nvar r = false\n");
+        model.push_str("location l0 init\n");
+        for i in 1..200 {
+            model.push_str(&format!("location l{i}\n"));
+        }
+        for i in 0..200 {
+            model.push_str(&format!("l{i} -go! do p = !p-> l{}\n", (i + 1) % 200));
+        }
+        let automaton = Automaton::parse(&model).ok().unwrap();
+        let actions = automaton.get_actions();
+
+        let start = Instant::now();
+        let mut borrowed = 0;
+        for _ in 0..2000 {
+            let state = automaton.get_initial_state();
+            borrowed += automaton.successors(&state, actions).len();
+        }
+        let borrowed_time = start.elapsed();
+
+        // As before the getters were borrowed, cloning the initial data and the edges
+        let start = Instant::now();
+        let mut cloned = 0;
+        for _ in 0..2000 {
+            let state = State::new(
+                &automaton.get_initial_location_cloned(),
+                &automaton.get_initial_environment_cloned(),
+            );
+            let edges = automaton.get_edges().clone();
+            cloned += edges
+                .iter()
+                .filter(|edge| edge.source == state.location && actions.contains(&edge.action))
+                .filter(|edge| automaton.is_edge_enabled(edge, &state))
+                .filter_map(|edge| edge.execute(&state).ok())
+                .count();
+        }
+        let cloned_time = start.elapsed();
+
+        assert_eq!(borrowed, cloned);
+        assert!(
+            borrowed_time * 2 < cloned_time,
+            "borrowed {borrowed_time:?} vs cloned {cloned_time:?}"
+        );
     }
 }
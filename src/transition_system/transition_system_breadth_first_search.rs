@@ -1,51 +1,663 @@
-use std::collections::{HashSet, VecDeque};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ops::ControlFlow,
+};
 
-use crate::automatom::channel::Channel;
+use crate::{automatom::channel::Channel, diagnostics::trace_span};
 
-use super::{state::State, transition_system::TransitionSystem};
+use super::{
+    search_observer::{CancellationToken, NoObserver, SearchInterrupted, SearchObserver},
+    state::State,
+    trace::Trace,
+    transition::Transition,
+    transition_system::TransitionSystem,
+};
+
+/// The limit which caused a bounded search to stop before exhausting the
+/// reachable states, along with the number of states seen at that point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TruncationReason {
+    MaxDepth {
+        limit: usize,
+        states: usize,
+    },
+    MaxStates {
+        limit: usize,
+        states: usize,
+    },
+    /// The cancellation token of the search was cancelled.
+    SearchInterrupted(SearchInterrupted),
+}
+
+/// How a state was first reached: its BFS layer and the index of the visited
+/// state it was discovered from along with the transition taken.
+#[derive(Debug, Clone)]
+struct Discovery {
+    depth: usize,
+    via: Option<(usize, Transition)>,
+}
 
 #[derive(Clone)]
-pub struct TransitionSystemBreadthFirstSearch<TS: TransitionSystem> {
-    transition_system: TS,
+pub struct TransitionSystemBreadthFirstSearch<
+    'a,
+    TS: TransitionSystem,
+    O: SearchObserver = NoObserver,
+> {
+    transition_system: &'a TS,
     actions: HashSet<Channel>,
     visited: Vec<State>,
-    frontier: VecDeque<State>,
+    discoveries: Vec<Discovery>,
+    frontier: VecDeque<(State, Discovery)>,
+    /// The index of every visited state in `visited`.
+    indices: HashMap<State, usize>,
+    /// The abstractions of the visited states and of those in the frontier.
+    discovered: HashSet<State>,
+    initialized: bool,
+    max_depth: Option<usize>,
+    max_states: Option<usize>,
+    truncated: Option<TruncationReason>,
+    abstraction: Option<HashSet<String>>,
+    skip_self_loops: bool,
+    observer: O,
+    cancellation: Option<CancellationToken>,
 }
 
-impl<TS: TransitionSystem> TransitionSystemBreadthFirstSearch<TS> {
-    pub fn new(actions: &HashSet<Channel>, transition_system: TS) -> Self {
+impl<'a, TS: TransitionSystem> TransitionSystemBreadthFirstSearch<'a, TS, NoObserver> {
+    pub fn new(actions: &HashSet<Channel>, transition_system: &'a TS) -> Self {
+        Self::with_limits(actions, transition_system, None, None)
+    }
+
+    /// Creates a search which does not explore states deeper than `max_depth`
+    /// (the initial state has depth zero) and yields at most `max_states` states.
+    pub fn with_limits(
+        actions: &HashSet<Channel>,
+        transition_system: &'a TS,
+        max_depth: Option<usize>,
+        max_states: Option<usize>,
+    ) -> Self {
         TransitionSystemBreadthFirstSearch {
             transition_system,
             actions: actions.clone(),
             visited: Vec::new(),
+            discoveries: Vec::new(),
             frontier: VecDeque::new(),
+            indices: HashMap::new(),
+            discovered: HashSet::new(),
+            initialized: false,
+            max_depth,
+            max_states,
+            truncated: None,
+            abstraction: None,
+            skip_self_loops: false,
+            observer: NoObserver,
+            cancellation: None,
         }
     }
 }
 
-impl<TS: TransitionSystem> Iterator for TransitionSystemBreadthFirstSearch<TS> {
+impl<'a, TS: TransitionSystem, O: SearchObserver> TransitionSystemBreadthFirstSearch<'a, TS, O> {
+    /// Reports the progress of the search to the observer.
+    pub fn with_observer<P: SearchObserver>(
+        self,
+        observer: P,
+    ) -> TransitionSystemBreadthFirstSearch<'a, TS, P> {
+        TransitionSystemBreadthFirstSearch {
+            transition_system: self.transition_system,
+            actions: self.actions,
+            visited: self.visited,
+            discoveries: self.discoveries,
+            frontier: self.frontier,
+            indices: self.indices,
+            discovered: self.discovered,
+            initialized: self.initialized,
+            max_depth: self.max_depth,
+            max_states: self.max_states,
+            truncated: self.truncated,
+            abstraction: self.abstraction,
+            skip_self_loops: self.skip_self_loops,
+            observer,
+            cancellation: self.cancellation,
+        }
+    }
+
+    /// Stops the search before yielding another state once the token is cancelled.
+    pub fn with_cancellation(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
+    pub fn get_observer(&self) -> &O {
+        &self.observer
+    }
+
+    /// Treats states in the same location whose environments agree on the kept
+    /// identifiers as the same state, such that only the first is explored.
+    pub fn with_state_abstraction(mut self, keep: HashSet<String>) -> Self {
+        self.abstraction = Some(keep);
+        self
+    }
+
+    /// Whether transitions leading back to the state they leave are dropped,
+    /// see `TransitionSystem::progressing_transitions`. They never reach a new
+    /// state, so the reachable states are the same either way. Self-loops whose
+    /// update changes the environment lead to another state and are still expanded.
+    pub fn with_skip_self_loops(mut self, skip_self_loops: bool) -> Self {
+        self.skip_self_loops = skip_self_loops;
+        self
+    }
+
+    /// Reports which limit, if any, stopped the search from reaching all states.
+    pub fn was_truncated(&self) -> Option<TruncationReason> {
+        self.truncated
+    }
+
+    /// Reports whether the search stopped because it was cancelled.
+    pub fn was_interrupted(&self) -> Option<SearchInterrupted> {
+        match self.truncated {
+            Some(TruncationReason::SearchInterrupted(interrupted)) => Some(interrupted),
+            _ => None,
+        }
+    }
+
+    /// The number of transitions on the shortest path from the initial state to
+    /// the state, if it has been visited.
+    pub fn depth_of(&self, state: &State) -> Option<usize> {
+        let index = self.indices.get(state)?;
+        Some(self.discoveries[*index].depth)
+    }
+
+    /// Reconstructs a shortest trace from the initial state to the state, if it
+    /// has been visited.
+    pub fn path_to(&self, state: &State) -> Option<Trace> {
+        let mut index = *self.indices.get(state)?;
+        let mut reversed: Vec<Transition> = Vec::new();
+        while let Some((predecessor, transition)) = &self.discoveries[index].via {
+            reversed.push(transition.clone());
+            index = *predecessor;
+        }
+
+        let mut trace = Trace::new(&self.visited[index]);
+        for transition in reversed.iter().rev() {
+            trace
+                .push(transition)
+                .expect("every transition of the search leaves the state it was found from");
+        }
+        Some(trace)
+    }
+
+    fn is_discovered(&self, state: &State) -> bool {
+        self.discovered.contains(&self.abstract_state(state))
+    }
+
+    fn abstract_state(&self, state: &State) -> State {
+        match &self.abstraction {
+            Some(keep) => state.project(keep),
+            None => state.clone(),
+        }
+    }
+}
+
+impl<'a, TS: TransitionSystem, O: SearchObserver> Iterator
+    for TransitionSystemBreadthFirstSearch<'a, TS, O>
+{
     type Item = State;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // If true, then we have the first invocation to next and init it
-        if self.visited.is_empty() && self.frontier.is_empty() {
-            self.frontier
-                .push_back(self.transition_system.get_initial_state());
+        trace_span!("breadth_first_search::next");
+        // The initial state is only ever enqueued once, such that it is not
+        // re-initialized if the search is exhausted or it is rediscovered
+        if !self.initialized {
+            self.initialized = true;
+            let initial = self.transition_system.get_initial_state();
+            self.discovered.insert(self.abstract_state(&initial));
+            self.frontier.push_back((
+                initial,
+                Discovery {
+                    depth: 0,
+                    via: None,
+                },
+            ));
         }
 
-        // If true, then we have finished the search
-        if self.frontier.is_empty() {
+        if self
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            if self.truncated.is_none() {
+                self.truncated = Some(TruncationReason::SearchInterrupted(SearchInterrupted {
+                    states: self.visited.len(),
+                }));
+            }
             return None;
         }
 
-        let state = self.frontier.pop_front().unwrap();
+        if let Some(limit) = self.max_states {
+            if self.visited.len() >= limit {
+                if !self.frontier.is_empty() && self.truncated.is_none() {
+                    self.truncated = Some(TruncationReason::MaxStates {
+                        limit,
+                        states: self.visited.len(),
+                    });
+                }
+                return None;
+            }
+        }
+
+        // If true, then we have finished the search
+        let (state, discovery) = self.frontier.pop_front()?;
+        let depth = discovery.depth;
+        let index = self.visited.len();
+        self.indices.insert(state.clone(), index);
         self.visited.push(state.clone());
-        for next in self.transition_system.successors(&state, &self.actions) {
-            if !self.visited.contains(&next) && !self.frontier.contains(&next) {
-                self.frontier.push_back(next.clone());
+        self.discoveries.push(discovery);
+        self.observer.on_state_visited(&state, depth);
+        #[cfg(feature = "trace")]
+        if self
+            .visited
+            .len()
+            .is_multiple_of(crate::diagnostics::FRONTIER_EVENT_INTERVAL)
+        {
+            crate::diagnostics::trace_event!(
+                "breadth_first_search::frontier",
+                "{} states visited, {} in the frontier",
+                self.visited.len(),
+                self.frontier.len()
+            );
+        }
+
+        // Successors are expanded as transitions to remember how they were reached
+        let transitions = match self.skip_self_loops {
+            true => self
+                .transition_system
+                .progressing_transitions(&state, &self.actions),
+            false => self
+                .transition_system
+                .timed_transitions(&state, &self.actions),
+        };
+        for transition in transitions {
+            self.observer.on_transition_traversed(&transition);
+            if self.is_discovered(&transition.target) {
+                continue;
+            }
+            match self.max_depth {
+                Some(limit) if depth >= limit => {
+                    if self.truncated.is_none() {
+                        self.truncated = Some(TruncationReason::MaxDepth {
+                            limit,
+                            states: self.visited.len(),
+                        });
+                    }
+                }
+                _ => {
+                    let next = transition.target.clone();
+                    self.discovered.insert(self.abstract_state(&next));
+                    let discovery = Discovery {
+                        depth: depth + 1,
+                        via: Some((index, transition)),
+                    };
+                    self.frontier.push_back((next, discovery));
+                }
             }
         }
+        self.observer.on_frontier_size(self.frontier.len());
 
         Some(state)
     }
 }
+
+/// Calls the closure on every state reachable by the actions in breadth-first
+/// order without collecting them. The search stops as soon as the closure
+/// breaks, in which case the break is returned.
+pub fn for_each_state<TS: TransitionSystem, F: FnMut(&State) -> ControlFlow<()>>(
+    ts: &TS,
+    actions: &HashSet<Channel>,
+    mut f: F,
+) -> ControlFlow<()> {
+    for state in TransitionSystemBreadthFirstSearch::new(actions, ts) {
+        f(&state)?;
+    }
+    ControlFlow::Continue(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, ops::ControlFlow};
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        diagnostics::SearchStats,
+        language::{environment::Environment, statement::Statement, value::Value},
+        transition_system::{
+            search_observer::{CancellationToken, SearchInterrupted, SearchObserver},
+            state::State,
+            transition_system::TransitionSystem,
+        },
+    };
+
+    use super::{for_each_state, TransitionSystemBreadthFirstSearch, TruncationReason};
+
+    fn new_chain(length: usize) -> (Automaton, HashSet<Channel>) {
+        let invariant = &Invariant::new_true();
+        let channel = Channel::new_output("out");
+        let mut locations = vec![Location::new_initial("l0", invariant)];
+        for index in 1..length {
+            locations.push(Location::new_normal(&format!("l{}", index), invariant));
+        }
+        let edges: HashSet<Edge> = locations
+            .windows(2)
+            .map(|pair| {
+                Edge::new(
+                    &pair[0],
+                    &channel,
+                    &Guard::new_true(),
+                    &Update::empty(),
+                    &pair[1],
+                )
+            })
+            .collect();
+        let automaton = Automaton::new("chain", &edges, None).ok().unwrap();
+        (automaton, HashSet::from([channel]))
+    }
+
+    #[test]
+    fn breadth_first_search_chain_yields_states_in_order() {
+        let invariant = &Invariant::new_true();
+        let channel = Channel::new_output("out");
+        let channels = HashSet::from([channel.clone()]);
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let edges = HashSet::from([
+            Edge::new(&a, &channel, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&b, &channel, &Guard::new_true(), &Update::empty(), &c),
+        ]);
+        let automaton = Automaton::new("chain", &edges, None).ok().unwrap();
+
+        let states: Vec<State> =
+            TransitionSystemBreadthFirstSearch::new(&channels, &automaton).collect();
+
+        let locations: Vec<Location> = states.into_iter().map(|state| state.location).collect();
+        assert_eq!(locations, vec![a, b, c]);
+    }
+
+    #[test]
+    fn breadth_first_search_cycle_terminates() {
+        let invariant = &Invariant::new_true();
+        let channel = Channel::new_output("out");
+        let channels = HashSet::from([channel.clone()]);
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let edges = HashSet::from([
+            Edge::new(&a, &channel, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&b, &channel, &Guard::new_true(), &Update::empty(), &a),
+            Edge::new_loop(&b, &channel, &Guard::new_true(), &Update::empty()),
+        ]);
+        let automaton = Automaton::new("cycle", &edges, None).ok().unwrap();
+
+        let mut search = TransitionSystemBreadthFirstSearch::new(&channels, &automaton);
+        assert_eq!(search.next().map(|state| state.location), Some(a));
+        assert_eq!(search.next().map(|state| state.location), Some(b));
+        assert_eq!(search.next(), None);
+        assert_eq!(search.next(), None);
+    }
+
+    #[test]
+    fn breadth_first_search_borrows_transition_system() {
+        let channel = Channel::new_output("out");
+        let channels = HashSet::from([channel.clone()]);
+        let a = Location::new_initial("a", &Invariant::new_true());
+        let edges = HashSet::from([Edge::new_loop(
+            &a,
+            &channel,
+            &Guard::new_true(),
+            &Update::empty(),
+        )]);
+        let automaton = Automaton::new("borrowed", &edges, None).ok().unwrap();
+        let borrowed: &Automaton = &automaton;
+
+        let first = TransitionSystemBreadthFirstSearch::new(&channels, borrowed).count();
+        let second = TransitionSystemBreadthFirstSearch::new(&channels, borrowed).count();
+
+        // The automaton is still usable since the searches only borrowed it
+        assert_eq!(first, 1);
+        assert_eq!(second, 1);
+        assert_eq!(automaton.get_edges().len(), 1);
+    }
+
+    #[test]
+    fn breadth_first_search_skip_self_loops_keeps_reachable_states() {
+        let automaton = Automaton::parse(
+            "
+            var ready = false
+            location a init
+            location b
+            a -idle!-> a
+            a -go!-> b
+            b -idle!-> b
+            b -set! do ready = true-> b
+            ",
+        )
+        .ok()
+        .unwrap();
+        let explore = |skip_self_loops: bool| {
+            let mut search =
+                TransitionSystemBreadthFirstSearch::new(automaton.get_actions(), &automaton)
+                    .with_skip_self_loops(skip_self_loops)
+                    .with_observer(SearchStats::default());
+            let states: HashSet<State> = search.by_ref().collect();
+            (states, search.get_observer().transitions_traversed)
+        };
+
+        let (all_states, all_traversed) = explore(false);
+        let (states, traversed) = explore(true);
+
+        // The update of set reaches a new state from b, so only the idle loops
+        // and setting ready once it holds are skipped
+        assert_eq!(states, all_states);
+        assert_eq!(states.len(), 3);
+        assert_eq!(all_traversed, 6);
+        assert_eq!(traversed, 2);
+    }
+
+    #[test]
+    fn breadth_first_search_max_depth_truncates() {
+        let (automaton, channels) = new_chain(10);
+
+        let mut search =
+            TransitionSystemBreadthFirstSearch::with_limits(&channels, &automaton, Some(3), None);
+        let states: Vec<State> = search.by_ref().collect();
+
+        assert_eq!(states.len(), 4);
+        assert_eq!(
+            search.was_truncated(),
+            Some(TruncationReason::MaxDepth {
+                limit: 3,
+                states: 4
+            })
+        );
+    }
+
+    #[test]
+    fn breadth_first_search_max_states_truncates() {
+        let (automaton, channels) = new_chain(10);
+
+        let mut search =
+            TransitionSystemBreadthFirstSearch::with_limits(&channels, &automaton, None, Some(5));
+        let states: Vec<State> = search.by_ref().collect();
+
+        assert_eq!(states.len(), 5);
+        assert_eq!(
+            search.was_truncated(),
+            Some(TruncationReason::MaxStates {
+                limit: 5,
+                states: 5
+            })
+        );
+    }
+
+    #[test]
+    fn breadth_first_search_limits_not_reached() {
+        let (automaton, channels) = new_chain(4);
+
+        let mut search = TransitionSystemBreadthFirstSearch::with_limits(
+            &channels,
+            &automaton,
+            Some(3),
+            Some(4),
+        );
+        let states: Vec<State> = search.by_ref().collect();
+
+        assert_eq!(states.len(), 4);
+        assert_eq!(search.was_truncated(), None);
+    }
+
+    #[test]
+    fn breadth_first_search_depth_of_and_path_to_shortest() {
+        let invariant = &Invariant::new_true();
+        let step = Channel::new_output("step");
+        let skip = Channel::new_input("skip");
+        let channels = HashSet::from([step.clone(), skip.clone()]);
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let edges = HashSet::from([
+            Edge::new(&a, &step, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&b, &step, &Guard::new_true(), &Update::empty(), &c),
+            Edge::new(&a, &skip, &Guard::new_true(), &Update::empty(), &c),
+        ]);
+        let automaton = Automaton::new("shortcut", &edges, None).ok().unwrap();
+
+        let mut search = TransitionSystemBreadthFirstSearch::new(&channels, &automaton);
+        let states: Vec<State> = search.by_ref().collect();
+        assert_eq!(states.len(), 3);
+
+        let environment = automaton.get_initial_environment();
+        let state_a = State::new(&a, environment);
+        let state_b = State::new(&b, environment);
+        let state_c = State::new(&c, environment);
+        assert_eq!(search.depth_of(&state_a), Some(0));
+        assert_eq!(search.depth_of(&state_b), Some(1));
+        assert_eq!(search.depth_of(&state_c), Some(1));
+
+        let trace = search.path_to(&state_c).unwrap();
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace.get_initial_state(), &state_a);
+        assert_eq!(trace.transitions()[0].action, skip);
+        assert_eq!(trace.last_state(), &state_c);
+
+        let trace = search.path_to(&state_a).unwrap();
+        assert!(trace.is_empty());
+    }
+
+    #[test]
+    fn breadth_first_search_path_to_unvisited_is_none() {
+        let (automaton, channels) = new_chain(3);
+        let search = TransitionSystemBreadthFirstSearch::new(&channels, &automaton);
+        let initial = automaton.get_initial_state();
+        assert_eq!(search.depth_of(&initial), None);
+        assert_eq!(search.path_to(&initial), None);
+    }
+
+    #[test]
+    fn breadth_first_search_state_abstraction_merges_states() {
+        let invariant = &Invariant::new_true();
+        let channel_in = Channel::new_input("in");
+        let channel_out = Channel::new_output("out");
+        let channels = HashSet::from([channel_in.clone(), channel_out.clone()]);
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let mut environment = Environment::new_empty();
+        environment.insert("kept", &Value::new_false());
+        environment.insert("bookkeeping", &Value::new_false());
+        let update = Update::new(&Statement::new_simple_assignment(
+            "bookkeeping",
+            &Value::new_true(),
+        ));
+        let edges = HashSet::from([
+            Edge::new(&a, &channel_in, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&a, &channel_out, &Guard::new_true(), &update, &b),
+        ]);
+        let automaton = Automaton::new("abstraction", &edges, Some(&environment))
+            .ok()
+            .unwrap();
+        let keep = HashSet::from([String::from("kept")]);
+
+        let concrete = TransitionSystemBreadthFirstSearch::new(&channels, &automaton).count();
+        let abstracted = TransitionSystemBreadthFirstSearch::new(&channels, &automaton)
+            .with_state_abstraction(keep)
+            .count();
+
+        assert_eq!(concrete, 3);
+        assert_eq!(abstracted, 2);
+    }
+
+    #[test]
+    fn breadth_first_search_observer_and_cancellation() {
+        #[derive(Default)]
+        struct Recorder {
+            depths: Vec<usize>,
+            frontier_sizes: Vec<usize>,
+        }
+
+        impl SearchObserver for Recorder {
+            fn on_state_visited(&mut self, _state: &State, depth: usize) {
+                self.depths.push(depth);
+            }
+
+            fn on_frontier_size(&mut self, size: usize) {
+                self.frontier_sizes.push(size);
+            }
+        }
+
+        let (automaton, channels) = new_chain(5);
+        let token = CancellationToken::new();
+        let mut bfs = TransitionSystemBreadthFirstSearch::new(&channels, &automaton)
+            .with_observer(Recorder::default())
+            .with_cancellation(&token);
+
+        assert!(bfs.next().is_some());
+        assert!(bfs.next().is_some());
+        token.cancel();
+
+        assert!(bfs.next().is_none());
+        assert_eq!(
+            bfs.was_truncated(),
+            Some(TruncationReason::SearchInterrupted(SearchInterrupted {
+                states: 2
+            }))
+        );
+        assert_eq!(bfs.was_interrupted(), Some(SearchInterrupted { states: 2 }));
+        assert_eq!(bfs.get_observer().depths, vec![0, 1]);
+        assert_eq!(bfs.get_observer().frontier_sizes, vec![1, 1]);
+    }
+
+    #[test]
+    fn breadth_first_search_for_each_state_breaks_early() {
+        let (automaton, channels) = new_chain(6);
+        let mut visited: Vec<State> = Vec::new();
+
+        let stopped = for_each_state(&automaton, &channels, |state| {
+            visited.push(state.clone());
+            if visited.len() == 3 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        let mut count = 0;
+        let exhausted = for_each_state(&automaton, &channels, |_| {
+            count += 1;
+            ControlFlow::Continue(())
+        });
+
+        assert_eq!(stopped, ControlFlow::Break(()));
+        assert_eq!(visited.len(), 3);
+        assert_eq!(visited[2].location.get_name(), "l2");
+        assert_eq!(exhausted, ControlFlow::Continue(()));
+        assert_eq!(count, 6);
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::HashSet;
+
+use crate::{automatom::channel::Channel, diagnostics::trace_span};
+
+use super::{
+    search_observer::{CancellationToken, NoObserver, SearchInterrupted, SearchObserver},
+    state::State,
+    transition_system::TransitionSystem,
+};
+
+type BacktrackHook<'a> = Box<dyn FnMut(&State) + 'a>;
+
+struct Frame {
+    state: State,
+    pending: Vec<State>,
+}
+
+pub struct TransitionSystemDepthFirstSearch<
+    'a,
+    TS: TransitionSystem,
+    O: SearchObserver = NoObserver,
+> {
+    transition_system: &'a TS,
+    actions: HashSet<Channel>,
+    visited: Vec<State>,
+    stack: Vec<Frame>,
+    initialized: bool,
+    on_backtrack: Option<BacktrackHook<'a>>,
+    skip_self_loops: bool,
+    observer: O,
+    cancellation: Option<CancellationToken>,
+    interrupted: Option<SearchInterrupted>,
+}
+
+impl<'a, TS: TransitionSystem> TransitionSystemDepthFirstSearch<'a, TS, NoObserver> {
+    pub fn new(actions: &HashSet<Channel>, transition_system: &'a TS) -> Self {
+        TransitionSystemDepthFirstSearch {
+            transition_system,
+            actions: actions.clone(),
+            visited: Vec::new(),
+            stack: Vec::new(),
+            initialized: false,
+            on_backtrack: None,
+            skip_self_loops: false,
+            observer: NoObserver,
+            cancellation: None,
+            interrupted: None,
+        }
+    }
+}
+
+impl<'a, TS: TransitionSystem, O: SearchObserver> TransitionSystemDepthFirstSearch<'a, TS, O> {
+    /// Reports the progress of the search to the observer.
+    pub fn with_observer<P: SearchObserver>(
+        self,
+        observer: P,
+    ) -> TransitionSystemDepthFirstSearch<'a, TS, P> {
+        TransitionSystemDepthFirstSearch {
+            transition_system: self.transition_system,
+            actions: self.actions,
+            visited: self.visited,
+            stack: self.stack,
+            initialized: self.initialized,
+            on_backtrack: self.on_backtrack,
+            skip_self_loops: self.skip_self_loops,
+            observer,
+            cancellation: self.cancellation,
+            interrupted: self.interrupted,
+        }
+    }
+
+    /// Stops the search before yielding another state once the token is cancelled.
+    pub fn with_cancellation(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone());
+        self
+    }
+
+    pub fn get_observer(&self) -> &O {
+        &self.observer
+    }
+
+    /// Reports whether the search stopped because it was cancelled.
+    pub fn was_interrupted(&self) -> Option<SearchInterrupted> {
+        self.interrupted
+    }
+
+    /// Registers a hook which is invoked with a state once all of its
+    /// successors have been explored and the search leaves it again.
+    pub fn with_on_backtrack(mut self, on_backtrack: impl FnMut(&State) + 'a) -> Self {
+        self.on_backtrack = Some(Box::new(on_backtrack));
+        self
+    }
+
+    /// Whether transitions leading back to the state they leave are dropped,
+    /// as in `TransitionSystemBreadthFirstSearch::with_skip_self_loops`.
+    pub fn with_skip_self_loops(mut self, skip_self_loops: bool) -> Self {
+        self.skip_self_loops = skip_self_loops;
+        self
+    }
+
+    /// The states currently on the search stack, from the initial state to the
+    /// most recently discovered one.
+    pub fn path(&self) -> Vec<&State> {
+        self.stack.iter().map(|frame| &frame.state).collect()
+    }
+
+    fn discover(&mut self, state: State) -> State {
+        let mut pending: Vec<State> = match self.skip_self_loops {
+            true => self
+                .transition_system
+                .progressing_transitions(&state, &self.actions)
+                .into_iter()
+                .map(|transition| transition.target)
+                .collect(),
+            false => {
+                let mut successors = self.transition_system.successors(&state, &self.actions);
+                successors.extend(self.transition_system.delay_successors(&state));
+                successors
+            }
+        };
+        // Reversed such that popping yields the successors in their original order
+        pending.reverse();
+        self.observer.on_state_visited(&state, self.stack.len());
+        self.visited.push(state.clone());
+        #[cfg(feature = "trace")]
+        if self
+            .visited
+            .len()
+            .is_multiple_of(crate::diagnostics::FRONTIER_EVENT_INTERVAL)
+        {
+            crate::diagnostics::trace_event!(
+                "depth_first_search::frontier",
+                "{} states visited, {} on the stack",
+                self.visited.len(),
+                self.stack.len()
+            );
+        }
+        self.stack.push(Frame {
+            state: state.clone(),
+            pending,
+        });
+        self.observer.on_frontier_size(self.stack.len());
+        state
+    }
+}
+
+impl<'a, TS: TransitionSystem, O: SearchObserver> Iterator
+    for TransitionSystemDepthFirstSearch<'a, TS, O>
+{
+    type Item = State;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        trace_span!("depth_first_search::next");
+        if self
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            if self.interrupted.is_none() {
+                self.interrupted = Some(SearchInterrupted {
+                    states: self.visited.len(),
+                });
+            }
+            return None;
+        }
+
+        if !self.initialized {
+            self.initialized = true;
+            let initial = self.transition_system.get_initial_state();
+            return Some(self.discover(initial));
+        }
+
+        while let Some(frame) = self.stack.last_mut() {
+            match frame.pending.pop() {
+                Some(next) => {
+                    if !self.visited.contains(&next) {
+                        return Some(self.discover(next));
+                    }
+                }
+                None => {
+                    let finished = self.stack.pop().unwrap();
+                    if let Some(on_backtrack) = self.on_backtrack.as_mut() {
+                        on_backtrack(&finished.state);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashSet};
+
+    use crate::{
+        automatom::{
+            automaton::Automaton, channel::Channel, edge::Edge, guard::Guard, invariant::Invariant,
+            location::Location, update::Update,
+        },
+        transition_system::{
+            search_observer::{CancellationToken, SearchInterrupted, SearchObserver},
+            state::State,
+        },
+    };
+
+    use super::TransitionSystemDepthFirstSearch;
+
+    #[test]
+    fn depth_first_search_tree_visits_branches_depth_first() {
+        let invariant = &Invariant::new_true();
+        let channel = Channel::new_output("out");
+        let channels = HashSet::from([channel.clone()]);
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let d = Location::new_normal("d", invariant);
+        let edges = HashSet::from([
+            Edge::new(&a, &channel, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&b, &channel, &Guard::new_true(), &Update::empty(), &c),
+            Edge::new(&a, &channel, &Guard::new_true(), &Update::empty(), &d),
+        ]);
+        let automaton = Automaton::new("tree", &edges, None).ok().unwrap();
+
+        let states: Vec<State> =
+            TransitionSystemDepthFirstSearch::new(&channels, &automaton).collect();
+
+        let locations: Vec<Location> = states.into_iter().map(|state| state.location).collect();
+        // The order of the branches is unspecified, but a branch is always exhausted first
+        assert!(
+            locations == vec![a.clone(), b.clone(), c.clone(), d.clone()]
+                || locations == vec![a, d, b, c]
+        );
+    }
+
+    #[test]
+    fn depth_first_search_cycle_terminates_and_backtracks() {
+        let invariant = &Invariant::new_true();
+        let channel = Channel::new_output("out");
+        let channels = HashSet::from([channel.clone()]);
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let edges = HashSet::from([
+            Edge::new(&a, &channel, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&b, &channel, &Guard::new_true(), &Update::empty(), &a),
+        ]);
+        let automaton = Automaton::new("cycle", &edges, None).ok().unwrap();
+        let backtracked: RefCell<Vec<Location>> = RefCell::new(Vec::new());
+
+        let states: Vec<State> = TransitionSystemDepthFirstSearch::new(&channels, &automaton)
+            .with_on_backtrack(|state| backtracked.borrow_mut().push(state.location.clone()))
+            .collect();
+
+        let locations: Vec<Location> = states.into_iter().map(|state| state.location).collect();
+        assert_eq!(locations, vec![a.clone(), b.clone()]);
+        assert_eq!(backtracked.into_inner(), vec![b, a]);
+    }
+
+    #[test]
+    fn depth_first_search_cancellation_stops_iteration() {
+        struct Depths(Vec<usize>);
+
+        impl SearchObserver for Depths {
+            fn on_state_visited(&mut self, _state: &State, depth: usize) {
+                self.0.push(depth);
+            }
+        }
+
+        let invariant = &Invariant::new_true();
+        let channel = Channel::new_output("out");
+        let channels = HashSet::from([channel.clone()]);
+        let a = Location::new_initial("a", invariant);
+        let b = Location::new_normal("b", invariant);
+        let c = Location::new_normal("c", invariant);
+        let edges = HashSet::from([
+            Edge::new(&a, &channel, &Guard::new_true(), &Update::empty(), &b),
+            Edge::new(&b, &channel, &Guard::new_true(), &Update::empty(), &c),
+        ]);
+        let automaton = Automaton::new("chain", &edges, None).ok().unwrap();
+        let token = CancellationToken::new();
+        let mut dfs = TransitionSystemDepthFirstSearch::new(&channels, &automaton)
+            .with_observer(Depths(Vec::new()))
+            .with_cancellation(&token);
+
+        assert!(dfs.next().is_some());
+        assert!(dfs.next().is_some());
+        token.cancel();
+
+        assert!(dfs.next().is_none());
+        assert_eq!(dfs.was_interrupted(), Some(SearchInterrupted { states: 2 }));
+        assert_eq!(dfs.get_observer().0, vec![0, 1]);
+    }
+
+    #[test]
+    fn depth_first_search_skip_self_loops_does_not_evaluate_them() {
+        let automaton = Automaton::parse(
+            "
+            var ready = false
+            location a init
+            location b
+            a -idle! when !ready-> a
+            a -go!-> b
+            b -idle! when !ready-> b
+            b -set! do ready = true-> b
+            ",
+        )
+        .ok()
+        .unwrap();
+        let explore = |skip_self_loops: bool| {
+            let automaton = automaton.clone().with_guard_cache();
+            let states: HashSet<State> =
+                TransitionSystemDepthFirstSearch::new(automaton.get_actions(), &automaton)
+                    .with_skip_self_loops(skip_self_loops)
+                    .collect();
+            (states, automaton.get_guard_cache().unwrap().evaluations())
+        };
+
+        let (all_states, all_evaluations) = explore(false);
+        let (states, evaluations) = explore(true);
+
+        // Only the guards of go and set are evaluated, set in both states of b
+        assert_eq!(states, all_states);
+        assert_eq!(states.len(), 3);
+        assert_eq!(all_evaluations, 6);
+        assert_eq!(evaluations, 3);
+    }
+}